@@ -0,0 +1,369 @@
+use std::{
+    io::Read,
+    path::{Path, PathBuf},
+};
+
+use budget_core::{
+    BudgetData, BudgetEvent,
+    amount::Locale,
+    export::{
+        csv::{self, CsvOptions, DecimalFormat},
+        journal::{self, AccountMapping, JournalFormat},
+    },
+    import::{
+        self, ImportBatch, ImportEntry, RowDecision, pasted,
+        spreadsheet::{self, ColumnMapping},
+    },
+    models::cost_item::CostCycle,
+    quick_add::{self, Kind, QuickAdd},
+    report::{ReportPeriod, Summary},
+};
+use chrono::{Datelike, Local, NaiveDate};
+
+use crate::{
+    Cli, Command, CsvArgs, ExportFormat, Fields, ImportFormat, ItemKind, print, session::Session,
+    tui,
+};
+
+pub fn run(cli: Cli) -> Result<(), String> {
+    let mut session = Session::open(cli.budget)?;
+    let as_json = cli.json;
+    match cli.command {
+        Command::Add { kind, text } => add(&mut session, kind, &text.join(" "), as_json),
+        Command::List { kind } => print::list(&session.budget_data, kind, as_json),
+        Command::Edit {
+            kind,
+            id,
+            text,
+            fields,
+        } => edit(&mut session, kind, id, &text.join(" "), fields, as_json),
+        Command::Delete { kind, id } => delete(&mut session, kind, id, as_json),
+        Command::Summary { month, year } => {
+            let summary = Summary::new(&session.budget_data, period(month, year)?);
+            print::summary(&summary, as_json)
+        }
+        Command::Import {
+            file,
+            format,
+            keep_duplicates,
+            positive_is_expense,
+            csv,
+        } => {
+            let format = match format {
+                Some(format) => format,
+                None => import_format(&file)?,
+            };
+            let (entries, errors) = read_entries(
+                &file,
+                format,
+                positive_is_expense,
+                &csv,
+                session.locale,
+                &session.accounts,
+            )?;
+            let mut batch =
+                ImportBatch::new(file.display().to_string(), entries, &session.budget_data);
+            if keep_duplicates {
+                batch.select_all(true);
+                batch.set_decision_for_selected(RowDecision::Accept);
+            }
+            let skipped = batch.rows.len() - batch.accepted_count();
+            let repeated = batch.repeated_count();
+            let accepted = batch.into_accepted();
+            let imported = accepted.len();
+            if imported > 0 {
+                session.apply(BudgetEvent::CommitImport(accepted))?;
+                session.save()?;
+            }
+            print::imported(imported, skipped, repeated, &errors, as_json)
+        }
+        Command::Export { path, format, csv } => {
+            let format = format.unwrap_or_else(|| export_format(&path));
+            let files = export(&session.budget_data, &path, format, &csv, &session.accounts)?;
+            print::written(&files, as_json)
+        }
+        Command::Tui => tui::run(&mut session),
+    }
+}
+
+/// `text` read as in quick add, with tags spelled as the budget has them.
+fn parse(session: &Session, text: &str, kind: Kind) -> QuickAdd {
+    let mut line = quick_add::parse(text, kind, session.locale);
+    line.match_tags(&session.budget_data.tags());
+    line
+}
+
+fn add(session: &mut Session, kind: ItemKind, text: &str, as_json: bool) -> Result<(), String> {
+    match kind {
+        ItemKind::Expense => {
+            let id = session.budget_data.next_cost_id;
+            let item = parse(session, text, Kind::Expense).cost_item()?;
+            session.apply(BudgetEvent::AddCostItem(item))?;
+            session.save()?;
+            print::expense("Added", &session.budget_data.cost_items[&id], as_json)
+        }
+        ItemKind::Income => {
+            let item = parse(session, text, Kind::Income).income_item()?;
+            session.apply(BudgetEvent::AddIncomeItem(item))?;
+            session.save()?;
+            let added = session.budget_data.income_items.last();
+            print::income(
+                "Added",
+                added.ok_or("the income item was not added")?,
+                as_json,
+            )
+        }
+    }
+}
+
+/// Replace the item with `text` parsed as in quick add, if given, then set
+/// the single fields.
+fn edit(
+    session: &mut Session,
+    kind: ItemKind,
+    id: u64,
+    text: &str,
+    fields: Fields,
+    as_json: bool,
+) -> Result<(), String> {
+    if text.trim().is_empty()
+        && fields.name.is_none()
+        && fields.amount.is_none()
+        && fields.cycle.is_none()
+        && fields.category.is_none()
+    {
+        return Err("nothing to change; give quick add text or a field to set".to_string());
+    }
+    let cycle = fields.cycle.as_deref().map(cycle_named).transpose()?;
+
+    match kind {
+        ItemKind::Expense => {
+            let Some(current) = session.budget_data.cost_items.get(&id) else {
+                return Err(format!("no expense with id {}", id));
+            };
+            let mut item = if text.trim().is_empty() {
+                current.clone()
+            } else {
+                parse(session, text, Kind::Expense).cost_item()?
+            };
+            item.id = id;
+            if let Some(name) = fields.name {
+                item.what = name;
+            }
+            if let Some(amount) = fields.amount {
+                item.cost = amount;
+            }
+            if let Some(cycle) = cycle {
+                item.cost_cycle = cycle;
+            }
+            if let Some(name) = &fields.category {
+                item.cost_category = quick_add::expense_category(name)
+                    .ok_or_else(|| format!("unknown expense category \"{}\"", name))?;
+            }
+            session.apply(BudgetEvent::UpdateCostItem {
+                id,
+                item: item.clone(),
+            })?;
+            session.save()?;
+            print::expense("Updated", &item, as_json)
+        }
+        ItemKind::Income => {
+            let Some(current) = session.budget_data.income_items.iter().find(|i| i.id == id) else {
+                return Err(format!("no income item with id {}", id));
+            };
+            let mut item = if text.trim().is_empty() {
+                current.clone()
+            } else {
+                parse(session, text, Kind::Income).income_item()?
+            };
+            item.id = id;
+            if let Some(name) = fields.name {
+                item.source = name;
+            }
+            if let Some(amount) = fields.amount {
+                item.amount = amount;
+            }
+            if let Some(cycle) = cycle {
+                item.income_cycle = cycle;
+            }
+            if let Some(name) = &fields.category {
+                item.category = quick_add::income_category(name)
+                    .ok_or_else(|| format!("unknown income category \"{}\"", name))?;
+            }
+            session.apply(BudgetEvent::UpdateIncomeItem {
+                id,
+                item: item.clone(),
+            })?;
+            session.save()?;
+            print::income("Updated", &item, as_json)
+        }
+    }
+}
+
+fn delete(session: &mut Session, kind: ItemKind, id: u64, as_json: bool) -> Result<(), String> {
+    match kind {
+        ItemKind::Expense => {
+            let Some(item) = session.budget_data.cost_items.get(&id).cloned() else {
+                return Err(format!("no expense with id {}", id));
+            };
+            session.apply(BudgetEvent::DeleteCostItem(id))?;
+            session.save()?;
+            print::expense("Deleted", &item, as_json)
+        }
+        ItemKind::Income => {
+            let item = session.budget_data.income_items.iter().find(|i| i.id == id);
+            let Some(item) = item.cloned() else {
+                return Err(format!("no income item with id {}", id));
+            };
+            session.apply(BudgetEvent::DeleteIncomeItem(id))?;
+            session.save()?;
+            print::income("Deleted", &item, as_json)
+        }
+    }
+}
+
+fn cycle_named(name: &str) -> Result<CostCycle, String> {
+    CostCycle::from_name(name).ok_or_else(|| format!("unknown cycle \"{}\"", name))
+}
+
+/// The year, the month given as YYYY-MM, or else this month.
+fn period(month: Option<String>, year: Option<i32>) -> Result<ReportPeriod, String> {
+    if let Some(year) = year {
+        return Ok(ReportPeriod::Year(year));
+    }
+    let date = match month {
+        Some(month) => NaiveDate::parse_from_str(&format!("{}-01", month), "%Y-%m-%d")
+            .map_err(|_| format!("\"{}\" is not a month like 2025-03", month))?,
+        None => Local::now().date_naive(),
+    };
+    Ok(ReportPeriod::Month {
+        year: date.year(),
+        month: date.month(),
+    })
+}
+
+fn import_format(file: &Path) -> Result<ImportFormat, String> {
+    if file == Path::new("-") {
+        return Ok(ImportFormat::Statement);
+    }
+    let extension = file
+        .extension()
+        .map(|ext| ext.to_string_lossy().to_lowercase());
+    match extension.as_deref() {
+        Some("csv") => Ok(ImportFormat::Csv),
+        Some("xlsx" | "xlsm" | "xls" | "ods") => Ok(ImportFormat::Spreadsheet),
+        Some("ledger" | "journal" | "hledger" | "beancount") => Ok(ImportFormat::Journal),
+        Some("txt" | "tsv") => Ok(ImportFormat::Statement),
+        _ => Err(format!(
+            "can't tell the format of {}; pass --format",
+            file.display()
+        )),
+    }
+}
+
+/// Entries read from the file and the rows that could not be read.
+fn read_entries(
+    file: &Path,
+    format: ImportFormat,
+    positive_is_expense: bool,
+    csv: &CsvArgs,
+    locale: Locale,
+    accounts: &AccountMapping,
+) -> Result<(Vec<ImportEntry>, Vec<String>), String> {
+    match format {
+        ImportFormat::Csv => {
+            let (items, errors) =
+                import::csv::read_cost_items_file(file, &csv_options(csv)?, locale)?;
+            Ok((
+                items.into_iter().map(ImportEntry::CostItem).collect(),
+                errors,
+            ))
+        }
+        ImportFormat::Spreadsheet => {
+            let workbook = spreadsheet::open(file)?;
+            let Some(sheet) = workbook.sheets.first() else {
+                return Err(format!("{}: the workbook has no sheets", file.display()));
+            };
+            let mapping = ColumnMapping::guess(sheet, locale);
+            let (items, errors) = spreadsheet::to_cost_items(sheet, &mapping, locale);
+            Ok((
+                items.into_iter().map(ImportEntry::CostItem).collect(),
+                errors,
+            ))
+        }
+        ImportFormat::Journal => {
+            let (transactions, errors) = import::journal::parse_file(file, accounts)?;
+            Ok((
+                transactions
+                    .into_iter()
+                    .map(ImportEntry::Transaction)
+                    .collect(),
+                errors,
+            ))
+        }
+        ImportFormat::Statement => {
+            let text = if file == Path::new("-") {
+                let mut text = String::new();
+                std::io::stdin()
+                    .read_to_string(&mut text)
+                    .map_err(|e| e.to_string())?;
+                text
+            } else {
+                std::fs::read_to_string(file).map_err(|e| format!("{}: {}", file.display(), e))?
+            };
+            let (transactions, errors) = pasted::parse_rows(&text, positive_is_expense, locale);
+            Ok((
+                transactions
+                    .into_iter()
+                    .map(ImportEntry::Transaction)
+                    .collect(),
+                errors,
+            ))
+        }
+    }
+}
+
+fn export_format(path: &Path) -> ExportFormat {
+    let extension = path
+        .extension()
+        .map(|ext| ext.to_string_lossy().to_lowercase());
+    match extension.as_deref() {
+        Some("ledger") => ExportFormat::Ledger,
+        Some("journal" | "hledger") => ExportFormat::Hledger,
+        Some("beancount") => ExportFormat::Beancount,
+        _ => ExportFormat::Csv,
+    }
+}
+
+/// Write the export; returns the files written.
+fn export(
+    budget_data: &BudgetData,
+    path: &Path,
+    format: ExportFormat,
+    csv: &CsvArgs,
+    accounts: &AccountMapping,
+) -> Result<Vec<PathBuf>, String> {
+    let format = match format {
+        ExportFormat::Csv => return csv::export_all(path, budget_data, &csv_options(csv)?),
+        ExportFormat::Ledger => JournalFormat::Ledger,
+        ExportFormat::Hledger => JournalFormat::Hledger,
+        ExportFormat::Beancount => JournalFormat::Beancount,
+    };
+    journal::export_to_file(path, &budget_data.transactions, format, accounts)?;
+    Ok(vec![path.to_path_buf()])
+}
+
+fn csv_options(csv: &CsvArgs) -> Result<CsvOptions, String> {
+    let delimiter = u8::try_from(csv.delimiter)
+        .ok()
+        .filter(u8::is_ascii)
+        .ok_or("the delimiter must be a single ASCII character")?;
+    Ok(CsvOptions {
+        delimiter,
+        decimal: if csv.decimal_comma {
+            DecimalFormat::Comma
+        } else {
+            DecimalFormat::Point
+        },
+    })
+}
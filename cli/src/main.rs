@@ -0,0 +1,161 @@
+//! `budget`: the budget from the command line, for scripts and quick
+//! edits. Works on the same budget as the GUI.
+
+mod commands;
+mod print;
+mod session;
+mod tui;
+
+use std::{path::PathBuf, process::ExitCode};
+
+use clap::{Args, Parser, Subcommand, ValueEnum};
+
+#[derive(Parser)]
+#[command(name = "budget", about = "Add, list and summarize budget items")]
+struct Cli {
+    /// Budget file to use instead of the one the GUI has open
+    #[arg(long, global = true, value_name = "PATH")]
+    budget: Option<PathBuf>,
+    /// Print JSON instead of text
+    #[arg(long, global = true)]
+    json: bool,
+    #[command(subcommand)]
+    command: Command,
+}
+
+#[derive(Subcommand)]
+enum Command {
+    /// Add an item written as in quick add, e.g. `Coffee 5 \daily @dining`
+    Add {
+        kind: ItemKind,
+        #[arg(required = true, num_args = 1.., allow_hyphen_values = true)]
+        text: Vec<String>,
+    },
+    /// List items; expenses and income when no kind is given
+    List { kind: Option<Listing> },
+    /// Change an item: give quick add text to replace it, or single fields
+    Edit {
+        kind: ItemKind,
+        /// Id of the item, as listed
+        id: u64,
+        #[arg(allow_hyphen_values = true)]
+        text: Vec<String>,
+        #[command(flatten)]
+        fields: Fields,
+    },
+    /// Delete an item
+    Delete {
+        kind: ItemKind,
+        /// Id of the item, as listed
+        id: u64,
+    },
+    /// Planned and actual amounts per category for a month or a year
+    Summary {
+        /// Month as YYYY-MM; this month by default
+        #[arg(long, conflicts_with = "year")]
+        month: Option<String>,
+        /// Whole year instead of a month
+        #[arg(long)]
+        year: Option<i32>,
+    },
+    /// Import expenses or transactions from a file
+    Import {
+        /// File to read; `-` reads a pasted statement from stdin
+        file: PathBuf,
+        /// Guessed from the file extension when not given
+        #[arg(long)]
+        format: Option<ImportFormat>,
+        /// Also import rows that look like entries already in the budget
+        #[arg(long)]
+        keep_duplicates: bool,
+        /// In statements, positive amounts are money spent
+        #[arg(long)]
+        positive_is_expense: bool,
+        #[command(flatten)]
+        csv: CsvArgs,
+    },
+    /// Export the budget as CSV files or transactions as a journal
+    Export {
+        /// Folder for CSV files, or the journal file
+        path: PathBuf,
+        /// Guessed from the file extension when not given
+        #[arg(long)]
+        format: Option<ExportFormat>,
+        #[command(flatten)]
+        csv: CsvArgs,
+    },
+    /// Manage the budget in a full-screen terminal UI
+    Tui,
+}
+
+#[derive(Clone, Copy, ValueEnum)]
+enum ItemKind {
+    #[value(alias = "expenses", alias = "cost")]
+    Expense,
+    Income,
+}
+
+#[derive(Clone, Copy, ValueEnum)]
+enum Listing {
+    #[value(alias = "expense", alias = "costs")]
+    Expenses,
+    Income,
+    #[value(alias = "transaction")]
+    Transactions,
+}
+
+/// Fields set one by one by `edit`.
+#[derive(Args)]
+struct Fields {
+    #[arg(long)]
+    name: Option<String>,
+    #[arg(long, allow_negative_numbers = true)]
+    amount: Option<f32>,
+    /// daily, weekly, monthly or yearly
+    #[arg(long)]
+    cycle: Option<String>,
+    /// A category or a quick add category keyword
+    #[arg(long)]
+    category: Option<String>,
+}
+
+#[derive(Args)]
+struct CsvArgs {
+    /// Field separator of CSV files
+    #[arg(long, default_value_t = ',')]
+    delimiter: char,
+    /// CSV amounts use a decimal comma, e.g. 12,50
+    #[arg(long)]
+    decimal_comma: bool,
+}
+
+#[derive(Clone, Copy, ValueEnum)]
+enum ImportFormat {
+    /// Expenses from a CSV file
+    Csv,
+    /// Expenses from an XLSX or ODS workbook
+    Spreadsheet,
+    /// Transactions from a Ledger, hledger or Beancount journal
+    Journal,
+    /// Transactions pasted from a bank statement
+    Statement,
+}
+
+#[derive(Clone, Copy, ValueEnum)]
+enum ExportFormat {
+    Csv,
+    Ledger,
+    Hledger,
+    Beancount,
+}
+
+fn main() -> ExitCode {
+    let cli = Cli::parse();
+    match commands::run(cli) {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(err) => {
+            eprintln!("budget: {}", err);
+            ExitCode::FAILURE
+        }
+    }
+}
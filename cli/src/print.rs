@@ -0,0 +1,307 @@
+//! Output of the commands, as text tables or as JSON for scripts.
+
+use std::path::PathBuf;
+
+use budget_core::{
+    BudgetData,
+    models::{cost_item::CostItem, income_item::IncomeItem, transaction::Transaction},
+    report::{Summary, monthly_expense_total, monthly_income_total},
+};
+use serde::Serialize;
+
+use crate::Listing;
+
+pub fn json(value: &impl Serialize) -> Result<(), String> {
+    let text = serde_json::to_string_pretty(value).map_err(|e| e.to_string())?;
+    println!("{}", text);
+    Ok(())
+}
+
+#[derive(Serialize)]
+struct Items<'a> {
+    expenses: Vec<&'a CostItem>,
+    income: &'a [IncomeItem],
+}
+
+pub fn list(
+    budget_data: &BudgetData,
+    listing: Option<Listing>,
+    as_json: bool,
+) -> Result<(), String> {
+    let mut expenses: Vec<&CostItem> = budget_data.cost_items.values().collect();
+    expenses.sort_by_key(|item| item.id);
+    let income = &budget_data.income_items;
+
+    match (listing, as_json) {
+        (Some(Listing::Expenses), true) => json(&expenses),
+        (Some(Listing::Income), true) => json(income),
+        (Some(Listing::Transactions), true) => json(&budget_data.transactions),
+        (None, true) => json(&Items { expenses, income }),
+        (Some(Listing::Expenses), false) => {
+            expense_table(budget_data, &expenses);
+            Ok(())
+        }
+        (Some(Listing::Income), false) => {
+            income_table(budget_data, income);
+            Ok(())
+        }
+        (Some(Listing::Transactions), false) => {
+            transaction_table(&budget_data.transactions);
+            Ok(())
+        }
+        (None, false) => {
+            expense_table(budget_data, &expenses);
+            println!();
+            income_table(budget_data, income);
+            Ok(())
+        }
+    }
+}
+
+fn expense_table(budget_data: &BudgetData, expenses: &[&CostItem]) {
+    if expenses.is_empty() {
+        println!("No expenses");
+        return;
+    }
+    let rows = expenses
+        .iter()
+        .map(|item| {
+            vec![
+                item.id.to_string(),
+                item.what.clone(),
+                money(item.cost),
+                item.cost_cycle.label().to_string(),
+                item.cost_category.label().to_string(),
+                money(item.cost_cycle.to_monthly(item.cost)),
+            ]
+        })
+        .collect();
+    table(
+        &["ID", "Expense", "Amount", "Cycle", "Category", "Monthly"],
+        &[true, false, true, false, false, true],
+        rows,
+    );
+    println!(
+        "Monthly total: {}",
+        money(monthly_expense_total(budget_data))
+    );
+}
+
+fn income_table(budget_data: &BudgetData, income: &[IncomeItem]) {
+    if income.is_empty() {
+        println!("No income");
+        return;
+    }
+    let rows = income
+        .iter()
+        .map(|item| {
+            vec![
+                item.id.to_string(),
+                item.source.clone(),
+                money(item.amount),
+                item.income_cycle.label().to_string(),
+                item.category.label().to_string(),
+                money(item.income_cycle.to_monthly(item.amount)),
+            ]
+        })
+        .collect();
+    table(
+        &["ID", "Income", "Amount", "Cycle", "Category", "Monthly"],
+        &[true, false, true, false, false, true],
+        rows,
+    );
+    println!(
+        "Monthly total: {}",
+        money(monthly_income_total(budget_data))
+    );
+}
+
+fn transaction_table(transactions: &[Transaction]) {
+    if transactions.is_empty() {
+        println!("No transactions");
+        return;
+    }
+    let rows = transactions
+        .iter()
+        .map(|transaction| {
+            vec![
+                transaction.id.to_string(),
+                transaction.date.to_string(),
+                transaction.description.clone(),
+                money(transaction.signed_amount()),
+                transaction.category.label().to_string(),
+            ]
+        })
+        .collect();
+    table(
+        &["ID", "Date", "Description", "Amount", "Category"],
+        &[true, false, false, true, false],
+        rows,
+    );
+}
+
+pub fn expense(verb: &str, item: &CostItem, as_json: bool) -> Result<(), String> {
+    if as_json {
+        return json(item);
+    }
+    println!(
+        "{} expense {}: {} {} {} ({})",
+        verb,
+        item.id,
+        item.what,
+        money(item.cost),
+        item.cost_cycle.label().to_lowercase(),
+        item.cost_category.label()
+    );
+    Ok(())
+}
+
+pub fn income(verb: &str, item: &IncomeItem, as_json: bool) -> Result<(), String> {
+    if as_json {
+        return json(item);
+    }
+    println!(
+        "{} income {}: {} {} {} ({})",
+        verb,
+        item.id,
+        item.source,
+        money(item.amount),
+        item.income_cycle.label().to_lowercase(),
+        item.category.label()
+    );
+    Ok(())
+}
+
+pub fn summary(summary: &Summary, as_json: bool) -> Result<(), String> {
+    if as_json {
+        return json(summary);
+    }
+    println!("{}", summary.period.title());
+    let mut rows: Vec<Vec<String>> = summary
+        .categories
+        .iter()
+        .filter(|line| line.planned != 0.0 || line.actual != 0.0)
+        .map(|line| {
+            vec![
+                line.category.label().to_string(),
+                money(line.planned),
+                money(line.actual),
+            ]
+        })
+        .collect();
+    rows.push(vec![String::new(); 3]);
+    rows.push(vec![
+        "Income".to_string(),
+        money(summary.planned_income),
+        money(summary.actual_income),
+    ]);
+    rows.push(vec![
+        "Expenses".to_string(),
+        money(summary.planned_expenses),
+        money(summary.actual_expenses),
+    ]);
+    table(
+        &["Category", "Planned", "Actual"],
+        &[false, true, true],
+        rows,
+    );
+    println!(
+        "{} transactions, {:.0}% of planned income used",
+        summary.transaction_count,
+        summary.income_used() * 100.0
+    );
+    Ok(())
+}
+
+#[derive(Serialize)]
+struct Imported<'a> {
+    imported: usize,
+    skipped: usize,
+    repeated: usize,
+    errors: &'a [String],
+}
+
+/// `repeated` rows look like an earlier row of the file; they are imported.
+pub fn imported(
+    imported: usize,
+    skipped: usize,
+    repeated: usize,
+    errors: &[String],
+    as_json: bool,
+) -> Result<(), String> {
+    if as_json {
+        return json(&Imported {
+            imported,
+            skipped,
+            repeated,
+            errors,
+        });
+    }
+    for error in errors {
+        eprintln!("{}", error);
+    }
+    if skipped > 0 {
+        println!(
+            "Imported {} entries, skipped {} that look like duplicates",
+            imported, skipped
+        );
+    } else {
+        println!("Imported {} entries", imported);
+    }
+    if repeated > 0 {
+        println!(
+            "{} of them look the same as an earlier row of the file; delete them if they are \
+             not separate entries",
+            repeated
+        );
+    }
+    Ok(())
+}
+
+pub fn written(files: &[PathBuf], as_json: bool) -> Result<(), String> {
+    if as_json {
+        return json(&files);
+    }
+    for file in files {
+        println!("Wrote {}", file.display());
+    }
+    Ok(())
+}
+
+pub fn money(amount: f32) -> String {
+    if amount <= -0.005 {
+        format!("-${:.2}", -amount)
+    } else {
+        // Sums of nothing are -0.0
+        format!("${:.2}", amount + 0.0)
+    }
+}
+
+/// Columns padded to their widest cell; `right` aligns number columns.
+fn table(headers: &[&str], right: &[bool], rows: Vec<Vec<String>>) {
+    let mut widths: Vec<usize> = headers.iter().map(|h| h.chars().count()).collect();
+    for row in &rows {
+        for (width, cell) in widths.iter_mut().zip(row) {
+            *width = (*width).max(cell.chars().count());
+        }
+    }
+    let line = |cells: Vec<&str>| {
+        let padded: Vec<String> = cells
+            .iter()
+            .zip(&widths)
+            .zip(right)
+            .map(|((cell, &width), &right)| {
+                if right {
+                    format!("{:>width$}", cell)
+                } else {
+                    format!("{:<width$}", cell)
+                }
+            })
+            .collect();
+        println!("{}", padded.join("  ").trim_end());
+    };
+    line(headers.to_vec());
+    for row in &rows {
+        line(row.iter().map(String::as_str).collect());
+    }
+}
@@ -0,0 +1,210 @@
+use std::{
+    io::{BufRead, IsTerminal, Write},
+    path::{Path, PathBuf},
+    time::SystemTime,
+};
+
+use budget_core::{
+    BudgetData, BudgetEvent,
+    amount::Locale,
+    export::journal::AccountMapping,
+    settings::{AppSettings, RecentBudget},
+    storage::{
+        self, BackendKind,
+        crypto::{FileKey, Passphrase},
+        file,
+        history::{self, HistoryEntry, Recorded},
+        sync::SyncState,
+    },
+};
+
+/// Read when an encrypted budget is opened, before asking on the terminal.
+const PASSPHRASE_VAR: &str = "BUDGET_PASSPHRASE";
+
+/// The budget one command works on: the one the GUI has open unless
+/// another is named. Changes go through the same events as in the GUI, so
+/// they land in the history and reach other devices in the sync folder.
+pub struct Session {
+    kind: BackendKind,
+    path: PathBuf,
+    key: Option<FileKey>,
+    pub budget_data: BudgetData,
+    /// How amounts are typed, from the GUI's settings.
+    pub locale: Locale,
+    /// Accounts of journal exports and imports, from the GUI's settings.
+    pub accounts: AccountMapping,
+    sync: Option<SyncState>,
+    /// Author recorded in the history.
+    who: String,
+    changed: bool,
+    /// Changes from other devices picked up on opening, recorded in the
+    /// history with the first change of this session; read-only commands
+    /// write nothing.
+    unrecorded_sync: bool,
+    /// Modification time of the stored budget when it was last read or written.
+    on_disk: Option<SystemTime>,
+}
+
+impl Session {
+    pub fn open(budget: Option<PathBuf>) -> Result<Self, String> {
+        let mut settings = AppSettings::load();
+        settings.ensure_device_id()?;
+        let (kind, path) = match budget {
+            Some(path) => (kind_for(&settings, &path), path),
+            None => (settings.storage, settings.storage_path().to_path_buf()),
+        };
+
+        let (budget_data, key, passphrase) = if storage::is_encrypted(kind, &path) {
+            let passphrase = passphrase(&path)?;
+            let (budget_data, key) = file::unlock(&path, &passphrase)?;
+            (budget_data, Some(key), Some(passphrase))
+        } else {
+            (storage::load(kind, &path, None)?, None, None)
+        };
+
+        let mut session = Self {
+            kind,
+            path,
+            key,
+            budget_data,
+            locale: settings.locale,
+            accounts: settings.accounts,
+            sync: None,
+            who: history::who(&settings.device_id),
+            changed: false,
+            unrecorded_sync: false,
+            on_disk: None,
+        };
+        session.on_disk = storage::modified(&session.path);
+        if let Some(folder) = settings.sync_folders.get(&session.path) {
+            // A log of its own, so the CLI never writes to the GUI's log
+            // while the GUI is running
+            let device = format!("{}-cli", settings.device_id);
+            let before = session.budget_data.clone();
+            let (sync, _) = SyncState::start(
+                folder,
+                &device,
+                &session.path,
+                &mut session.budget_data,
+                session.key.as_ref(),
+                passphrase.as_ref(),
+            )?;
+            session.sync = Some(sync);
+            session.unrecorded_sync = session.budget_data != before;
+        }
+        Ok(session)
+    }
+
+    pub fn name(&self) -> String {
+        RecentBudget {
+            kind: self.kind,
+            path: self.path.clone(),
+            name: self.budget_data.settings.name.clone(),
+        }
+        .label()
+    }
+
+    /// Apply a data event and record it in the history and for other devices.
+    /// Returns the events that undo it.
+    pub fn apply(&mut self, event: BudgetEvent) -> Result<Vec<BudgetEvent>, String> {
+        if !history::path_for(&self.path).exists() {
+            self.history_snapshot("History started")?;
+        } else if self.unrecorded_sync {
+            self.history_snapshot("Changes from other devices")?;
+        }
+        let applied = self.budget_data.apply_tracked(&event);
+        self.changed = true;
+        self.record_history(HistoryEntry::new(
+            &self.who,
+            Recorded::Event(event.clone()),
+            applied.items,
+        ))?;
+        if let Some(sync) = &mut self.sync {
+            sync.record(&event, &self.budget_data)?;
+        }
+        Ok(applied.undo)
+    }
+
+    /// Write the budget back if anything changed.
+    pub fn save(&mut self) -> Result<(), String> {
+        if !self.changed {
+            return Ok(());
+        }
+        storage::save(self.kind, &self.path, &self.budget_data, self.key.as_ref())?;
+        self.changed = false;
+        self.on_disk = storage::modified(&self.path);
+        Ok(())
+    }
+
+    /// Pick up what the GUI, another program or other devices changed since
+    /// the budget was read. Only for sessions that save every change, as
+    /// unsaved changes would be lost. Returns whether the budget changed.
+    pub fn refresh(&mut self) -> Result<bool, String> {
+        let mut refreshed = false;
+        let modified = storage::modified(&self.path);
+        if modified.is_some() && modified != self.on_disk {
+            self.on_disk = modified;
+            self.budget_data = storage::load(self.kind, &self.path, self.key.as_ref())?;
+            self.history_snapshot("Reloaded after a change on disk")?;
+            refreshed = true;
+        }
+        if let Some(sync) = &mut self.sync
+            && sync.poll(&mut self.budget_data)?
+        {
+            self.changed = true;
+            self.history_snapshot("Changes from other devices")?;
+            self.save()?;
+            refreshed = true;
+        }
+        Ok(refreshed)
+    }
+
+    fn history_snapshot(&mut self, reason: &str) -> Result<(), String> {
+        self.unrecorded_sync = false;
+        let change = Recorded::Snapshot {
+            reason: reason.to_string(),
+            budget: self.budget_data.clone(),
+        };
+        self.record_history(HistoryEntry::new(&self.who, change, Vec::new()))
+    }
+
+    fn record_history(&self, entry: HistoryEntry) -> Result<(), String> {
+        let path = history::path_for(&self.path);
+        history::append(&path, &[entry], self.key.as_ref())
+    }
+}
+
+/// The backend a budget at `path` uses: as remembered among the recent
+/// budgets, else guessed from the file extension.
+fn kind_for(settings: &AppSettings, path: &Path) -> BackendKind {
+    if let Some(recent) = settings.recent.iter().find(|recent| recent.path == path) {
+        return recent.kind;
+    }
+    match path.extension().and_then(|ext| ext.to_str()) {
+        Some("sqlite3" | "sqlite" | "db") => BackendKind::Sqlite,
+        _ => BackendKind::File,
+    }
+}
+
+/// Passphrase of an encrypted budget, from `BUDGET_PASSPHRASE` or asked for
+/// on the terminal without echoing it. Piped input is read as a line.
+fn passphrase(path: &Path) -> Result<Passphrase, String> {
+    if let Ok(text) = std::env::var(PASSPHRASE_VAR) {
+        return Ok(Passphrase::new(text));
+    }
+    let prompt = format!("Passphrase for {}: ", path.display());
+    if std::io::stdin().is_terminal() {
+        let text = rpassword::prompt_password(prompt).map_err(|e| e.to_string())?;
+        return Ok(Passphrase::new(text));
+    }
+    eprint!("{}", prompt);
+    std::io::stderr().flush().map_err(|e| e.to_string())?;
+    let mut line = String::new();
+    std::io::stdin()
+        .lock()
+        .read_line(&mut line)
+        .map_err(|e| e.to_string())?;
+    Ok(Passphrase::new(
+        line.trim_end_matches(['\r', '\n']).to_string(),
+    ))
+}
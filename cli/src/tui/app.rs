@@ -0,0 +1,321 @@
+use budget_core::{
+    BudgetEvent,
+    amount::{self, Locale},
+    models::cost_item::{CostCycle, CostItem},
+    quick_add,
+    storage::merge::ItemKey,
+    undo::{Step, UndoStack},
+};
+use ratatui::crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+
+use crate::session::Session;
+
+/// Cost item fields that can be edited in the table, in column order.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Column {
+    Name,
+    Amount,
+    Cycle,
+    Category,
+}
+
+impl Column {
+    pub const ALL: [Column; 4] = [
+        Column::Name,
+        Column::Amount,
+        Column::Cycle,
+        Column::Category,
+    ];
+
+    /// The field as text, to start editing from.
+    fn text(&self, item: &CostItem, locale: Locale) -> String {
+        match self {
+            Column::Name => item.what.clone(),
+            Column::Amount => amount::format(item.cost, locale),
+            Column::Cycle => item.cost_cycle.label().to_string(),
+            Column::Category => item.cost_category.label().to_string(),
+        }
+    }
+
+    /// `item` with the field set from `text`.
+    fn set(&self, item: &CostItem, text: &str, locale: Locale) -> Result<CostItem, String> {
+        let text = text.trim();
+        let mut item = item.clone();
+        match self {
+            Column::Name if text.is_empty() => return Err("The name can't be empty".to_string()),
+            Column::Name => item.what = text.to_string(),
+            Column::Amount => {
+                item.cost = amount::parse(text, locale)
+                    .ok_or_else(|| format!("\"{}\" is not an amount", text))?
+            }
+            Column::Cycle => {
+                item.cost_cycle = CostCycle::from_name(text)
+                    .ok_or_else(|| format!("\"{}\" is not a cycle", text))?
+            }
+            Column::Category => {
+                item.cost_category = quick_add::expense_category(text)
+                    .ok_or_else(|| format!("\"{}\" is not a category", text))?
+            }
+        }
+        Ok(item)
+    }
+}
+
+pub enum Mode {
+    Browse,
+    /// Editing one cell of the selected cost item.
+    Edit(String),
+    QuickAdd,
+}
+
+pub struct App<'a> {
+    pub session: &'a mut Session,
+    pub mode: Mode,
+    /// Row of the selected cost item in `rows()`.
+    pub selected: usize,
+    pub column: Column,
+    pub quick_add: String,
+    /// What the quick add line adds.
+    pub target: quick_add::Kind,
+    pub status: Option<Result<String, String>>,
+    undo: UndoStack,
+    pub quit: bool,
+}
+
+impl<'a> App<'a> {
+    pub fn new(session: &'a mut Session) -> Self {
+        Self {
+            session,
+            mode: Mode::Browse,
+            selected: 0,
+            column: Column::Name,
+            quick_add: String::new(),
+            target: quick_add::Kind::Expense,
+            status: None,
+            undo: UndoStack::default(),
+            quit: false,
+        }
+    }
+
+    /// Cost items in table order.
+    pub fn rows(&self) -> Vec<&CostItem> {
+        let mut rows: Vec<&CostItem> = self.session.budget_data.cost_items.values().collect();
+        rows.sort_by_key(|item| item.id);
+        rows
+    }
+
+    fn selected_item(&self) -> Option<CostItem> {
+        self.rows().get(self.selected).map(|item| (*item).clone())
+    }
+
+    pub fn handle_key(&mut self, key: KeyEvent) {
+        if key.modifiers.contains(KeyModifiers::CONTROL) && key.code == KeyCode::Char('c') {
+            self.quit = true;
+            return;
+        }
+        self.status = None;
+        match self.mode {
+            Mode::Browse => self.browse_key(key),
+            Mode::Edit(_) => self.edit_key(key),
+            Mode::QuickAdd => self.quick_add_key(key),
+        }
+    }
+
+    fn browse_key(&mut self, key: KeyEvent) {
+        let column = Column::ALL
+            .iter()
+            .position(|c| *c == self.column)
+            .unwrap_or(0);
+        match key.code {
+            KeyCode::Char('r') if key.modifiers.contains(KeyModifiers::CONTROL) => self.redo(),
+            KeyCode::Char('q') | KeyCode::Esc => self.quit = true,
+            KeyCode::Up | KeyCode::Char('k') => self.selected = self.selected.saturating_sub(1),
+            KeyCode::Down | KeyCode::Char('j') => self.selected += 1,
+            KeyCode::Left | KeyCode::Char('h') => {
+                self.column = Column::ALL[column.saturating_sub(1)];
+            }
+            KeyCode::Right | KeyCode::Char('l') => {
+                self.column = Column::ALL[(column + 1).min(Column::ALL.len() - 1)];
+            }
+            KeyCode::Enter | KeyCode::Char('e') => {
+                if let Some(item) = self.selected_item() {
+                    self.mode = Mode::Edit(self.column.text(&item, self.session.locale));
+                }
+            }
+            KeyCode::Delete | KeyCode::Char('d') => {
+                if let Some(item) = self.selected_item() {
+                    self.apply(BudgetEvent::DeleteCostItem(item.id));
+                }
+            }
+            KeyCode::Char('a') | KeyCode::Char('/') => {
+                self.target = quick_add::Kind::Expense;
+                self.mode = Mode::QuickAdd;
+            }
+            KeyCode::Char('i') => {
+                self.target = quick_add::Kind::Income;
+                self.mode = Mode::QuickAdd;
+            }
+            KeyCode::Char('u') => self.undo(),
+            _ => {}
+        }
+        self.clamp_selection();
+    }
+
+    fn edit_key(&mut self, key: KeyEvent) {
+        let Mode::Edit(text) = &mut self.mode else {
+            return;
+        };
+        match key.code {
+            KeyCode::Esc => self.mode = Mode::Browse,
+            KeyCode::Backspace => {
+                text.pop();
+            }
+            KeyCode::Char(c) => text.push(c),
+            KeyCode::Enter => {
+                let text = text.clone();
+                let Some(item) = self.selected_item() else {
+                    self.mode = Mode::Browse;
+                    return;
+                };
+                match self.column.set(&item, &text, self.session.locale) {
+                    Ok(edited) => {
+                        self.mode = Mode::Browse;
+                        if edited != item {
+                            self.apply(BudgetEvent::UpdateCostItem {
+                                id: item.id,
+                                item: edited,
+                            });
+                        }
+                    }
+                    Err(err) => self.status = Some(Err(err)),
+                }
+            }
+            _ => {}
+        }
+    }
+
+    fn quick_add_key(&mut self, key: KeyEvent) {
+        match key.code {
+            KeyCode::Esc => self.mode = Mode::Browse,
+            KeyCode::Tab => {
+                self.target = match self.target {
+                    quick_add::Kind::Expense => quick_add::Kind::Income,
+                    quick_add::Kind::Income => quick_add::Kind::Expense,
+                }
+            }
+            KeyCode::Backspace => {
+                self.quick_add.pop();
+            }
+            KeyCode::Char(c) => self.quick_add.push(c),
+            KeyCode::Enter => {
+                let mut line = quick_add::parse(&self.quick_add, self.target, self.session.locale);
+                line.match_tags(&self.session.budget_data.tags());
+                // A line with errors stays to be fixed; they show below it
+                let Ok(event) = line.event() else {
+                    return;
+                };
+                self.quick_add.clear();
+                let expense = matches!(event, BudgetEvent::AddCostItem(_));
+                self.apply(event);
+                if expense {
+                    // New items get the highest id, so they are last
+                    self.selected = self.rows().len().saturating_sub(1);
+                }
+            }
+            _ => {}
+        }
+    }
+
+    /// Apply and save a change, keeping it for undo.
+    fn apply(&mut self, event: BudgetEvent) {
+        let (label, deleted) = self.session.budget_data.describe(&event);
+        let group = match event {
+            BudgetEvent::UpdateCostItem { id, .. } => Some(ItemKey::CostItem(id)),
+            _ => None,
+        };
+        let undo_events = match self.session.apply(event) {
+            Ok(undo_events) => undo_events,
+            Err(err) => {
+                self.status = Some(Err(err));
+                return;
+            }
+        };
+        let undo_hint = deleted.is_some();
+        self.undo.push(
+            Step::new(label.clone(), undo_events)
+                .grouped(group)
+                .deleted(deleted),
+        );
+        let label = if undo_hint {
+            format!("{}; press u to undo", label)
+        } else {
+            label
+        };
+        self.save(label);
+    }
+
+    /// Apply events in order. Returns the events that undo all of them.
+    fn apply_all(&mut self, events: Vec<BudgetEvent>) -> Result<Vec<BudgetEvent>, String> {
+        let mut inverse = Vec::new();
+        for event in events {
+            let mut undo_events = self.session.apply(event)?;
+            undo_events.append(&mut inverse);
+            inverse = undo_events;
+        }
+        Ok(inverse)
+    }
+
+    fn undo(&mut self) {
+        let Some(step) = self.undo.undo() else {
+            self.status = Some(Ok("Nothing to undo".to_string()));
+            return;
+        };
+        match self.apply_all(step.events) {
+            Ok(redo) => {
+                let message = format!("Undid {}", step.label);
+                self.undo.undone(Step::new(step.label, redo));
+                self.save(message);
+            }
+            Err(err) => self.status = Some(Err(err)),
+        }
+    }
+
+    fn redo(&mut self) {
+        let Some(step) = self.undo.redo() else {
+            self.status = Some(Ok("Nothing to redo".to_string()));
+            return;
+        };
+        match self.apply_all(step.events) {
+            Ok(undo) => {
+                let message = format!("Redid {}", step.label);
+                self.undo.redone(Step::new(step.label, undo));
+                self.save(message);
+            }
+            Err(err) => self.status = Some(Err(err)),
+        }
+    }
+
+    fn save(&mut self, message: String) {
+        self.status = Some(self.session.save().map(|_| message));
+        self.clamp_selection();
+    }
+
+    /// Pick up changes made elsewhere.
+    pub fn refresh(&mut self) {
+        match self.session.refresh() {
+            Ok(true) => {
+                self.undo.clear();
+                self.status = Some(Ok("Reloaded; the budget changed elsewhere".to_string()));
+                self.clamp_selection();
+            }
+            Ok(false) => {}
+            Err(err) => self.status = Some(Err(err)),
+        }
+    }
+
+    fn clamp_selection(&mut self) {
+        self.selected = self
+            .selected
+            .min(self.session.budget_data.cost_items.len().saturating_sub(1));
+    }
+}
@@ -0,0 +1,234 @@
+use budget_core::{
+    models::cost_item::ExpenceCategory,
+    quick_add,
+    report::{
+        CATEGORY_COLORS, ReportPeriod, Summary, monthly_category_totals, monthly_expense_total,
+        monthly_income_total,
+    },
+};
+use chrono::{Datelike, Local};
+use ratatui::{
+    Frame,
+    layout::{Constraint, Layout, Rect},
+    style::{Color, Modifier, Style, Stylize},
+    text::{Line, Span},
+    widgets::{Block, Cell, Paragraph, Row, Table, TableState},
+};
+
+use crate::{
+    print::money,
+    tui::app::{App, Column, Mode},
+};
+
+const GREEN: Color = Color::Rgb(0x2C, 0xA0, 0x2C);
+const RED: Color = Color::Rgb(0xD6, 0x27, 0x28);
+
+pub fn draw(frame: &mut Frame, app: &App) {
+    let [totals_area, body, quick_add_area, footer] = Layout::vertical([
+        Constraint::Length(4),
+        Constraint::Min(6),
+        Constraint::Length(4),
+        Constraint::Length(1),
+    ])
+    .areas(frame.area());
+    let [table_area, categories_area] =
+        Layout::horizontal([Constraint::Percentage(62), Constraint::Percentage(38)]).areas(body);
+
+    totals(frame, totals_area, app);
+    cost_table(frame, table_area, app);
+    categories(frame, categories_area, app);
+    quick_add_line(frame, quick_add_area, app);
+    status_line(frame, footer, app);
+}
+
+/// Planned monthly totals, and this month so far from the transactions.
+fn totals(frame: &mut Frame, area: Rect, app: &App) {
+    let budget_data = &app.session.budget_data;
+    let income = monthly_income_total(budget_data);
+    let expenses = monthly_expense_total(budget_data);
+    let left = income - expenses;
+    let used = if income > 0.0 {
+        format!("{:.0}%", expenses / income * 100.0)
+    } else {
+        "-".to_string()
+    };
+
+    let today = Local::now().date_naive();
+    let month = Summary::new(
+        budget_data,
+        ReportPeriod::Month {
+            year: today.year(),
+            month: today.month(),
+        },
+    );
+
+    let planned = Line::from(vec![
+        "Income ".into(),
+        Span::styled(money(income), Style::new().fg(GREEN).bold()),
+        "   Expenses ".into(),
+        Span::styled(money(expenses), Style::new().fg(RED).bold()),
+        "   Left ".into(),
+        Span::styled(
+            money(left),
+            Style::new().fg(if left < 0.0 { RED } else { GREEN }).bold(),
+        ),
+        format!("   {} of income used", used).into(),
+    ]);
+    let actual = Line::from(format!(
+        "{}: spent {} of {} planned, received {}, {} transactions",
+        month.period.title(),
+        money(month.actual_expenses),
+        money(month.planned_expenses),
+        money(month.actual_income),
+        month.transaction_count
+    ))
+    .dim();
+
+    let block = Block::bordered().title(format!(" {} · per month ", app.session.name()));
+    frame.render_widget(Paragraph::new(vec![planned, actual]).block(block), area);
+}
+
+fn cost_table(frame: &mut Frame, area: Rect, app: &App) {
+    let rows = app.rows();
+    let block = Block::bordered().title(format!(" Expenses ({}) ", rows.len()));
+    if rows.is_empty() {
+        let hint = Paragraph::new("No items yet. Press a to quick add one.")
+            .italic()
+            .block(block);
+        frame.render_widget(hint, area);
+        return;
+    }
+
+    let table_rows = rows.iter().enumerate().map(|(row, item)| {
+        let fields = [
+            Line::from(item.what.as_str()),
+            Line::from(money(item.cost)).right_aligned(),
+            Line::from(item.cost_cycle.label()),
+            Line::from(item.cost_category.label()),
+        ];
+        let mut cells = vec![Cell::from(item.id.to_string())];
+        for (column, text) in Column::ALL.iter().zip(fields) {
+            let cell = if row != app.selected || *column != app.column {
+                Cell::from(text)
+            } else if let Mode::Edit(editing) = &app.mode {
+                Cell::from(format!("{}▏", editing)).style(Style::new().fg(Color::Yellow))
+            } else {
+                Cell::from(text).style(Style::new().add_modifier(Modifier::REVERSED))
+            };
+            cells.push(cell);
+        }
+        cells.push(Cell::from(
+            Line::from(money(item.cost_cycle.to_monthly(item.cost))).right_aligned(),
+        ));
+        Row::new(cells)
+    });
+
+    let table = Table::new(
+        table_rows,
+        [
+            Constraint::Length(4),
+            Constraint::Min(12),
+            Constraint::Length(10),
+            Constraint::Length(8),
+            Constraint::Length(14),
+            Constraint::Length(10),
+        ],
+    )
+    .header(
+        Row::new([
+            Line::from("ID"),
+            Line::from("Name"),
+            Line::from("Amount").right_aligned(),
+            Line::from("Cycle"),
+            Line::from("Category"),
+            Line::from("Monthly").right_aligned(),
+        ])
+        .style(Style::new().bold()),
+    )
+    .row_highlight_style(Style::new().bg(Color::DarkGray))
+    .block(block);
+    let mut state = TableState::default().with_selected(Some(app.selected));
+    frame.render_stateful_widget(table, area, &mut state);
+}
+
+/// Monthly total of each category with any spending, as bars.
+fn categories(frame: &mut Frame, area: Rect, app: &App) {
+    let mut totals: Vec<(usize, ExpenceCategory, f32)> =
+        monthly_category_totals(&app.session.budget_data)
+            .into_iter()
+            .enumerate()
+            .filter(|(_, (_, total))| *total > 0.0)
+            .map(|(i, (category, total))| (i, category, total))
+            .collect();
+    totals.sort_by(|a, b| b.2.total_cmp(&a.2));
+
+    let label_width = 14;
+    let amount_width = 11;
+    let bar_space = (area.width as usize).saturating_sub(label_width + amount_width + 4);
+    let largest = totals.first().map(|(_, _, total)| *total).unwrap_or(0.0);
+    let lines: Vec<Line> = totals
+        .iter()
+        .map(|(i, category, total)| {
+            let (r, g, b) = CATEGORY_COLORS[i % CATEGORY_COLORS.len()];
+            let bar = (total / largest * bar_space as f32).round().max(1.0) as usize;
+            Line::from(vec![
+                format!("{:<label_width$}", category.label()).into(),
+                Span::styled("█".repeat(bar), Style::new().fg(Color::Rgb(r, g, b))),
+                " ".repeat(bar_space.saturating_sub(bar)).into(),
+                format!(" {:>amount_width$}", money(*total)).into(),
+            ])
+        })
+        .collect();
+
+    let block = Block::bordered().title(" By category · per month ");
+    frame.render_widget(Paragraph::new(lines).block(block), area);
+}
+
+fn quick_add_line(frame: &mut Frame, area: Rect, app: &App) {
+    let focused = matches!(app.mode, Mode::QuickAdd);
+    let (title, hint) = match app.target {
+        quick_add::Kind::Expense => (" Quick add expense ", "e.g. Coffee 5 \\daily @dining #work"),
+        quick_add::Kind::Income => (" Quick add income ", "e.g. Salary 3200 \\monthly @salary"),
+    };
+
+    let input = if focused {
+        Line::from(format!("> {}▏", app.quick_add))
+    } else {
+        Line::from("  Press a to add an expense, i for income").dim()
+    };
+    let preview = if !focused {
+        Line::default()
+    } else if app.quick_add.trim().is_empty() {
+        Line::from(format!("  {}", hint)).dim()
+    } else {
+        let line = quick_add::parse(&app.quick_add, app.target, app.session.locale);
+        if line.errors.is_empty() {
+            Line::from(format!("  → {}", line.preview())).fg(GREEN)
+        } else {
+            let errors: Vec<&str> = line.errors.iter().map(|e| e.message.as_str()).collect();
+            Line::from(format!("  {}", errors.join(", "))).fg(RED)
+        }
+    };
+
+    let mut block = Block::bordered().title(title);
+    if focused {
+        block = block.border_style(Style::new().fg(Color::Yellow));
+    }
+    frame.render_widget(Paragraph::new(vec![input, preview]).block(block), area);
+}
+
+fn status_line(frame: &mut Frame, area: Rect, app: &App) {
+    let line = match &app.status {
+        Some(Ok(message)) => Line::from(message.as_str()),
+        Some(Err(err)) => Line::from(err.as_str()).fg(RED),
+        None => Line::from(match app.mode {
+            Mode::Browse => {
+                "↑↓ select  ←→ column  Enter edit  d delete  a add  i add income  u undo  ^R redo  q quit"
+            }
+            Mode::Edit(_) => "Enter save  Esc cancel",
+            Mode::QuickAdd => "Enter add  Tab expense/income  Esc back",
+        })
+        .dim(),
+    };
+    frame.render_widget(Paragraph::new(line), area);
+}
@@ -0,0 +1,49 @@
+//! Full-screen terminal front-end: dashboard totals, the category
+//! breakdown, the cost items table with inline editing and the quick add
+//! line. Every change is saved straight away, so the GUI and other
+//! devices see it as they see the GUI's own.
+
+mod app;
+mod draw;
+
+use std::time::{Duration, Instant};
+
+use ratatui::{
+    DefaultTerminal,
+    crossterm::event::{self, Event, KeyEventKind},
+};
+
+use crate::{session::Session, tui::app::App};
+
+/// How often the stored budget is checked for changes made elsewhere.
+const REFRESH_INTERVAL: Duration = Duration::from_secs(2);
+
+pub fn run(session: &mut Session) -> Result<(), String> {
+    let mut app = App::new(session);
+    let mut terminal = ratatui::init();
+    let result = event_loop(&mut terminal, &mut app);
+    ratatui::restore();
+    result
+}
+
+fn event_loop(terminal: &mut DefaultTerminal, app: &mut App) -> Result<(), String> {
+    let mut last_refresh = Instant::now();
+    while !app.quit {
+        terminal
+            .draw(|frame| draw::draw(frame, app))
+            .map_err(|e| e.to_string())?;
+
+        let timeout = REFRESH_INTERVAL.saturating_sub(last_refresh.elapsed());
+        if event::poll(timeout).map_err(|e| e.to_string())?
+            && let Event::Key(key) = event::read().map_err(|e| e.to_string())?
+            && key.kind == KeyEventKind::Press
+        {
+            app.handle_key(key);
+        }
+        if last_refresh.elapsed() >= REFRESH_INTERVAL {
+            app.refresh();
+            last_refresh = Instant::now();
+        }
+    }
+    Ok(())
+}
@@ -0,0 +1,204 @@
+//! Amounts as people type them: `12,50`, `1 200`, `1.200,50 kr`, `$35` or
+//! `1.2k`. The locale decides what a lone `,` or `.` means; where both
+//! appear, the last one is the decimal mark.
+
+use serde::{Deserialize, Serialize};
+
+/// How numbers are written, picked in the settings.
+#[derive(Debug, Clone, Copy, PartialEq, Default, Serialize, Deserialize)]
+pub enum Locale {
+    /// 1,234.50
+    #[default]
+    English,
+    /// 1.234,50
+    German,
+    /// 1 234,50
+    Nordic,
+}
+
+/// Currency written before the number.
+const PREFIXES: [&str; 6] = ["$", "€", "£", "kr", "nok", "sek"];
+/// Currency written after the number, longest first so `kr.` wins over `kr`.
+const SUFFIXES: [&str; 10] = [
+    "kr.", "kr", "nok", "sek", "dkk", "eur", "usd", "€", "$", ",-",
+];
+
+impl Locale {
+    pub const ALL: [Locale; 3] = [Locale::English, Locale::German, Locale::Nordic];
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            Locale::English => "English (1,234.50)",
+            Locale::German => "German (1.234,50)",
+            Locale::Nordic => "Nordic (1 234,50)",
+        }
+    }
+
+    pub fn decimal_mark(&self) -> char {
+        match self {
+            Locale::English => '.',
+            Locale::German | Locale::Nordic => ',',
+        }
+    }
+
+    fn group_mark(&self) -> char {
+        match self {
+            Locale::English => ',',
+            Locale::German => '.',
+            Locale::Nordic => ' ',
+        }
+    }
+}
+
+/// The amount in `text`, or `None` if it isn't one. Currency symbols and
+/// `kr`/`NOK`/`SEK` around the number are ignored, and a trailing `k` or
+/// `m` means thousands or millions.
+pub fn parse(text: &str, locale: Locale) -> Option<f32> {
+    let lower = text.trim().to_lowercase();
+    let (sign, unsigned) = match lower.strip_prefix('-') {
+        Some(rest) => (-1.0, rest.trim_start()),
+        None => (1.0, lower.as_str()),
+    };
+    let mut number = unsigned;
+    if let Some(rest) = PREFIXES.iter().find_map(|p| number.strip_prefix(p)) {
+        number = rest.trim_start();
+    }
+    if let Some(rest) = SUFFIXES.iter().find_map(|s| number.strip_suffix(s)) {
+        number = rest.trim_end();
+    }
+    let (number, factor) = match number.strip_suffix('k') {
+        Some(rest) => (rest, 1_000.0),
+        None => match number.strip_suffix('m') {
+            Some(rest) => (rest, 1_000_000.0),
+            None => (number, 1.0),
+        },
+    };
+
+    let amount = sign * parse_number(number, locale)? * factor;
+    amount.is_finite().then_some(amount)
+}
+
+/// `amount` with two decimals and thousands grouped as `locale` writes them.
+pub fn format(amount: f32, locale: Locale) -> String {
+    let text = format!("{:.2}", amount.abs());
+    let (whole, cents) = text.split_once('.').unwrap_or((&text, "00"));
+
+    let mut grouped = String::new();
+    for (i, digit) in whole.chars().enumerate() {
+        if i > 0 && (whole.len() - i) % 3 == 0 {
+            grouped.push(locale.group_mark());
+        }
+        grouped.push(digit);
+    }
+    let sign = if amount < 0.0 && text != "0.00" {
+        "-"
+    } else {
+        ""
+    };
+    format!("{}{}{}{}", sign, grouped, locale.decimal_mark(), cents)
+}
+
+/// Digits with at most one decimal mark and thousands grouped by spaces,
+/// apostrophes or the mark that isn't the decimal one.
+fn parse_number(text: &str, locale: Locale) -> Option<f32> {
+    let last_dot = text.rfind('.');
+    let last_comma = text.rfind(',');
+    let decimal = match (last_dot, last_comma) {
+        (Some(dot), Some(comma)) => Some(dot.max(comma)),
+        (Some(at), None) | (None, Some(at)) => {
+            let mark = text[at..].chars().next()?;
+            let after = &text[at + 1..];
+            if text.matches(mark).count() > 1 {
+                // 1.200.000 or 1,200,000
+                None
+            } else if mark == locale.decimal_mark() {
+                Some(at)
+            } else if after.len() == 3 && after.chars().all(|c| c.is_ascii_digit()) {
+                // 1.200 in German or 1,200 in English
+                None
+            } else {
+                Some(at)
+            }
+        }
+        (None, None) => None,
+    };
+    let (whole, fraction) = match decimal {
+        Some(at) => (&text[..at], Some(&text[at + 1..])),
+        None => (text, None),
+    };
+
+    let digits = |part: &str| part.chars().all(|c| c.is_ascii_digit());
+    let groups: Vec<&str> = whole
+        .split(|c: char| c.is_whitespace() || matches!(c, '.' | ',' | '\'' | '’'))
+        .collect();
+    let grouped = groups.len() > 1
+        && (1..=3).contains(&groups[0].len())
+        && groups[1..].iter().all(|group| group.len() == 3);
+    if !groups.iter().all(|group| digits(group)) || (groups.len() > 1 && !grouped) {
+        return None;
+    }
+    let whole = groups.concat();
+    match fraction {
+        Some(fraction) if fraction.is_empty() || !digits(fraction) => None,
+        Some(fraction) => format!("{}.{}", whole, fraction).parse().ok(),
+        None if whole.is_empty() => None,
+        None => whole.parse().ok(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn decimal_comma_and_thousands() {
+        let nordic = Locale::Nordic;
+        assert_eq!(parse("12,50", nordic), Some(12.5));
+        assert_eq!(parse("1 200", nordic), Some(1200.0));
+        assert_eq!(parse("1.200,50 kr", nordic), Some(1200.5));
+        assert_eq!(parse("1 234 567,5", nordic), Some(1234567.5));
+        assert_eq!(parse("1.200", nordic), Some(1200.0));
+        assert_eq!(parse("1.5", nordic), Some(1.5));
+        assert_eq!(parse("1,234", nordic), Some(1.234));
+
+        let english = Locale::English;
+        assert_eq!(parse("12,50", english), Some(12.5));
+        assert_eq!(parse("1,234", english), Some(1234.0));
+        assert_eq!(parse("1,200,000.5", english), Some(1_200_000.5));
+        assert_eq!(parse("1'200.5", english), Some(1200.5));
+    }
+
+    #[test]
+    fn currencies_and_shorthands() {
+        let nordic = Locale::Nordic;
+        assert_eq!(parse("349 NOK", nordic), Some(349.0));
+        assert_eq!(parse("SEK 99,90", nordic), Some(99.9));
+        assert_eq!(parse("100,-", nordic), Some(100.0));
+        assert_eq!(parse("45 kr.", nordic), Some(45.0));
+        assert_eq!(parse("$35", Locale::English), Some(35.0));
+        assert_eq!(parse("€3200", Locale::German), Some(3200.0));
+        assert_eq!(parse("1.2k", Locale::English), Some(1200.0));
+        assert_eq!(parse("2,5m", nordic), Some(2_500_000.0));
+        assert_eq!(parse("-12,5", nordic), Some(-12.5));
+    }
+
+    #[test]
+    fn not_amounts() {
+        for text in [
+            "", "kr", "k", "12 50", "1,2,3", "1.2.3,4", "12a", "5.", "1..2", "coffee",
+        ] {
+            assert_eq!(parse(text, Locale::English), None, "{:?}", text);
+        }
+    }
+
+    #[test]
+    fn formats_for_the_locale() {
+        assert_eq!(format(1234567.5, Locale::English), "1,234,567.50");
+        assert_eq!(format(1200.0, Locale::German), "1.200,00");
+        assert_eq!(format(-45.5, Locale::Nordic), "-45,50");
+        assert_eq!(format(999.0, Locale::Nordic), "999,00");
+        for locale in Locale::ALL {
+            assert_eq!(parse(&format(1234.5, locale), locale), Some(1234.5));
+        }
+    }
+}
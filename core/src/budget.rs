@@ -0,0 +1,432 @@
+use std::collections::HashMap;
+
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    event::BudgetEvent,
+    import::ImportEntry,
+    models::{cost_item::CostItem, income_item::IncomeItem, transaction::Transaction},
+    settings::BudgetSettings,
+    storage::{
+        history::{Item, ItemChange},
+        merge::ItemKey,
+    },
+};
+
+/// What an applied event did: the items it changed and how to undo it.
+pub struct Applied {
+    pub items: Vec<ItemChange>,
+    /// Events that take the budget back to before the event.
+    pub undo: Vec<BudgetEvent>,
+}
+
+/// One budget, as stored.
+#[derive(Debug, Default, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct BudgetData {
+    pub cost_items: HashMap<u64, CostItem>,
+    pub income_items: Vec<IncomeItem>,
+    pub transactions: Vec<Transaction>,
+    pub next_cost_id: u64,
+    pub next_transaction_id: u64,
+    pub next_income_id: u64,
+    pub settings: BudgetSettings,
+}
+
+impl BudgetData {
+    /// Apply a data-changing event; other events leave the budget alone.
+    pub fn apply(&mut self, event: &BudgetEvent) {
+        match event.clone() {
+            BudgetEvent::AddCostItem(mut cost_item) => {
+                cost_item.id = self.next_cost_id;
+                self.cost_items.insert(self.next_cost_id, cost_item);
+                self.next_cost_id += 1;
+            }
+            BudgetEvent::UpdateCostItem { id, item } if self.cost_items.contains_key(&id) => {
+                self.cost_items.insert(id, item);
+            }
+            BudgetEvent::DeleteCostItem(id) => {
+                self.cost_items.remove(&id);
+            }
+            BudgetEvent::RestoreCostItem(cost_item)
+                if !self.cost_items.contains_key(&cost_item.id) =>
+            {
+                self.next_cost_id = self.next_cost_id.max(cost_item.id + 1);
+                self.cost_items.insert(cost_item.id, cost_item);
+            }
+            BudgetEvent::AddIncomeItem(mut income_item) => {
+                income_item.id = self.next_income_id;
+                self.next_income_id += 1;
+                self.income_items.push(income_item);
+            }
+            BudgetEvent::UpdateIncomeItem { id, item } => {
+                if let Some(income_item) = self.income_items.iter_mut().find(|i| i.id == id) {
+                    *income_item = IncomeItem { id, ..item };
+                }
+            }
+            BudgetEvent::DeleteIncomeItem(id) => {
+                self.income_items.retain(|income_item| income_item.id != id);
+            }
+            BudgetEvent::RestoreIncomeItem(income_item)
+                if !self.income_items.iter().any(|i| i.id == income_item.id) =>
+            {
+                self.next_income_id = self.next_income_id.max(income_item.id + 1);
+                let index = self.income_items.partition_point(|i| i.id < income_item.id);
+                self.income_items.insert(index, income_item);
+            }
+            BudgetEvent::DeleteTransaction(id) => {
+                self.transactions.retain(|transaction| transaction.id != id);
+            }
+            BudgetEvent::RestoreTransaction(transaction)
+                if !self.transactions.iter().any(|t| t.id == transaction.id) =>
+            {
+                self.next_transaction_id = self.next_transaction_id.max(transaction.id + 1);
+                let index = self.transactions.partition_point(|t| t.id < transaction.id);
+                self.transactions.insert(index, transaction);
+            }
+            BudgetEvent::CommitImport(entries) => {
+                for entry in entries {
+                    match entry {
+                        ImportEntry::Transaction(mut transaction) => {
+                            transaction.id = self.next_transaction_id;
+                            self.next_transaction_id += 1;
+                            self.transactions.push(transaction);
+                        }
+                        ImportEntry::CostItem(mut cost_item) => {
+                            cost_item.id = self.next_cost_id;
+                            self.cost_items.insert(self.next_cost_id, cost_item);
+                            self.next_cost_id += 1;
+                        }
+                    }
+                }
+            }
+            BudgetEvent::RenameBudget(name) => {
+                self.settings.name = name;
+            }
+            _ => {}
+        }
+    }
+
+    /// Apply an event and report what it changed.
+    pub fn apply_tracked(&mut self, event: &BudgetEvent) -> Applied {
+        let keys = self.affected_items(event);
+        let before: Vec<Option<Item>> = keys.iter().map(|key| self.item(*key)).collect();
+        let name = self.settings.name.clone();
+        self.apply(event);
+        let items: Vec<ItemChange> = keys
+            .into_iter()
+            .zip(before)
+            .map(|(key, before)| ItemChange {
+                key,
+                before,
+                after: self.item(key),
+            })
+            .collect();
+
+        let mut undo: Vec<BudgetEvent> = items
+            .iter()
+            .rev()
+            .filter(|change| change.before != change.after)
+            .filter_map(ItemChange::revert_event)
+            .collect();
+        if let BudgetEvent::RenameBudget(_) = event {
+            undo.push(BudgetEvent::RenameBudget(name));
+        }
+        Applied { items, undo }
+    }
+
+    /// Items `event` would touch, looked up before it is applied.
+    pub fn affected_items(&self, event: &BudgetEvent) -> Vec<ItemKey> {
+        match event {
+            BudgetEvent::AddCostItem(_) => vec![ItemKey::CostItem(self.next_cost_id)],
+            BudgetEvent::UpdateCostItem { id, .. } | BudgetEvent::DeleteCostItem(id) => {
+                vec![ItemKey::CostItem(*id)]
+            }
+            BudgetEvent::RestoreCostItem(item) => vec![ItemKey::CostItem(item.id)],
+            BudgetEvent::AddIncomeItem(_) => vec![ItemKey::IncomeItem(self.next_income_id)],
+            BudgetEvent::UpdateIncomeItem { id, .. } | BudgetEvent::DeleteIncomeItem(id) => {
+                vec![ItemKey::IncomeItem(*id)]
+            }
+            BudgetEvent::RestoreIncomeItem(item) => vec![ItemKey::IncomeItem(item.id)],
+            BudgetEvent::DeleteTransaction(id) => vec![ItemKey::Transaction(*id)],
+            BudgetEvent::RestoreTransaction(transaction) => {
+                vec![ItemKey::Transaction(transaction.id)]
+            }
+            BudgetEvent::CommitImport(entries) => {
+                let added = entries
+                    .iter()
+                    .filter(|entry| matches!(entry, ImportEntry::CostItem(_)))
+                    .count() as u64;
+                let moved = entries.len() as u64 - added;
+                (self.next_cost_id..self.next_cost_id + added)
+                    .map(ItemKey::CostItem)
+                    .chain(
+                        (self.next_transaction_id..self.next_transaction_id + moved)
+                            .map(ItemKey::Transaction),
+                    )
+                    .collect()
+            }
+            BudgetEvent::RenameBudget(_) => Vec::new(),
+        }
+    }
+
+    pub fn item(&self, key: ItemKey) -> Option<Item> {
+        match key {
+            ItemKey::CostItem(id) => self.cost_items.get(&id).cloned().map(Item::Cost),
+            ItemKey::IncomeItem(id) => self
+                .income_items
+                .iter()
+                .find(|income_item| income_item.id == id)
+                .cloned()
+                .map(Item::Income),
+            ItemKey::Transaction(id) => self
+                .transactions
+                .iter()
+                .find(|transaction| transaction.id == id)
+                .cloned()
+                .map(Item::Transaction),
+        }
+    }
+
+    /// Label of the undo step for `event`, and for deletes the name of
+    /// what goes.
+    pub fn describe(&self, event: &BudgetEvent) -> (String, Option<String>) {
+        let quoted = |key| self.item(key).map(|item| format!("\"{}\"", item.name()));
+        match event {
+            BudgetEvent::AddCostItem(item) => (format!("Add \"{}\"", item.what), None),
+            BudgetEvent::AddIncomeItem(item) => (format!("Add \"{}\"", item.source), None),
+            BudgetEvent::UpdateCostItem { item, .. } => (format!("Edit \"{}\"", item.what), None),
+            BudgetEvent::UpdateIncomeItem { item, .. } => {
+                (format!("Edit \"{}\"", item.source), None)
+            }
+            BudgetEvent::DeleteCostItem(_)
+            | BudgetEvent::DeleteIncomeItem(_)
+            | BudgetEvent::DeleteTransaction(_) => {
+                let name = self
+                    .affected_items(event)
+                    .into_iter()
+                    .find_map(quoted)
+                    .unwrap_or_else(|| "item".to_string());
+                (format!("Delete {}", name), Some(name))
+            }
+            BudgetEvent::RestoreCostItem(item) => (format!("Restore \"{}\"", item.what), None),
+            BudgetEvent::RestoreIncomeItem(item) => (format!("Restore \"{}\"", item.source), None),
+            BudgetEvent::RestoreTransaction(transaction) => {
+                (format!("Restore \"{}\"", transaction.description), None)
+            }
+            BudgetEvent::CommitImport(entries) => {
+                (format!("Import {} entries", entries.len()), None)
+            }
+            BudgetEvent::RenameBudget(name) => (format!("Rename the budget to \"{}\"", name), None),
+        }
+    }
+
+    /// Number the income items in order if they share ids, as budgets saved
+    /// before income items had ids do, and move the next id past them.
+    pub fn number_income_items(&mut self) {
+        let mut ids: Vec<u64> = self.income_items.iter().map(|item| item.id).collect();
+        ids.sort_unstable();
+        ids.dedup();
+        if ids.len() < self.income_items.len() {
+            for (id, item) in self.income_items.iter_mut().enumerate() {
+                item.id = id as u64;
+            }
+        }
+        let next = self.income_items.iter().map(|item| item.id + 1).max();
+        self.next_income_id = self.next_income_id.max(next.unwrap_or(0));
+    }
+
+    /// Every tag on the cost and income items, once each ignoring case, in
+    /// alphabetical order. The oldest item's spelling is kept.
+    pub fn tags(&self) -> Vec<String> {
+        let mut cost_items: Vec<&CostItem> = self.cost_items.values().collect();
+        cost_items.sort_by_key(|item| item.id);
+        let cost_tags = cost_items.into_iter().map(|item| &item.tags);
+        let income_tags = self.income_items.iter().map(|item| &item.tags);
+
+        let mut tags: Vec<String> = Vec::new();
+        for tag in cost_tags.chain(income_tags).flatten().flatten() {
+            if !tags.iter().any(|known| known.eq_ignore_ascii_case(tag)) {
+                tags.push(tag.clone());
+            }
+        }
+        tags.sort_by_key(|tag| tag.to_lowercase());
+        tags
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::fixtures::{cost_item, income_item, transaction};
+
+    /// Apply `event`, then its undo events, and check the budget is back.
+    /// Ids handed out stay used, so the next-id counters are left out.
+    fn assert_undoes(budget_data: &mut BudgetData, event: BudgetEvent) {
+        let contents = |budget_data: &BudgetData| {
+            let mut budget_data = budget_data.clone();
+            budget_data.next_cost_id = 0;
+            budget_data.next_transaction_id = 0;
+            budget_data.next_income_id = 0;
+            budget_data
+        };
+        let before = contents(budget_data);
+        let applied = budget_data.apply_tracked(&event);
+        assert_ne!(contents(budget_data), before, "{:?} changed nothing", event);
+        for undo in &applied.undo {
+            budget_data.apply(undo);
+        }
+        assert_eq!(contents(budget_data), before, "undoing {:?}", event);
+    }
+
+    #[test]
+    fn added_cost_items_get_new_ids() {
+        let mut budget_data = BudgetData::default();
+        budget_data.apply(&BudgetEvent::AddCostItem(cost_item("Rent", 900.0)));
+        budget_data.apply(&BudgetEvent::AddCostItem(cost_item("Bus", 40.0)));
+        budget_data.apply(&BudgetEvent::DeleteCostItem(0));
+        budget_data.apply(&BudgetEvent::AddCostItem(cost_item("Gym", 30.0)));
+
+        let mut ids: Vec<u64> = budget_data.cost_items.keys().copied().collect();
+        ids.sort();
+        assert_eq!(ids, [1, 2]);
+        assert_eq!(budget_data.cost_items[&2].what, "Gym");
+        assert_eq!(budget_data.next_cost_id, 3);
+    }
+
+    #[test]
+    fn events_for_missing_items_are_ignored() {
+        let mut budget_data = BudgetData::default();
+        budget_data.apply(&BudgetEvent::AddIncomeItem(income_item("Salary", 1000.0)));
+        let before = budget_data.clone();
+
+        budget_data.apply(&BudgetEvent::UpdateCostItem {
+            id: 7,
+            item: cost_item("Rent", 900.0),
+        });
+        budget_data.apply(&BudgetEvent::UpdateIncomeItem {
+            id: 3,
+            item: income_item("Bonus", 1000.0),
+        });
+        budget_data.apply(&BudgetEvent::DeleteIncomeItem(3));
+        assert_eq!(budget_data, before);
+    }
+
+    #[test]
+    fn every_event_can_be_undone() {
+        let mut budget_data = BudgetData::default();
+        budget_data.apply(&BudgetEvent::AddCostItem(cost_item("Rent", 900.0)));
+        budget_data.apply(&BudgetEvent::AddIncomeItem(income_item("Salary", 1000.0)));
+        budget_data.apply(&BudgetEvent::AddIncomeItem(income_item("Bonus", 1000.0)));
+
+        assert_undoes(
+            &mut budget_data,
+            BudgetEvent::AddCostItem(cost_item("Bus", 40.0)),
+        );
+        assert_undoes(
+            &mut budget_data,
+            BudgetEvent::UpdateCostItem {
+                id: 0,
+                item: cost_item("Rent", 950.0),
+            },
+        );
+        assert_undoes(&mut budget_data, BudgetEvent::DeleteCostItem(0));
+        assert_undoes(
+            &mut budget_data,
+            BudgetEvent::AddIncomeItem(income_item("Gift", 1000.0)),
+        );
+        assert_undoes(
+            &mut budget_data,
+            BudgetEvent::UpdateIncomeItem {
+                id: 1,
+                item: income_item("Commission", 1000.0),
+            },
+        );
+        // The income item after it stays after it once it is back
+        assert_undoes(&mut budget_data, BudgetEvent::DeleteIncomeItem(0));
+        assert_undoes(
+            &mut budget_data,
+            BudgetEvent::CommitImport(vec![
+                ImportEntry::Transaction(transaction("Groceries", 12.5)),
+                ImportEntry::CostItem(cost_item("Phone", 20.0)),
+                ImportEntry::Transaction(transaction("Cinema", 12.5)),
+            ]),
+        );
+        assert_undoes(
+            &mut budget_data,
+            BudgetEvent::RenameBudget("Household".to_string()),
+        );
+    }
+
+    #[test]
+    fn undoing_an_undo_redoes() {
+        let mut budget_data = BudgetData::default();
+        budget_data.apply(&BudgetEvent::AddIncomeItem(income_item("Salary", 1000.0)));
+        budget_data.apply(&BudgetEvent::AddIncomeItem(income_item("Bonus", 1000.0)));
+        budget_data.apply(&BudgetEvent::AddCostItem(cost_item("Rent", 900.0)));
+
+        let mut deleted = budget_data.clone();
+        let applied = deleted.apply_tracked(&BudgetEvent::DeleteIncomeItem(0));
+        let mut redo = Vec::new();
+        for event in &applied.undo {
+            redo.extend(deleted.apply_tracked(event).undo);
+        }
+        assert_eq!(deleted, budget_data);
+        for event in &redo {
+            deleted.apply(event);
+        }
+        assert_eq!(deleted.income_items.len(), 1);
+        assert_eq!(deleted.income_items[0].source, "Bonus");
+        assert_eq!(deleted.income_items[0].id, 1);
+
+        // A restored cost item keeps its id, so later edits still find it
+        let applied = budget_data.apply_tracked(&BudgetEvent::DeleteCostItem(0));
+        budget_data.apply(&applied.undo[0]);
+        assert_eq!(budget_data.cost_items[&0].what, "Rent");
+    }
+
+    #[test]
+    fn old_income_items_are_numbered() {
+        let mut budget_data = BudgetData {
+            income_items: vec![income_item("Salary", 1000.0), income_item("Bonus", 1000.0)],
+            ..BudgetData::default()
+        };
+        budget_data.number_income_items();
+        assert_eq!(budget_data.income_items[1].id, 1);
+        assert_eq!(budget_data.next_income_id, 2);
+
+        // Numbered items keep their ids
+        budget_data.apply(&BudgetEvent::DeleteIncomeItem(0));
+        budget_data.number_income_items();
+        assert_eq!(budget_data.income_items[0].id, 1);
+        assert_eq!(budget_data.next_income_id, 2);
+    }
+
+    #[test]
+    fn describe_names_the_item() {
+        let mut budget_data = BudgetData::default();
+        budget_data.apply(&BudgetEvent::AddCostItem(cost_item("Rent", 900.0)));
+
+        let (label, deleted) = budget_data.describe(&BudgetEvent::DeleteCostItem(0));
+        assert_eq!(label, "Delete \"Rent\"");
+        assert_eq!(deleted.as_deref(), Some("\"Rent\""));
+
+        let (label, deleted) =
+            budget_data.describe(&BudgetEvent::AddCostItem(cost_item("Bus", 40.0)));
+        assert_eq!(label, "Add \"Bus\"");
+        assert_eq!(deleted, None);
+    }
+
+    #[test]
+    fn tags_are_listed_once() {
+        let mut budget_data = BudgetData::default();
+        let mut rent = cost_item("Rent", 900.0);
+        rent.tags = Some(vec!["home".to_string(), "Fixed".to_string()]);
+        budget_data.apply(&BudgetEvent::AddCostItem(rent));
+        let mut salary = income_item("Salary", 1000.0);
+        salary.tags = Some(vec!["fixed".to_string(), "work".to_string()]);
+        budget_data.apply(&BudgetEvent::AddIncomeItem(salary));
+
+        assert_eq!(budget_data.tags(), ["Fixed", "home", "work"]);
+    }
+}
@@ -0,0 +1,33 @@
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    import::ImportEntry,
+    models::{cost_item::CostItem, income_item::IncomeItem, transaction::Transaction},
+};
+
+/// A change to the budget. Front-ends turn what the user does into these,
+/// and `BudgetData::apply` carries them out.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub enum BudgetEvent {
+    AddCostItem(CostItem),
+    UpdateCostItem {
+        id: u64,
+        item: CostItem,
+    },
+    DeleteCostItem(u64),
+    /// Put a deleted cost item back under its old id.
+    RestoreCostItem(CostItem),
+    AddIncomeItem(IncomeItem),
+    UpdateIncomeItem {
+        id: u64,
+        item: IncomeItem,
+    },
+    DeleteIncomeItem(u64),
+    /// Put a deleted income item back under its old id.
+    RestoreIncomeItem(IncomeItem),
+    DeleteTransaction(u64),
+    /// Put a deleted transaction back under its old id.
+    RestoreTransaction(Transaction),
+    CommitImport(Vec<ImportEntry>),
+    RenameBudget(String),
+}
@@ -0,0 +1,199 @@
+use std::{
+    io::Write,
+    path::{Path, PathBuf},
+};
+
+use crate::{
+    BudgetData,
+    amount::Locale,
+    models::{cost_item::CostItem, income_item::IncomeItem, transaction::Transaction},
+};
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum DecimalFormat {
+    /// 1234.50
+    Point,
+    /// 1234,50
+    Comma,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct CsvOptions {
+    pub delimiter: u8,
+    pub decimal: DecimalFormat,
+}
+
+impl Default for CsvOptions {
+    fn default() -> Self {
+        Self {
+            delimiter: b',',
+            decimal: DecimalFormat::Point,
+        }
+    }
+}
+
+impl CsvOptions {
+    pub fn format_amount(&self, amount: f32) -> String {
+        let text = format!("{:.2}", amount);
+        match self.decimal {
+            DecimalFormat::Point => text,
+            DecimalFormat::Comma => text.replace('.', ","),
+        }
+    }
+
+    /// `locale`, from the settings, if it writes decimals as these options
+    /// do; else one that does, to read amounts with.
+    pub fn locale(&self, locale: Locale) -> Locale {
+        match (self.decimal, locale.decimal_mark()) {
+            (DecimalFormat::Point, '.') | (DecimalFormat::Comma, ',') => locale,
+            (DecimalFormat::Point, _) => Locale::English,
+            (DecimalFormat::Comma, _) => Locale::German,
+        }
+    }
+
+    fn writer<W: Write>(&self, writer: W) -> csv::Writer<W> {
+        csv::WriterBuilder::new()
+            .delimiter(self.delimiter)
+            .from_writer(writer)
+    }
+}
+
+/// Tags separated by `;`, as `import::csv` reads them back. A `;`, `,` or
+/// `\` inside a tag is written with a `\` before it.
+fn join_tags(tags: &Option<Vec<String>>) -> String {
+    let escape = |tag: &String| {
+        tag.chars().fold(String::new(), |mut out, c| {
+            if matches!(c, ';' | ',' | '\\') {
+                out.push('\\');
+            }
+            out.push(c);
+            out
+        })
+    };
+    tags.as_ref()
+        .map(|tags| tags.iter().map(escape).collect::<Vec<_>>().join(";"))
+        .unwrap_or_default()
+}
+
+pub fn write_cost_items<W: Write>(
+    writer: W,
+    items: &[&CostItem],
+    options: &CsvOptions,
+) -> Result<(), String> {
+    let mut csv = options.writer(writer);
+    csv.write_record([
+        "what", "cost", "cycle", "category", "tags", "monthly", "yearly",
+    ])
+    .map_err(|e| e.to_string())?;
+
+    for item in items {
+        csv.write_record([
+            item.what.clone(),
+            options.format_amount(item.cost),
+            item.cost_cycle.label().to_string(),
+            item.cost_category.label().to_string(),
+            join_tags(&item.tags),
+            options.format_amount(item.cost_cycle.to_monthly(item.cost)),
+            options.format_amount(item.cost_cycle.to_yearly(item.cost)),
+        ])
+        .map_err(|e| e.to_string())?;
+    }
+
+    csv.flush().map_err(|e| e.to_string())
+}
+
+pub fn write_income_items<W: Write>(
+    writer: W,
+    items: &[IncomeItem],
+    options: &CsvOptions,
+) -> Result<(), String> {
+    let mut csv = options.writer(writer);
+    csv.write_record([
+        "source", "amount", "cycle", "category", "tags", "monthly", "yearly",
+    ])
+    .map_err(|e| e.to_string())?;
+
+    for item in items {
+        csv.write_record([
+            item.source.clone(),
+            options.format_amount(item.amount),
+            item.income_cycle.label().to_string(),
+            item.category.label().to_string(),
+            join_tags(&item.tags),
+            options.format_amount(item.income_cycle.to_monthly(item.amount)),
+            options.format_amount(item.income_cycle.to_yearly(item.amount)),
+        ])
+        .map_err(|e| e.to_string())?;
+    }
+
+    csv.flush().map_err(|e| e.to_string())
+}
+
+/// Expenses are written as negative amounts so the column sums to the net result.
+pub fn write_transactions<W: Write>(
+    writer: W,
+    transactions: &[Transaction],
+    options: &CsvOptions,
+) -> Result<(), String> {
+    let mut csv = options.writer(writer);
+    csv.write_record(["date", "description", "amount", "type", "category", "tags"])
+        .map_err(|e| e.to_string())?;
+
+    for transaction in transactions {
+        let kind = if transaction.category.is_income() {
+            "income"
+        } else {
+            "expense"
+        };
+        csv.write_record([
+            transaction.date.to_string(),
+            transaction.description.clone(),
+            options.format_amount(transaction.signed_amount()),
+            kind.to_string(),
+            transaction.category.label().to_string(),
+            join_tags(&transaction.tags),
+        ])
+        .map_err(|e| e.to_string())?;
+    }
+
+    csv.flush().map_err(|e| e.to_string())
+}
+
+/// `cost_items.csv`, `income_items.csv` and `transactions.csv`, by file name.
+pub fn render_all(
+    budget_data: &BudgetData,
+    options: &CsvOptions,
+) -> Result<Vec<(&'static str, Vec<u8>)>, String> {
+    let mut cost_items: Vec<&CostItem> = budget_data.cost_items.values().collect();
+    cost_items.sort_by_key(|item| item.id);
+
+    let mut costs = Vec::new();
+    write_cost_items(&mut costs, &cost_items, options)?;
+    let mut income = Vec::new();
+    write_income_items(&mut income, &budget_data.income_items, options)?;
+    let mut transactions = Vec::new();
+    write_transactions(&mut transactions, &budget_data.transactions, options)?;
+
+    Ok(vec![
+        ("cost_items.csv", costs),
+        ("income_items.csv", income),
+        ("transactions.csv", transactions),
+    ])
+}
+
+/// Write `cost_items.csv`, `income_items.csv` and `transactions.csv` into `dir`.
+pub fn export_all(
+    dir: &Path,
+    budget_data: &BudgetData,
+    options: &CsvOptions,
+) -> Result<Vec<PathBuf>, String> {
+    std::fs::create_dir_all(dir).map_err(|e| e.to_string())?;
+
+    let mut paths = Vec::new();
+    for (name, contents) in render_all(budget_data, options)? {
+        let path = dir.join(name);
+        std::fs::write(&path, contents).map_err(|e| format!("{}: {}", path.display(), e))?;
+        paths.push(path);
+    }
+    Ok(paths)
+}
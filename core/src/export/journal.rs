@@ -0,0 +1,315 @@
+use std::{fmt::Write as _, path::Path};
+
+use serde::{Deserialize, Serialize};
+
+use crate::models::{
+    cost_item::ExpenceCategory,
+    income_item::IncomeCategory,
+    transaction::{Transaction, TransactionCategory},
+};
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum JournalFormat {
+    Ledger,
+    Hledger,
+    Beancount,
+}
+
+impl JournalFormat {
+    pub const ALL: [JournalFormat; 3] = [
+        JournalFormat::Ledger,
+        JournalFormat::Hledger,
+        JournalFormat::Beancount,
+    ];
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            JournalFormat::Ledger => "Ledger",
+            JournalFormat::Hledger => "hledger",
+            JournalFormat::Beancount => "Beancount",
+        }
+    }
+
+    pub fn extension(&self) -> &'static str {
+        match self {
+            JournalFormat::Ledger => "ledger",
+            JournalFormat::Hledger => "journal",
+            JournalFormat::Beancount => "beancount",
+        }
+    }
+}
+
+/// Which account each category is booked against, plus the account money
+/// moves in and out of.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct AccountMapping {
+    pub expense_accounts: Vec<(ExpenceCategory, String)>,
+    pub income_accounts: Vec<(IncomeCategory, String)>,
+    pub asset_account: String,
+    pub commodity: String,
+}
+
+impl Default for AccountMapping {
+    fn default() -> Self {
+        Self {
+            expense_accounts: ExpenceCategory::ALL
+                .into_iter()
+                .map(|c| {
+                    let account = format!("Expenses:{}", c.label().replace(' ', ""));
+                    (c, account)
+                })
+                .collect(),
+            income_accounts: IncomeCategory::ALL
+                .into_iter()
+                .map(|c| {
+                    let account = format!("Income:{}", c.label().replace(' ', ""));
+                    (c, account)
+                })
+                .collect(),
+            asset_account: "Assets:Checking".to_string(),
+            commodity: "USD".to_string(),
+        }
+    }
+}
+
+impl AccountMapping {
+    pub fn account_for(&self, category: &TransactionCategory) -> &str {
+        let account = match category {
+            TransactionCategory::Expense(expense) => self
+                .expense_accounts
+                .iter()
+                .find(|(c, _)| c == expense)
+                .map(|(_, account)| account),
+            TransactionCategory::Income(income) => self
+                .income_accounts
+                .iter()
+                .find(|(c, _)| c == income)
+                .map(|(_, account)| account),
+        };
+        match (account, category) {
+            (Some(account), _) => account,
+            (None, TransactionCategory::Expense(_)) => "Expenses:Other",
+            (None, TransactionCategory::Income(_)) => "Income:Other",
+        }
+    }
+}
+
+pub fn render(
+    transactions: &[Transaction],
+    format: JournalFormat,
+    mapping: &AccountMapping,
+) -> String {
+    let mut sorted: Vec<&Transaction> = transactions.iter().collect();
+    sorted.sort_by_key(|t| (t.date, t.id));
+
+    let mut out = String::new();
+
+    if format == JournalFormat::Beancount {
+        write_beancount_header(&mut out, &sorted, mapping);
+    }
+
+    for transaction in sorted {
+        match format {
+            JournalFormat::Ledger | JournalFormat::Hledger => {
+                write_ledger_entry(&mut out, transaction, format, mapping)
+            }
+            JournalFormat::Beancount => write_beancount_entry(&mut out, transaction, mapping),
+        }
+        out.push('\n');
+    }
+
+    out
+}
+
+pub fn export_to_file(
+    path: &Path,
+    transactions: &[Transaction],
+    format: JournalFormat,
+    mapping: &AccountMapping,
+) -> Result<(), String> {
+    std::fs::write(path, render(transactions, format, mapping))
+        .map_err(|e| format!("{}: {}", path.display(), e))
+}
+
+/// Returns (debited account, credited account) for a transaction.
+fn postings<'a>(transaction: &Transaction, mapping: &'a AccountMapping) -> (&'a str, &'a str) {
+    let category_account = mapping.account_for(&transaction.category);
+    if transaction.category.is_income() {
+        (&mapping.asset_account, category_account)
+    } else {
+        (category_account, &mapping.asset_account)
+    }
+}
+
+/// The transaction's tags with every character journals don't allow in a
+/// tag, like spaces, `:` or `#`, replaced by `-`.
+fn journal_tags(transaction: &Transaction) -> Vec<String> {
+    transaction
+        .tags
+        .iter()
+        .flatten()
+        .filter(|tag| !tag.is_empty())
+        .map(|tag| {
+            tag.chars()
+                .map(|c| {
+                    if c.is_ascii_alphanumeric() || matches!(c, '-' | '_' | '/' | '.') {
+                        c
+                    } else {
+                        '-'
+                    }
+                })
+                .collect()
+        })
+        .collect()
+}
+
+fn write_ledger_entry(
+    out: &mut String,
+    transaction: &Transaction,
+    format: JournalFormat,
+    mapping: &AccountMapping,
+) {
+    let _ = writeln!(out, "{} {}", transaction.date, transaction.description);
+
+    let tags = journal_tags(transaction);
+    if !tags.is_empty() {
+        match format {
+            // ledger: `; :tag1:tag2:`
+            JournalFormat::Ledger => {
+                let _ = writeln!(out, "    ; :{}:", tags.join(":"));
+            }
+            // hledger: `; tag1:, tag2:`
+            _ => {
+                let joined: Vec<String> = tags.iter().map(|t| format!("{}:", t)).collect();
+                let _ = writeln!(out, "    ; {}", joined.join(", "));
+            }
+        }
+    }
+
+    let (debit, credit) = postings(transaction, mapping);
+    let _ = writeln!(
+        out,
+        "    {:<40}  {:.2} {}",
+        debit, transaction.amount, mapping.commodity
+    );
+    let _ = writeln!(out, "    {}", credit);
+}
+
+fn write_beancount_header(out: &mut String, sorted: &[&Transaction], mapping: &AccountMapping) {
+    let Some(first) = sorted.first() else {
+        return;
+    };
+    let open_date = first.date;
+
+    let _ = writeln!(
+        out,
+        "option \"operating_currency\" \"{}\"",
+        mapping.commodity
+    );
+    out.push('\n');
+
+    let mut accounts: Vec<&str> = vec![&mapping.asset_account];
+    for transaction in sorted {
+        let account = mapping.account_for(&transaction.category);
+        if !accounts.contains(&account) {
+            accounts.push(account);
+        }
+    }
+    for account in accounts {
+        let _ = writeln!(out, "{} open {}", open_date, account);
+    }
+    out.push('\n');
+}
+
+fn write_beancount_entry(out: &mut String, transaction: &Transaction, mapping: &AccountMapping) {
+    let narration = transaction.description.replace('"', "'");
+    let tags: String = journal_tags(transaction)
+        .iter()
+        .map(|t| format!(" #{}", t))
+        .collect();
+    let _ = writeln!(out, "{} * \"{}\"{}", transaction.date, narration, tags);
+
+    let (debit, credit) = postings(transaction, mapping);
+    let _ = writeln!(
+        out,
+        "  {:<40}  {:.2} {}",
+        debit, transaction.amount, mapping.commodity
+    );
+    let _ = writeln!(
+        out,
+        "  {:<40} {:.2} {}",
+        credit, -transaction.amount, mapping.commodity
+    );
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::fixtures::transaction;
+
+    #[test]
+    fn tags_are_written_so_journals_read_them() {
+        let transaction = Transaction {
+            category: TransactionCategory::Expense(ExpenceCategory::DiningOut),
+            tags: Some(vec![
+                "eating out".to_string(),
+                "kafé".to_string(),
+                "a:b#c".to_string(),
+                "trip/2025.v2".to_string(),
+            ]),
+            ..transaction("Lunch", 12.5)
+        };
+        let mapping = AccountMapping::default();
+        let tags = "eating-out kaf- a-b-c trip/2025.v2";
+
+        let beancount = render(
+            std::slice::from_ref(&transaction),
+            JournalFormat::Beancount,
+            &mapping,
+        );
+        let hashed: Vec<String> = tags.split(' ').map(|t| format!("#{}", t)).collect();
+        assert!(
+            beancount.contains(&format!("\"Lunch\" {}", hashed.join(" "))),
+            "{}",
+            beancount
+        );
+
+        let ledger = render(
+            std::slice::from_ref(&transaction),
+            JournalFormat::Ledger,
+            &mapping,
+        );
+        assert!(
+            ledger.contains(&format!("; :{}:", tags.replace(' ', ":"))),
+            "{}",
+            ledger
+        );
+
+        let hledger = render(&[transaction], JournalFormat::Hledger, &mapping);
+        assert!(
+            hledger.contains(&format!("; {}:", tags.replace(' ', ":, "))),
+            "{}",
+            hledger
+        );
+    }
+
+    #[test]
+    fn saved_mappings_fill_in_missing_accounts() {
+        let mapping: AccountMapping =
+            serde_json::from_str(r#"{"asset_account": "Assets:Bank"}"#).unwrap();
+        assert_eq!(
+            mapping,
+            AccountMapping {
+                asset_account: "Assets:Bank".to_string(),
+                ..AccountMapping::default()
+            }
+        );
+
+        let json = serde_json::to_string(&mapping).unwrap();
+        assert_eq!(
+            serde_json::from_str::<AccountMapping>(&json).unwrap(),
+            mapping
+        );
+    }
+}
@@ -0,0 +1,3 @@
+pub mod csv;
+pub mod journal;
+pub mod text_table;
\ No newline at end of file
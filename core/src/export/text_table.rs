@@ -0,0 +1,196 @@
+use crate::{
+    amount::{self, Locale},
+    models::{cost_item::CostItem, income_item::IncomeItem},
+};
+
+/// Plain-text table formats for pasting into spreadsheets or chat.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum TableFormat {
+    Tsv,
+    Markdown,
+}
+
+impl TableFormat {
+    pub fn label(&self) -> &'static str {
+        match self {
+            TableFormat::Tsv => "TSV",
+            TableFormat::Markdown => "Markdown",
+        }
+    }
+}
+
+/// Header, rows and a totals row; `numeric` marks right-aligned columns.
+struct TextTable {
+    headers: Vec<&'static str>,
+    numeric: Vec<bool>,
+    rows: Vec<Vec<String>>,
+    totals: Vec<String>,
+}
+
+impl TextTable {
+    fn render(&self, format: TableFormat) -> String {
+        let rows = std::iter::once(self.headers.iter().map(|h| h.to_string()).collect())
+            .chain(self.rows.iter().cloned())
+            .chain(std::iter::once(self.totals.clone()));
+
+        let mut out = String::new();
+        match format {
+            TableFormat::Tsv => {
+                for row in rows {
+                    let cells: Vec<String> = row
+                        .iter()
+                        .map(|cell| cell.replace(['\t', '\n', '\r'], " "))
+                        .collect();
+                    out.push_str(&cells.join("\t"));
+                    out.push('\n');
+                }
+            }
+            TableFormat::Markdown => {
+                for (i, row) in rows.enumerate() {
+                    let cells: Vec<String> = row
+                        .iter()
+                        .map(|cell| cell.replace('|', "\\|").replace(['\n', '\r'], " "))
+                        .collect();
+                    out.push_str(&format!("| {} |\n", cells.join(" | ")));
+
+                    if i == 0 {
+                        let rule: Vec<&str> = self
+                            .numeric
+                            .iter()
+                            .map(|numeric| if *numeric { "---:" } else { "---" })
+                            .collect();
+                        out.push_str(&format!("| {} |\n", rule.join(" | ")));
+                    }
+                }
+            }
+        }
+        out
+    }
+}
+
+/// Cost items in the given order, with monthly and yearly equivalents and a
+/// totals row. Amounts are written as `locale` writes them, without a
+/// currency sign, so spreadsheets in that locale read them as numbers.
+pub fn cost_items(items: &[&CostItem], format: TableFormat, locale: Locale) -> String {
+    let amount = |value| amount::format(value, locale);
+    let mut monthly_total = 0.0;
+    let rows = items
+        .iter()
+        .map(|item| {
+            let monthly = item.cost_cycle.to_monthly(item.cost);
+            monthly_total += monthly;
+            vec![
+                item.what.clone(),
+                amount(item.cost),
+                item.cost_cycle.label().to_string(),
+                item.cost_category.label().to_string(),
+                item.tags
+                    .as_ref()
+                    .map(|tags| tags.join(", "))
+                    .unwrap_or_default(),
+                amount(monthly),
+                amount(item.cost_cycle.to_yearly(item.cost)),
+            ]
+        })
+        .collect();
+
+    TextTable {
+        headers: vec![
+            "What", "Cost", "Cycle", "Category", "Tags", "Monthly", "Yearly",
+        ],
+        numeric: vec![false, true, false, false, false, true, true],
+        rows,
+        totals: vec![
+            "Total".to_string(),
+            String::new(),
+            String::new(),
+            String::new(),
+            String::new(),
+            amount(monthly_total),
+            amount(monthly_total * 12.0),
+        ],
+    }
+    .render(format)
+}
+
+pub fn income_items(items: &[IncomeItem], format: TableFormat, locale: Locale) -> String {
+    let amount = |value| amount::format(value, locale);
+    let mut monthly_total = 0.0;
+    let rows = items
+        .iter()
+        .map(|income| {
+            let monthly = income.income_cycle.to_monthly(income.amount);
+            monthly_total += monthly;
+            vec![
+                income.source.clone(),
+                amount(income.amount),
+                income.income_cycle.label().to_string(),
+                income.category.label().to_string(),
+                amount(monthly),
+                amount(income.income_cycle.to_yearly(income.amount)),
+            ]
+        })
+        .collect();
+
+    TextTable {
+        headers: vec!["Source", "Amount", "Cycle", "Category", "Monthly", "Yearly"],
+        numeric: vec![false, true, false, false, true, true],
+        rows,
+        totals: vec![
+            "Total".to_string(),
+            String::new(),
+            String::new(),
+            String::new(),
+            amount(monthly_total),
+            amount(monthly_total * 12.0),
+        ],
+    }
+    .render(format)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        fixtures::{cost_item, income_item},
+        models::cost_item::CostCycle,
+    };
+
+    #[test]
+    fn tsv_has_locale_amounts_and_a_total() {
+        let mut rent = cost_item("Rent\tflat", 1200.0);
+        rent.tags = Some(vec!["home".to_string(), "fixed".to_string()]);
+        let mut insurance = cost_item("Insurance\nhome", 240.0);
+        insurance.cost_cycle = CostCycle::Yearly;
+
+        let text = cost_items(&[&rent, &insurance], TableFormat::Tsv, Locale::German);
+        let lines: Vec<&str> = text.lines().collect();
+        assert_eq!(
+            lines,
+            [
+                "What\tCost\tCycle\tCategory\tTags\tMonthly\tYearly",
+                "Rent flat\t1.200,00\tMonthly\tOther\thome, fixed\t1.200,00\t14.400,00",
+                "Insurance home\t240,00\tYearly\tOther\t\t20,00\t240,00",
+                "Total\t\t\t\t\t1.220,00\t14.640,00",
+            ]
+        );
+    }
+
+    #[test]
+    fn markdown_escapes_pipes_and_aligns_amounts() {
+        let salary = income_item("Salary | main\r\njob", 3000.0);
+
+        let text = income_items(&[salary], TableFormat::Markdown, Locale::English);
+        let lines: Vec<&str> = text.lines().collect();
+        assert_eq!(
+            lines,
+            [
+                "| Source | Amount | Cycle | Category | Monthly | Yearly |",
+                "| --- | ---: | --- | --- | ---: | ---: |",
+                "| Salary \\| main  job | 3,000.00 | Monthly | Salary | 3,000.00 | 36,000.00 |",
+                "| Total |  |  |  | 3,000.00 | 36,000.00 |",
+            ]
+        );
+        assert!(!text.contains('$'));
+    }
+}
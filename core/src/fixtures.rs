@@ -0,0 +1,45 @@
+//! Items for tests, with the fields a test doesn't care about filled in.
+
+use chrono::NaiveDate;
+
+use crate::models::{
+    cost_item::{CostCycle, CostItem, ExpenceCategory},
+    income_item::{IncomeCategory, IncomeItem},
+    transaction::{Transaction, TransactionCategory},
+};
+
+/// A monthly cost in the Other category.
+pub fn cost_item(what: &str, cost: f32) -> CostItem {
+    CostItem {
+        id: 0,
+        what: what.to_string(),
+        cost,
+        cost_cycle: CostCycle::Monthly,
+        cost_category: ExpenceCategory::Other,
+        tags: None,
+    }
+}
+
+/// A monthly salary.
+pub fn income_item(source: &str, amount: f32) -> IncomeItem {
+    IncomeItem {
+        id: 0,
+        source: source.to_string(),
+        category: IncomeCategory::Salary,
+        amount,
+        income_cycle: CostCycle::Monthly,
+        tags: None,
+    }
+}
+
+/// Groceries bought on 2025-03-01.
+pub fn transaction(description: &str, amount: f32) -> Transaction {
+    Transaction {
+        id: 0,
+        date: NaiveDate::from_ymd_opt(2025, 3, 1).unwrap(),
+        description: description.to_string(),
+        amount,
+        category: TransactionCategory::Expense(ExpenceCategory::Groceries),
+        tags: None,
+    }
+}
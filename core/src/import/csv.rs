@@ -0,0 +1,220 @@
+use std::{fs::File, io::Read, path::Path};
+
+use crate::{
+    amount::{self, Locale},
+    export::csv::CsvOptions,
+    models::cost_item::{CostCycle, CostItem, ExpenceCategory},
+};
+
+/// Read cost items from a CSV file, typically one produced by
+/// `export::csv::write_cost_items` and edited in a spreadsheet. Columns are
+/// matched by header name; only `what` and `cost` are required. Amounts are
+/// read in `locale`, the one from the settings, unless the options say the
+/// file writes decimals another way.
+pub fn read_cost_items<R: Read>(
+    reader: R,
+    options: &CsvOptions,
+    locale: Locale,
+) -> Result<(Vec<CostItem>, Vec<String>), String> {
+    let locale = options.locale(locale);
+    let mut csv = csv::ReaderBuilder::new()
+        .delimiter(options.delimiter)
+        .flexible(true)
+        .trim(csv::Trim::All)
+        .from_reader(reader);
+
+    let headers: Vec<String> = csv
+        .headers()
+        .map_err(|e| e.to_string())?
+        .iter()
+        .map(|h| h.to_lowercase())
+        .collect();
+    let column = |names: &[&str]| headers.iter().position(|h| names.contains(&h.as_str()));
+
+    let what_col = column(&["what", "name", "description"]).ok_or("missing 'what' column")?;
+    let cost_col = column(&["cost", "amount"]).ok_or("missing 'cost' column")?;
+    let cycle_col = column(&["cycle"]);
+    let category_col = column(&["category"]);
+    let tags_col = column(&["tags"]);
+
+    let mut items = Vec::new();
+    let mut errors = Vec::new();
+
+    for (row_no, record) in csv.records().enumerate() {
+        // +2: one for the header, one for 1-based line numbers
+        let line = row_no + 2;
+        let record = match record {
+            Ok(record) => record,
+            Err(err) => {
+                errors.push(format!("Line {}: {}", line, err));
+                continue;
+            }
+        };
+
+        let what = record.get(what_col).unwrap_or("").to_string();
+        if what.is_empty() {
+            errors.push(format!("Line {}: empty name", line));
+            continue;
+        }
+
+        let Some(cost) = record
+            .get(cost_col)
+            .and_then(|text| amount::parse(text, locale))
+        else {
+            errors.push(format!("Line {}: invalid cost", line));
+            continue;
+        };
+
+        let cost_cycle = match cycle_col.and_then(|col| record.get(col)) {
+            None | Some("") => CostCycle::Monthly,
+            Some(text) => match CostCycle::from_name(text) {
+                Some(cycle) => cycle,
+                None => {
+                    errors.push(format!("Line {}: unknown cycle '{}'", line, text));
+                    continue;
+                }
+            },
+        };
+
+        let cost_category = match category_col.and_then(|col| record.get(col)) {
+            None | Some("") => ExpenceCategory::guess(&what),
+            Some(text) => match ExpenceCategory::from_name(text) {
+                Some(category) => category,
+                None => {
+                    errors.push(format!("Line {}: unknown category '{}'", line, text));
+                    continue;
+                }
+            },
+        };
+
+        let tags = split_tags(tags_col.and_then(|col| record.get(col)).unwrap_or(""));
+
+        items.push(CostItem {
+            id: 0,
+            what,
+            cost,
+            cost_cycle,
+            cost_category,
+            tags: if tags.is_empty() { None } else { Some(tags) },
+        });
+    }
+
+    Ok((items, errors))
+}
+
+/// Tags separated by `;` or `,`, with `\` keeping the next character in the
+/// tag, as `export::csv` writes them.
+fn split_tags(text: &str) -> Vec<String> {
+    let mut tags = Vec::new();
+    let mut tag = String::new();
+    let mut chars = text.chars();
+    while let Some(c) = chars.next() {
+        match c {
+            '\\' => tag.extend(chars.next()),
+            ';' | ',' => tags.push(std::mem::take(&mut tag)),
+            _ => tag.push(c),
+        }
+    }
+    tags.push(tag);
+
+    tags.iter()
+        .map(|tag| tag.trim().trim_start_matches('#').to_string())
+        .filter(|tag| !tag.is_empty())
+        .collect()
+}
+
+pub fn read_cost_items_file(
+    path: &Path,
+    options: &CsvOptions,
+    locale: Locale,
+) -> Result<(Vec<CostItem>, Vec<String>), String> {
+    let file = File::open(path).map_err(|e| format!("{}: {}", path.display(), e))?;
+    read_cost_items(file, options, locale)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        export::csv::{DecimalFormat, write_cost_items},
+        fixtures::cost_item,
+    };
+
+    #[test]
+    fn exported_cost_items_read_back_the_same() {
+        let items = vec![
+            CostItem {
+                cost_category: ExpenceCategory::Housing,
+                tags: Some(vec![
+                    "Home".to_string(),
+                    "fixed costs".to_string(),
+                    "a;b, c\\d".to_string(),
+                ]),
+                ..cost_item("Rent, flat", 1200.5)
+            },
+            CostItem {
+                cost_cycle: CostCycle::Daily,
+                cost_category: ExpenceCategory::DiningOut,
+                ..cost_item("Coffee", 4.25)
+            },
+        ];
+        let options = CsvOptions {
+            delimiter: b';',
+            decimal: DecimalFormat::Comma,
+        };
+        let mut written = Vec::new();
+        write_cost_items(&mut written, &items.iter().collect::<Vec<_>>(), &options).unwrap();
+
+        let (read, errors) =
+            read_cost_items(written.as_slice(), &options, Locale::English).unwrap();
+        assert!(errors.is_empty(), "{:?}", errors);
+        assert_eq!(read, items);
+    }
+
+    #[test]
+    fn unknown_cycles_are_errors() {
+        let text = "what,cost,cycle\nRent,900,monthly\nGym,30,fortnightly\nBus,40,\n";
+        let (items, errors) =
+            read_cost_items(text.as_bytes(), &CsvOptions::default(), Locale::English).unwrap();
+        let names: Vec<&str> = items.iter().map(|item| item.what.as_str()).collect();
+        assert_eq!(names, ["Rent", "Bus"]);
+        assert_eq!(errors, ["Line 3: unknown cycle 'fortnightly'"]);
+    }
+
+    #[test]
+    fn unknown_categories_are_errors() {
+        let text = "what,cost,category\nRent,900,housing\nGym,30,fitness\nCoffee,4,\n";
+        let (items, errors) =
+            read_cost_items(text.as_bytes(), &CsvOptions::default(), Locale::English).unwrap();
+        let categories: Vec<ExpenceCategory> = items
+            .iter()
+            .map(|item| item.cost_category.clone())
+            .collect();
+        assert_eq!(
+            categories,
+            [ExpenceCategory::Housing, ExpenceCategory::DiningOut]
+        );
+        assert_eq!(errors, ["Line 3: unknown category 'fitness'"]);
+    }
+
+    #[test]
+    fn tags_are_split_on_semicolons_and_commas() {
+        assert_eq!(
+            split_tags("food, home;#fixed costs; ;a\\,b"),
+            ["food", "home", "fixed costs", "a,b"]
+        );
+    }
+
+    #[test]
+    fn amounts_are_read_in_the_locale() {
+        let options = CsvOptions {
+            delimiter: b';',
+            decimal: DecimalFormat::Comma,
+        };
+        let text = "what;cost\nRent;1 200,50 kr\nCoffee;4,25\n";
+        let (items, errors) = read_cost_items(text.as_bytes(), &options, Locale::Nordic).unwrap();
+        assert!(errors.is_empty(), "{:?}", errors);
+        let costs: Vec<f32> = items.iter().map(|item| item.cost).collect();
+        assert_eq!(costs, [1200.5, 4.25]);
+    }
+}
@@ -0,0 +1,355 @@
+use std::path::Path;
+
+use chrono::NaiveDate;
+
+use crate::{
+    amount::{self, Locale},
+    export::journal::AccountMapping,
+    models::{
+        cost_item::ExpenceCategory,
+        income_item::IncomeCategory,
+        transaction::{Transaction, TransactionCategory},
+    },
+};
+
+/// Read transactions from a ledger, hledger or beancount journal.
+///
+/// Every posting to an `Expenses:*` or `Income:*` account becomes one
+/// transaction; postings to other accounts (assets, liabilities) are the
+/// other side of the booking and are ignored. Accounts are matched against
+/// `mapping` first and then by their last component, falling back to Other.
+pub fn parse(text: &str, mapping: &AccountMapping) -> (Vec<Transaction>, Vec<String>) {
+    let mut transactions = Vec::new();
+    let mut errors = Vec::new();
+    let mut current: Option<Entry> = None;
+
+    for (line_no, raw_line) in text.lines().enumerate() {
+        let line = raw_line.trim_end();
+        let indented = line.starts_with(' ') || line.starts_with('\t');
+
+        if line.trim().is_empty() || !indented {
+            if let Some(entry) = current.take() {
+                entry.into_transactions(mapping, &mut transactions, &mut errors);
+            }
+            if !line.trim().is_empty() {
+                current = parse_header(line, line_no + 1);
+            }
+            continue;
+        }
+
+        let Some(entry) = current.as_mut() else {
+            continue;
+        };
+
+        let content = line.trim();
+
+        if let Some(comment) = content.strip_prefix(';') {
+            entry.tags.extend(ledger_comment_tags(comment));
+            continue;
+        }
+        // beancount metadata (`key: "value"`)
+        if content.contains(": ") && !content.contains("  ") {
+            continue;
+        }
+
+        match parse_posting(content) {
+            Some(posting) => entry.postings.push(posting),
+            None => errors.push(format!("Line {}: could not read posting", line_no + 1)),
+        }
+    }
+
+    if let Some(entry) = current.take() {
+        entry.into_transactions(mapping, &mut transactions, &mut errors);
+    }
+
+    (transactions, errors)
+}
+
+pub fn parse_file(
+    path: &Path,
+    mapping: &AccountMapping,
+) -> Result<(Vec<Transaction>, Vec<String>), String> {
+    let text = std::fs::read_to_string(path).map_err(|e| format!("{}: {}", path.display(), e))?;
+    Ok(parse(&text, mapping))
+}
+
+struct Entry {
+    line: usize,
+    date: NaiveDate,
+    description: String,
+    tags: Vec<String>,
+    postings: Vec<(String, Option<f32>)>,
+}
+
+impl Entry {
+    fn into_transactions(
+        self,
+        mapping: &AccountMapping,
+        transactions: &mut Vec<Transaction>,
+        errors: &mut Vec<String>,
+    ) {
+        // A single posting may leave its amount out; it balances the rest.
+        let elided = self.postings.iter().filter(|(_, a)| a.is_none()).count();
+        if elided > 1 {
+            errors.push(format!(
+                "Line {}: more than one posting without an amount",
+                self.line
+            ));
+            return;
+        }
+        let balance: f32 = -self.postings.iter().filter_map(|(_, a)| *a).sum::<f32>();
+
+        for (account, amount) in &self.postings {
+            let Some(category) = category_for_account(account, mapping) else {
+                continue;
+            };
+            // Income postings are negative in double-entry books. What is
+            // left negative, a refund or a reversal, lowers its category.
+            let amount = amount.unwrap_or(balance);
+            let amount = if category.is_income() {
+                -amount
+            } else {
+                amount
+            };
+            if amount.abs() < 0.005 {
+                continue;
+            }
+
+            transactions.push(Transaction {
+                id: 0,
+                date: self.date,
+                description: self.description.clone(),
+                amount,
+                category,
+                tags: if self.tags.is_empty() {
+                    None
+                } else {
+                    Some(self.tags.clone())
+                },
+            });
+        }
+    }
+}
+
+/// Parse a transaction line such as `2024-03-01 * Rema 1000` (ledger) or
+/// `2024-03-01 * "Rema" "Groceries" #food` (beancount). Directives like
+/// `open`, `option` or `account` return `None`.
+fn parse_header(line: &str, line_no: usize) -> Option<Entry> {
+    let (date_text, rest) = line.split_once(char::is_whitespace)?;
+    let date_text = date_text.split('=').next().unwrap_or(date_text);
+    let date = NaiveDate::parse_from_str(date_text, "%Y-%m-%d")
+        .or_else(|_| NaiveDate::parse_from_str(date_text, "%Y/%m/%d"))
+        .ok()?;
+
+    let mut rest = rest.trim();
+    for flag in ["*", "!", "txn"] {
+        if let Some(stripped) = rest.strip_prefix(flag) {
+            rest = stripped.trim_start();
+            break;
+        }
+    }
+    if let Some(stripped) = rest.strip_prefix('(')
+        && let Some((_, after)) = stripped.split_once(')')
+    {
+        rest = after.trim_start();
+    }
+
+    let first_word = rest.split_whitespace().next().unwrap_or("");
+    if matches!(
+        first_word,
+        "open" | "close" | "balance" | "pad" | "price" | "note" | "document" | "event"
+    ) {
+        return None;
+    }
+
+    let (description, mut tags) = if rest.starts_with('"') {
+        parse_beancount_narration(rest)
+    } else {
+        match rest.split_once(';') {
+            Some((description, comment)) => {
+                (description.trim().to_string(), ledger_comment_tags(comment))
+            }
+            None => (rest.trim().to_string(), Vec::new()),
+        }
+    };
+    tags.dedup();
+
+    Some(Entry {
+        line: line_no,
+        date,
+        description,
+        tags,
+        postings: Vec::new(),
+    })
+}
+
+/// `"payee" "narration" #tag ^link` → ("payee narration", [tag])
+fn parse_beancount_narration(rest: &str) -> (String, Vec<String>) {
+    let mut strings = Vec::new();
+    let mut tags = Vec::new();
+
+    let mut remaining = rest;
+    while let Some(start) = remaining.find('"') {
+        let after = &remaining[start + 1..];
+        let Some(end) = after.find('"') else {
+            break;
+        };
+        strings.push(after[..end].to_string());
+        remaining = &after[end + 1..];
+    }
+    for word in remaining.split_whitespace() {
+        if let Some(tag) = word.strip_prefix('#') {
+            tags.push(tag.to_lowercase());
+        }
+    }
+
+    let description = strings
+        .into_iter()
+        .filter(|s| !s.is_empty())
+        .collect::<Vec<_>>()
+        .join(" ");
+    (description, tags)
+}
+
+/// Tags from ledger (`:a:b:`) and hledger (`a:, b:value`) comments.
+fn ledger_comment_tags(comment: &str) -> Vec<String> {
+    let comment = comment.trim();
+    if comment.starts_with(':') && comment.ends_with(':') {
+        return comment
+            .split(':')
+            .filter(|t| !t.is_empty())
+            .map(|t| t.to_lowercase())
+            .collect();
+    }
+
+    comment
+        .split(',')
+        .filter_map(|part| {
+            let (name, _) = part.trim().split_once(':')?;
+            let name = name.trim();
+            if name.is_empty() || name.contains(char::is_whitespace) {
+                None
+            } else {
+                Some(name.to_lowercase())
+            }
+        })
+        .collect()
+}
+
+/// Split `Expenses:Food  12.50 USD` into the account and the amount. The
+/// account ends at the first tab or run of two spaces.
+fn parse_posting(content: &str) -> Option<(String, Option<f32>)> {
+    let content = content.split(';').next().unwrap_or("").trim();
+    if content.is_empty() {
+        return None;
+    }
+
+    let split_at = content
+        .find('\t')
+        .into_iter()
+        .chain(content.find("  "))
+        .min();
+
+    let (account, amount_text) = match split_at {
+        Some(idx) => (&content[..idx], content[idx..].trim()),
+        // beancount allows a single space between account and amount
+        None => match content.split_once(char::is_whitespace) {
+            Some((account, rest)) if parse_journal_amount(rest).is_some() => (account, rest.trim()),
+            _ => (content, ""),
+        },
+    };
+
+    let account = account
+        .trim()
+        .trim_matches(|c| c == '(' || c == ')' || c == '[' || c == ']');
+    if account.is_empty() {
+        return None;
+    }
+
+    Some((account.to_string(), parse_journal_amount(amount_text)))
+}
+
+/// The first number in `12.50 USD`, `$12.50`, `EUR -1,200.00` or
+/// `10 AAPL {150.00 USD} @ 160.00 USD`; costs and prices after `{` or `@`
+/// are not the amount.
+fn parse_journal_amount(text: &str) -> Option<f32> {
+    text.split_whitespace()
+        .take_while(|token| !token.starts_with(['{', '@']))
+        .find_map(|token| amount::parse(token, Locale::English))
+}
+
+fn category_for_account(account: &str, mapping: &AccountMapping) -> Option<TransactionCategory> {
+    if let Some((category, _)) = mapping
+        .expense_accounts
+        .iter()
+        .find(|(_, mapped)| mapped.eq_ignore_ascii_case(account))
+    {
+        return Some(TransactionCategory::Expense(category.clone()));
+    }
+    if let Some((category, _)) = mapping
+        .income_accounts
+        .iter()
+        .find(|(_, mapped)| mapped.eq_ignore_ascii_case(account))
+    {
+        return Some(TransactionCategory::Income(category.clone()));
+    }
+
+    let (root, rest) = account.split_once(':').unwrap_or((account, ""));
+    // Try each sub-account from the leaf upwards, e.g. Expenses:Food:Groceries
+    let mut candidates: Vec<&str> = rest.split(':').collect();
+    candidates.reverse();
+
+    match root.to_lowercase().as_str() {
+        "expenses" | "expense" => Some(TransactionCategory::Expense(
+            candidates
+                .iter()
+                .find_map(|name| ExpenceCategory::from_name(name))
+                .unwrap_or_else(|| ExpenceCategory::guess(rest)),
+        )),
+        "income" | "revenue" | "revenues" => Some(TransactionCategory::Income(
+            candidates
+                .iter()
+                .find_map(|name| IncomeCategory::from_name(name))
+                .unwrap_or_else(|| IncomeCategory::guess(rest)),
+        )),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn amounts_stop_at_costs_and_prices() {
+        assert_eq!(parse_journal_amount("12.50 USD"), Some(12.5));
+        assert_eq!(parse_journal_amount("$12.50"), Some(12.5));
+        assert_eq!(parse_journal_amount("EUR -1,200.00"), Some(-1200.0));
+        assert_eq!(parse_journal_amount("1.200,50 NOK"), Some(1200.5));
+        assert_eq!(
+            parse_journal_amount("10 AAPL {150.00 USD} @ 160.00 USD"),
+            Some(10.0)
+        );
+        assert_eq!(parse_journal_amount("@ 3.00 USD"), None);
+        assert_eq!(parse_journal_amount("2024-03-01"), None);
+    }
+
+    #[test]
+    fn refunds_keep_their_category() {
+        let text = "\
+2024-03-01 * Rema
+    Expenses:Groceries  -20.00 NOK
+    Assets:Bank
+";
+        let (transactions, errors) = parse(text, &AccountMapping::default());
+        assert!(errors.is_empty());
+        assert_eq!(transactions.len(), 1);
+        assert_eq!(transactions[0].amount, -20.0);
+        // Money coming back
+        assert_eq!(transactions[0].signed_amount(), 20.0);
+        assert_eq!(
+            transactions[0].category,
+            TransactionCategory::Expense(ExpenceCategory::Groceries)
+        );
+    }
+}
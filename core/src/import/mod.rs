@@ -0,0 +1,369 @@
+pub mod csv;
+pub mod journal;
+pub mod pasted;
+pub mod spreadsheet;
+
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    BudgetData,
+    models::{
+        cost_item::CostItem,
+        transaction::{Transaction, TransactionCategory},
+    },
+};
+
+/// Something an importer produced that can be committed to `BudgetData`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub enum ImportEntry {
+    Transaction(Transaction),
+    CostItem(CostItem),
+}
+
+impl ImportEntry {
+    pub fn description(&self) -> &str {
+        match self {
+            ImportEntry::Transaction(transaction) => &transaction.description,
+            ImportEntry::CostItem(item) => &item.what,
+        }
+    }
+
+    /// Date for transactions, cycle for cost items.
+    pub fn when(&self) -> String {
+        match self {
+            ImportEntry::Transaction(transaction) => transaction.date.to_string(),
+            ImportEntry::CostItem(item) => item.cost_cycle.label().to_string(),
+        }
+    }
+
+    /// Money in as positive, money out as negative.
+    pub fn signed_amount(&self) -> f32 {
+        match self {
+            ImportEntry::Transaction(transaction) => transaction.signed_amount(),
+            ImportEntry::CostItem(item) => -item.cost,
+        }
+    }
+
+    pub fn category(&self) -> TransactionCategory {
+        match self {
+            ImportEntry::Transaction(transaction) => transaction.category.clone(),
+            ImportEntry::CostItem(item) => TransactionCategory::Expense(item.cost_category.clone()),
+        }
+    }
+
+    pub fn accepts_income_category(&self) -> bool {
+        matches!(self, ImportEntry::Transaction(_))
+    }
+
+    /// Cost items can only take expense categories; income categories are ignored for them.
+    pub fn set_category(&mut self, category: &TransactionCategory) {
+        match (self, category) {
+            (ImportEntry::Transaction(transaction), _) => transaction.category = category.clone(),
+            (ImportEntry::CostItem(item), TransactionCategory::Expense(expense)) => {
+                item.cost_category = expense.clone()
+            }
+            (ImportEntry::CostItem(_), TransactionCategory::Income(_)) => {}
+        }
+    }
+
+    pub fn tags(&self) -> &Option<Vec<String>> {
+        match self {
+            ImportEntry::Transaction(transaction) => &transaction.tags,
+            ImportEntry::CostItem(item) => &item.tags,
+        }
+    }
+
+    fn tags_mut(&mut self) -> &mut Option<Vec<String>> {
+        match self {
+            ImportEntry::Transaction(transaction) => &mut transaction.tags,
+            ImportEntry::CostItem(item) => &mut item.tags,
+        }
+    }
+}
+
+#[derive(Clone, Copy, PartialEq)]
+pub enum RowDecision {
+    Accept,
+    Skip,
+}
+
+/// One incoming row waiting in the staging area.
+pub struct ImportRow {
+    pub entry: ImportEntry,
+    pub selected: bool,
+    pub decision: RowDecision,
+    /// Description of the existing entry this row looks like, if any.
+    pub duplicate_of: Option<String>,
+    /// The row looks like an earlier row of the same import. Such rows are
+    /// kept, as two coffees on one day are two entries, but reported.
+    pub repeated: bool,
+}
+
+/// Staging area for imported rows. Nothing touches `BudgetData` until the
+/// batch is committed as a single `BudgetEvent`.
+pub struct ImportBatch {
+    pub source: String,
+    pub rows: Vec<ImportRow>,
+}
+
+impl ImportBatch {
+    pub fn new(source: String, entries: Vec<ImportEntry>, budget_data: &BudgetData) -> Self {
+        let mut rows: Vec<ImportRow> = Vec::with_capacity(entries.len());
+
+        for entry in entries {
+            let existing = find_existing_duplicate(&entry, budget_data);
+            let decision = if existing.is_some() {
+                RowDecision::Skip
+            } else {
+                RowDecision::Accept
+            };
+            let earlier = rows
+                .iter()
+                .find(|row| is_duplicate(&row.entry, &entry))
+                .map(|row| format!("{} (earlier in this import)", row.entry.description()));
+            let repeated = existing.is_none() && earlier.is_some();
+
+            rows.push(ImportRow {
+                entry,
+                selected: false,
+                decision,
+                duplicate_of: existing.or(earlier),
+                repeated,
+            });
+        }
+
+        Self { source, rows }
+    }
+
+    pub fn select_all(&mut self, selected: bool) {
+        for row in &mut self.rows {
+            row.selected = selected;
+        }
+    }
+
+    pub fn select_duplicates(&mut self) {
+        for row in &mut self.rows {
+            row.selected = row.duplicate_of.is_some();
+        }
+    }
+
+    pub fn selected_count(&self) -> usize {
+        self.rows.iter().filter(|row| row.selected).count()
+    }
+
+    pub fn set_decision_for_selected(&mut self, decision: RowDecision) {
+        for row in self.rows.iter_mut().filter(|row| row.selected) {
+            row.decision = decision;
+        }
+    }
+
+    pub fn recategorize_selected(&mut self, category: &TransactionCategory) {
+        for row in self.rows.iter_mut().filter(|row| row.selected) {
+            row.entry.set_category(category);
+        }
+    }
+
+    pub fn tag_selected(&mut self, tag: &str) {
+        for row in self.rows.iter_mut().filter(|row| row.selected) {
+            let tags = row.entry.tags_mut().get_or_insert_with(Vec::new);
+            if !tags.iter().any(|t| t == tag) {
+                tags.push(tag.to_string());
+            }
+        }
+    }
+
+    pub fn accepted_count(&self) -> usize {
+        self.rows
+            .iter()
+            .filter(|row| row.decision == RowDecision::Accept)
+            .count()
+    }
+
+    pub fn duplicate_count(&self) -> usize {
+        self.rows
+            .iter()
+            .filter(|row| row.duplicate_of.is_some())
+            .count()
+    }
+
+    /// Rows that only look like an earlier row of the import.
+    pub fn repeated_count(&self) -> usize {
+        self.rows.iter().filter(|row| row.repeated).count()
+    }
+
+    pub fn into_accepted(self) -> Vec<ImportEntry> {
+        self.rows
+            .into_iter()
+            .filter(|row| row.decision == RowDecision::Accept)
+            .map(|row| row.entry)
+            .collect()
+    }
+}
+
+fn find_existing_duplicate(entry: &ImportEntry, budget_data: &BudgetData) -> Option<String> {
+    match entry {
+        ImportEntry::Transaction(incoming) => budget_data
+            .transactions
+            .iter()
+            .find(|existing| transactions_match(existing, incoming))
+            .map(|existing| format!("{} ({})", existing.description, existing.date)),
+        ImportEntry::CostItem(incoming) => budget_data
+            .cost_items
+            .values()
+            .find(|existing| cost_items_match(existing, incoming))
+            .map(|existing| format!("{} ({})", existing.what, existing.cost_cycle.label())),
+    }
+}
+
+fn is_duplicate(existing: &ImportEntry, incoming: &ImportEntry) -> bool {
+    match (existing, incoming) {
+        (ImportEntry::Transaction(a), ImportEntry::Transaction(b)) => transactions_match(a, b),
+        (ImportEntry::CostItem(a), ImportEntry::CostItem(b)) => cost_items_match(a, b),
+        _ => false,
+    }
+}
+
+fn transactions_match(existing: &Transaction, incoming: &Transaction) -> bool {
+    existing.date == incoming.date
+        && (existing.amount - incoming.amount).abs() < 0.005
+        && existing.category.is_income() == incoming.category.is_income()
+        && descriptions_similar(&existing.description, &incoming.description)
+}
+
+fn cost_items_match(existing: &CostItem, incoming: &CostItem) -> bool {
+    existing.cost_cycle == incoming.cost_cycle
+        && (existing.cost - incoming.cost).abs() < 0.005
+        && descriptions_similar(&existing.what, &incoming.what)
+}
+
+/// Loose comparison that tolerates the noise banks add to descriptions
+/// (card numbers, casing, reference suffixes). Words are compared whole, so
+/// "bar" is not "barber shop".
+pub fn descriptions_similar(a: &str, b: &str) -> bool {
+    let a_words = normalized_words(a);
+    let b_words = normalized_words(b);
+
+    if a_words.is_empty() || b_words.is_empty() {
+        return a_words == b_words;
+    }
+
+    let (short, long) = if a_words.len() <= b_words.len() {
+        (&a_words, &b_words)
+    } else {
+        (&b_words, &a_words)
+    };
+    if long
+        .windows(short.len())
+        .any(|words| words == short.as_slice())
+    {
+        return true;
+    }
+
+    let shared = a_words.iter().filter(|w| b_words.contains(w)).count();
+    let union = a_words.len() + b_words.len() - shared;
+    shared as f32 / union as f32 >= 0.5
+}
+
+fn normalized_words(text: &str) -> Vec<String> {
+    text.to_lowercase()
+        .split(|c: char| !c.is_alphanumeric())
+        .filter(|w| !w.is_empty() && !w.chars().all(|c| c.is_ascii_digit()))
+        .map(|w| w.to_string())
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        BudgetEvent,
+        fixtures::{cost_item, transaction},
+        models::{cost_item::ExpenceCategory, income_item::IncomeCategory},
+    };
+
+    fn coffee() -> ImportEntry {
+        ImportEntry::Transaction(transaction("Coffee", 4.5))
+    }
+
+    #[test]
+    fn descriptions_compare_whole_words() {
+        assert!(descriptions_similar("REMA 1000 OSLO", "Rema Oslo *4321"));
+        assert!(descriptions_similar("Coffee", "coffee shop"));
+        assert!(!descriptions_similar("Bar", "Barber shop"));
+        assert!(!descriptions_similar("Rent", "Current account fee"));
+        assert!(!descriptions_similar("Coffee", "1234"));
+    }
+
+    #[test]
+    fn rows_like_existing_entries_are_skipped() {
+        let mut budget_data = BudgetData::default();
+        budget_data.apply(&BudgetEvent::CommitImport(vec![coffee()]));
+        budget_data.apply(&BudgetEvent::AddCostItem(cost_item("Gym", 30.0)));
+
+        let entries = vec![
+            ImportEntry::Transaction(transaction("COFFEE 0042", 4.5)),
+            ImportEntry::CostItem(cost_item("gym", 30.0)),
+            ImportEntry::Transaction(transaction("Coffee", 5.0)),
+        ];
+        let batch = ImportBatch::new("test".to_string(), entries, &budget_data);
+        assert_eq!(batch.duplicate_count(), 2);
+        assert_eq!(batch.repeated_count(), 0);
+        assert_eq!(batch.accepted_count(), 1);
+        assert_eq!(batch.into_accepted()[0].signed_amount(), -5.0);
+    }
+
+    #[test]
+    fn repeats_within_an_import_are_kept_and_reported() {
+        let batch = ImportBatch::new(
+            "test".to_string(),
+            vec![coffee(), coffee()],
+            &BudgetData::default(),
+        );
+        assert_eq!(batch.accepted_count(), 2);
+        assert_eq!(batch.repeated_count(), 1);
+        assert!(batch.rows[1].duplicate_of.is_some());
+    }
+
+    #[test]
+    fn bulk_changes_touch_only_selected_rows() {
+        let mut budget_data = BudgetData::default();
+        budget_data.apply(&BudgetEvent::CommitImport(vec![coffee()]));
+        let entries = vec![
+            coffee(),
+            ImportEntry::Transaction(transaction("Salary", 3000.0)),
+            ImportEntry::CostItem(cost_item("Gym", 30.0)),
+        ];
+        let mut batch = ImportBatch::new("test".to_string(), entries, &budget_data);
+
+        batch.select_duplicates();
+        assert_eq!(batch.selected_count(), 1);
+        batch.set_decision_for_selected(RowDecision::Accept);
+        assert_eq!(batch.accepted_count(), 3);
+
+        batch.select_all(true);
+        batch.rows[0].selected = false;
+        batch.recategorize_selected(&TransactionCategory::Income(IncomeCategory::Salary));
+        batch.tag_selected("march");
+        batch.tag_selected("march");
+        assert_eq!(
+            batch.rows[0].entry.category(),
+            TransactionCategory::Expense(ExpenceCategory::Groceries)
+        );
+        assert!(batch.rows[0].entry.tags().is_none());
+        assert_eq!(
+            batch.rows[1].entry.category(),
+            TransactionCategory::Income(IncomeCategory::Salary)
+        );
+        // Cost items only take expense categories
+        assert_eq!(
+            batch.rows[2].entry.category(),
+            TransactionCategory::Expense(ExpenceCategory::Other)
+        );
+        assert_eq!(batch.rows[2].entry.tags(), &Some(vec!["march".to_string()]));
+
+        batch.select_all(false);
+        batch.rows[2].selected = true;
+        batch.set_decision_for_selected(RowDecision::Skip);
+        assert_eq!(batch.into_accepted().len(), 2);
+    }
+}
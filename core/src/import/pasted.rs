@@ -0,0 +1,118 @@
+use chrono::NaiveDate;
+
+use crate::{
+    amount::{self, Locale},
+    models::{
+        cost_item::ExpenceCategory,
+        income_item::IncomeCategory,
+        transaction::{Transaction, TransactionCategory},
+    },
+};
+
+const DATE_FORMATS: [&str; 3] = ["%Y-%m-%d", "%d.%m.%Y", "%d/%m/%Y"];
+
+/// Parse rows pasted from a bank statement or spreadsheet. Each line needs a
+/// date, a description and an amount, separated by tabs, semicolons or commas.
+/// Amounts are read as `locale` writes them. Negative amounts are expenses;
+/// positive amounts are income unless `positive_is_expense` is set.
+pub fn parse_rows(
+    text: &str,
+    positive_is_expense: bool,
+    locale: Locale,
+) -> (Vec<Transaction>, Vec<String>) {
+    let mut transactions = Vec::new();
+    let mut errors = Vec::new();
+
+    for (line_no, line) in text.lines().enumerate() {
+        if line.trim().is_empty() {
+            continue;
+        }
+
+        match parse_row(line, positive_is_expense, locale) {
+            Ok(transaction) => transactions.push(transaction),
+            Err(err) => errors.push(format!("Line {}: {}", line_no + 1, err)),
+        }
+    }
+
+    (transactions, errors)
+}
+
+fn parse_row(line: &str, positive_is_expense: bool, locale: Locale) -> Result<Transaction, String> {
+    let separator = if line.contains('\t') {
+        '\t'
+    } else if line.contains(';') {
+        ';'
+    } else {
+        ','
+    };
+
+    let fields: Vec<&str> = line.split(separator).map(|f| f.trim()).collect();
+
+    let date_idx = fields
+        .iter()
+        .position(|f| parse_date(f).is_some())
+        .ok_or("no date found")?;
+    let date = parse_date(fields[date_idx]).ok_or("no date found")?;
+
+    let (amount_idx, signed_amount) = fields
+        .iter()
+        .enumerate()
+        .rev()
+        .filter(|(i, _)| *i != date_idx)
+        .find_map(|(i, f)| Some((i, amount::parse(f, locale)?)))
+        .ok_or("no amount found")?;
+
+    let description = fields
+        .iter()
+        .enumerate()
+        .filter(|(i, f)| *i != date_idx && *i != amount_idx && !f.is_empty())
+        .map(|(_, f)| *f)
+        .collect::<Vec<_>>()
+        .join(" ");
+
+    if description.is_empty() {
+        return Err("no description found".to_string());
+    }
+
+    let is_expense = signed_amount < 0.0 || positive_is_expense;
+    let category = if is_expense {
+        TransactionCategory::Expense(ExpenceCategory::guess(&description))
+    } else {
+        TransactionCategory::Income(IncomeCategory::guess(&description))
+    };
+
+    Ok(Transaction {
+        id: 0,
+        date,
+        description,
+        amount: signed_amount.abs(),
+        category,
+        tags: None,
+    })
+}
+
+pub fn parse_date(text: &str) -> Option<NaiveDate> {
+    DATE_FORMATS
+        .iter()
+        .find_map(|format| NaiveDate::parse_from_str(text.trim(), format).ok())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn amounts_are_read_in_the_locale() {
+        let text = "2025-03-01\tCoffee\t-12,50\n01.03.2025;Rema 1000;-1 200,50 kr\n";
+        let (transactions, errors) = parse_rows(text, false, Locale::Nordic);
+        assert!(errors.is_empty(), "{errors:?}");
+        let amounts: Vec<f32> = transactions.iter().map(|t| t.amount).collect();
+        assert_eq!(amounts, [12.5, 1200.5]);
+        assert_eq!(transactions[1].description, "Rema 1000");
+        assert!(!transactions[1].category.is_income());
+
+        let (transactions, _) = parse_rows("2025-03-01\tSalary\t$3,000.00", false, Locale::English);
+        assert_eq!(transactions[0].amount, 3000.0);
+        assert!(transactions[0].category.is_income());
+    }
+}
@@ -0,0 +1,349 @@
+use std::{
+    io::{Cursor, Read, Seek},
+    path::Path,
+};
+
+use calamine::{Data, Reader, Sheets, open_workbook_auto, open_workbook_auto_from_rs};
+
+use crate::{
+    amount::{self, Locale},
+    models::cost_item::{CostCycle, CostItem, ExpenceCategory},
+};
+
+/// All sheets of an XLSX/ODS workbook with every cell rendered as text.
+pub struct Workbook {
+    pub sheets: Vec<Sheet>,
+}
+
+pub struct Sheet {
+    pub name: String,
+    pub rows: Vec<Vec<String>>,
+    /// Cells the sheet stores as numbers, which are read as they are rather
+    /// than in the locale.
+    pub numbers: Vec<Vec<Option<f64>>>,
+    /// The number the sheet shows for each row; empty rows are left out of
+    /// `rows`.
+    pub row_numbers: Vec<usize>,
+}
+
+impl Sheet {
+    pub fn column_count(&self) -> usize {
+        self.rows.iter().map(|row| row.len()).max().unwrap_or(0)
+    }
+
+    /// The amount in a cell: its number, or its text as written in `locale`,
+    /// e.g. "kr 1 200,50" or "$45".
+    fn amount(&self, row: usize, col: usize, locale: Locale) -> Option<f32> {
+        if let Some(number) = self
+            .numbers
+            .get(row)
+            .and_then(|r| r.get(col))
+            .copied()
+            .flatten()
+        {
+            return Some(number as f32);
+        }
+        amount::parse(self.rows.get(row)?.get(col)?, locale)
+    }
+}
+
+pub fn open(path: &Path) -> Result<Workbook, String> {
+    let workbook = open_workbook_auto(path).map_err(|e| format!("{}: {}", path.display(), e))?;
+    read_sheets(workbook)
+}
+
+/// A workbook read into memory, e.g. picked in the browser; `name` is only
+/// for errors.
+pub fn from_bytes(name: &str, data: Vec<u8>) -> Result<Workbook, String> {
+    let workbook =
+        open_workbook_auto_from_rs(Cursor::new(data)).map_err(|e| format!("{}: {}", name, e))?;
+    read_sheets(workbook)
+}
+
+fn read_sheets<RS: Read + Seek>(mut workbook: Sheets<RS>) -> Result<Workbook, String> {
+    let mut sheets = Vec::new();
+    for name in workbook.sheet_names() {
+        let range = workbook
+            .worksheet_range(&name)
+            .map_err(|e| format!("{}: {}", name, e))?;
+        let first_row = range.start().map_or(0, |(row, _)| row as usize);
+        let (kept, (rows, numbers)): (Vec<usize>, (Vec<_>, Vec<_>)) = range
+            .rows()
+            .enumerate()
+            .map(|(i, row)| {
+                let text: Vec<String> = row
+                    .iter()
+                    .map(|cell| cell.to_string().trim().to_string())
+                    .collect();
+                let numbers = row
+                    .iter()
+                    .map(|cell| match cell {
+                        Data::Float(number) => Some(*number),
+                        Data::Int(number) => Some(*number as f64),
+                        _ => None,
+                    })
+                    .collect();
+                (first_row + i + 1, (text, numbers))
+            })
+            .filter(|(_, (row, _))| row.iter().any(|cell| !cell.is_empty()))
+            .unzip();
+        sheets.push(Sheet {
+            name,
+            rows,
+            numbers,
+            row_numbers: kept,
+        });
+    }
+
+    Ok(Workbook { sheets })
+}
+
+/// Which sheet column feeds which `CostItem` field. Only name and amount are
+/// required; a missing cycle or category is inferred from the name.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct ColumnMapping {
+    pub header_row: bool,
+    pub name: Option<usize>,
+    pub amount: Option<usize>,
+    pub cycle: Option<usize>,
+    pub category: Option<usize>,
+}
+
+impl ColumnMapping {
+    /// Guess the mapping from the first row of the sheet. Headers named
+    /// exactly like a field win; the rest are matched by what they contain,
+    /// so "Cost" and "Cost cycle" feed the amount and the cycle.
+    pub fn guess(sheet: &Sheet, locale: Locale) -> Self {
+        let Some(first) = sheet.rows.first() else {
+            return Self::default();
+        };
+        let headers: Vec<String> = first.iter().map(|h| h.trim().to_lowercase()).collect();
+        let fields: [&[&str]; 4] = [
+            &["what", "name", "item", "description", "post", "expense"],
+            &["cost", "amount", "price", "sum", "beløp", "belopp"],
+            &["cycle", "frequency", "period", "interval"],
+            &["category", "kategori", "type", "group"],
+        ];
+
+        let mut found =
+            fields.map(|names| headers.iter().position(|h| names.contains(&h.as_str())));
+        for (field, names) in fields.iter().enumerate() {
+            if found[field].is_none() {
+                found[field] = (0..headers.len()).find(|col| {
+                    !found.contains(&Some(*col))
+                        && names.iter().any(|name| headers[*col].contains(name))
+                });
+            }
+        }
+        let [name, amount, cycle, category] = found;
+
+        let header_row = name.is_some() || amount.is_some();
+
+        if header_row {
+            Self {
+                header_row,
+                name,
+                amount,
+                cycle,
+                category,
+            }
+        } else {
+            // No recognizable header: take the first text column as the name
+            // and the first numeric column as the amount.
+            Self {
+                header_row,
+                name: (0..first.len()).find(|&c| sheet.amount(0, c, locale).is_none()),
+                amount: (0..first.len()).find(|&c| sheet.amount(0, c, locale).is_some()),
+                cycle: None,
+                category: None,
+            }
+        }
+    }
+
+    pub fn is_complete(&self) -> bool {
+        self.name.is_some() && self.amount.is_some()
+    }
+}
+
+/// Cost items from the mapped columns, with amounts typed as text read in
+/// `locale`. Rows whose amount can't be read are reported, not imported.
+pub fn to_cost_items(
+    sheet: &Sheet,
+    mapping: &ColumnMapping,
+    locale: Locale,
+) -> (Vec<CostItem>, Vec<String>) {
+    let mut items = Vec::new();
+    let mut errors = Vec::new();
+
+    let (Some(name_col), Some(amount_col)) = (mapping.name, mapping.amount) else {
+        errors.push("Pick a name and an amount column".to_string());
+        return (items, errors);
+    };
+
+    let skip = if mapping.header_row { 1 } else { 0 };
+    for (row_no, row) in sheet.rows.iter().enumerate().skip(skip) {
+        let cell = |col: Option<usize>| col.and_then(|c| row.get(c)).map(|s| s.as_str());
+
+        let what = cell(Some(name_col)).unwrap_or("").to_string();
+        if what.is_empty() {
+            continue;
+        }
+
+        let shown_no = sheet.row_numbers.get(row_no).copied().unwrap_or(row_no + 1);
+        let Some(cost) = sheet.amount(row_no, amount_col, locale) else {
+            match cell(Some(amount_col)).filter(|text| !text.is_empty()) {
+                Some(text) => errors.push(format!(
+                    "Row {}: '{}' is not an amount, for '{}'",
+                    shown_no, text, what
+                )),
+                None => errors.push(format!("Row {}: no amount for '{}'", shown_no, what)),
+            }
+            continue;
+        };
+        if cost < 0.0 {
+            errors.push(format!(
+                "Row {}: the cost of '{}' is negative ({})",
+                shown_no,
+                what,
+                amount::format(cost, locale)
+            ));
+            continue;
+        }
+
+        let cost_cycle = cell(mapping.cycle)
+            .and_then(|text| CostCycle::from_name(text).or_else(|| CostCycle::guess(text)))
+            .or_else(|| CostCycle::guess(&what))
+            .unwrap_or(CostCycle::Monthly);
+
+        let cost_category = cell(mapping.category)
+            .and_then(ExpenceCategory::from_name)
+            .unwrap_or_else(|| {
+                let hint = format!("{} {}", what, cell(mapping.category).unwrap_or(""));
+                ExpenceCategory::guess(&hint)
+            });
+
+        items.push(CostItem {
+            id: 0,
+            what,
+            cost,
+            cost_cycle,
+            cost_category,
+            tags: None,
+        });
+    }
+
+    (items, errors)
+}
+
+/// Spreadsheet-style column label: 0 -> A, 25 -> Z, 26 -> AA.
+pub fn column_label(mut index: usize) -> String {
+    let mut label = String::new();
+    loop {
+        label.insert(0, (b'A' + (index % 26) as u8) as char);
+        if index < 26 {
+            break;
+        }
+        index = index / 26 - 1;
+    }
+    label
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn text(cells: &[&str]) -> Vec<String> {
+        cells.iter().map(|c| c.to_string()).collect()
+    }
+
+    /// A sheet of text cells, numbered from the top.
+    fn sheet(rows: &[&[&str]]) -> Sheet {
+        Sheet {
+            name: "Budget".to_string(),
+            rows: rows.iter().map(|row| text(row)).collect(),
+            numbers: Vec::new(),
+            row_numbers: (1..=rows.len()).collect(),
+        }
+    }
+
+    #[test]
+    fn amounts_are_read_in_the_locale() {
+        let sheet = Sheet {
+            name: "Budget".to_string(),
+            rows: vec![
+                text(&["What", "Cost"]),
+                text(&["Rent", "1.200,50 kr"]),
+                text(&["Coffee", "12,5"]),
+                text(&["Gym", "1.234"]),
+                text(&["Phone", "twelve"]),
+                text(&["Bus", ""]),
+            ],
+            numbers: vec![
+                vec![None, None],
+                vec![None, None],
+                vec![None, None],
+                // A number cell keeps its decimals
+                vec![None, Some(1.234)],
+            ],
+            row_numbers: (1..=6).collect(),
+        };
+        let mapping = ColumnMapping::guess(&sheet, Locale::Nordic);
+        assert_eq!((mapping.name, mapping.amount), (Some(0), Some(1)));
+
+        let (items, errors) = to_cost_items(&sheet, &mapping, Locale::Nordic);
+        let costs: Vec<f32> = items.iter().map(|item| item.cost).collect();
+        assert_eq!(costs, [1200.5, 12.5, 1.234]);
+        assert_eq!(
+            errors,
+            [
+                "Row 5: 'twelve' is not an amount, for 'Phone'",
+                "Row 6: no amount for 'Bus'"
+            ]
+        );
+    }
+
+    #[test]
+    fn exact_headers_win_over_partial_ones() {
+        let sheet = sheet(&[
+            &["Cost cycle", "What", "Cost", "Type of cost"],
+            &["Monthly", "Rent", "1200", "Housing"],
+        ]);
+        let mapping = ColumnMapping::guess(&sheet, Locale::English);
+        assert_eq!(
+            mapping,
+            ColumnMapping {
+                header_row: true,
+                name: Some(1),
+                amount: Some(2),
+                cycle: Some(0),
+                category: Some(3),
+            }
+        );
+    }
+
+    #[test]
+    fn errors_name_the_row_the_sheet_shows() {
+        // The used range starts on row 3 and row 5 is empty
+        let mut sheet = sheet(&[
+            &["What", "Cost"],
+            &["Rent", "1200"],
+            &["Refund", "-40"],
+            &["Gym", "?"],
+        ]);
+        sheet.row_numbers = vec![3, 4, 6, 7];
+
+        let (items, errors) = to_cost_items(
+            &sheet,
+            &ColumnMapping::guess(&sheet, Locale::English),
+            Locale::English,
+        );
+        assert_eq!(items.len(), 1);
+        assert_eq!(
+            errors,
+            [
+                "Row 6: the cost of 'Refund' is negative (-40.00)",
+                "Row 7: '?' is not an amount, for 'Gym'"
+            ]
+        );
+    }
+}
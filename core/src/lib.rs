@@ -0,0 +1,21 @@
+//! The budget without a UI: its data and the events that change it, storage,
+//! import and export, reports, and parsing of amounts and quick-add lines.
+//! Front-ends turn what the user does into `BudgetEvent`s and show
+//! `BudgetData`.
+
+pub mod amount;
+pub mod budget;
+pub mod event;
+pub mod export;
+#[cfg(test)]
+mod fixtures;
+pub mod import;
+pub mod models;
+pub mod quick_add;
+pub mod report;
+pub mod settings;
+pub mod storage;
+pub mod undo;
+
+pub use budget::BudgetData;
+pub use event::BudgetEvent;
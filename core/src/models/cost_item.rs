@@ -0,0 +1,189 @@
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub enum CostCycle {
+    Daily,
+    Weekly,
+    Monthly,
+    Yearly,
+}
+
+impl CostCycle {
+    pub const ALL: [CostCycle; 4] = [
+        CostCycle::Daily,
+        CostCycle::Weekly,
+        CostCycle::Monthly,
+        CostCycle::Yearly,
+    ];
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            CostCycle::Daily => "Daily",
+            CostCycle::Weekly => "Weekly",
+            CostCycle::Monthly => "Monthly",
+            CostCycle::Yearly => "Yearly",
+        }
+    }
+
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name.trim().to_lowercase().as_str() {
+            "daily" | "day" | "d" => Some(CostCycle::Daily),
+            "weekly" | "week" | "w" => Some(CostCycle::Weekly),
+            "monthly" | "month" | "m" => Some(CostCycle::Monthly),
+            "yearly" | "year" | "annual" | "y" => Some(CostCycle::Yearly),
+            _ => None,
+        }
+    }
+
+    /// Guess a cycle from free text using the quick add keywords.
+    pub fn guess(text: &str) -> Option<Self> {
+        let input = text.to_lowercase();
+        if input.contains("daily") {
+            Some(CostCycle::Daily)
+        } else if input.contains("weekly") {
+            Some(CostCycle::Weekly)
+        } else if input.contains("yearly") || input.contains("annual") {
+            Some(CostCycle::Yearly)
+        } else if input.contains("monthly") {
+            Some(CostCycle::Monthly)
+        } else {
+            None
+        }
+    }
+
+    /// Convert an amount paid every cycle into its monthly equivalent.
+    pub fn to_monthly(&self, amount: f32) -> f32 {
+        match self {
+            CostCycle::Daily => amount * 30.44,
+            CostCycle::Weekly => amount * 4.348,
+            CostCycle::Monthly => amount,
+            CostCycle::Yearly => amount / 12.0,
+        }
+    }
+
+    pub fn to_yearly(&self, amount: f32) -> f32 {
+        self.to_monthly(amount) * 12.0
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub enum ExpenceCategory {
+    Housing,
+    Transportation,
+    Groceries,
+    Healthcare,
+    PersonalCare,
+    DiningOut,
+    Entertainment,
+    Shopping,
+    Savings,
+    DebtPayments,
+    Utilities,
+    Insurance,
+    Other,
+}
+
+impl ExpenceCategory {
+    pub const ALL: [ExpenceCategory; 13] = [
+        ExpenceCategory::Housing,
+        ExpenceCategory::Transportation,
+        ExpenceCategory::Groceries,
+        ExpenceCategory::Healthcare,
+        ExpenceCategory::PersonalCare,
+        ExpenceCategory::DiningOut,
+        ExpenceCategory::Entertainment,
+        ExpenceCategory::Shopping,
+        ExpenceCategory::Savings,
+        ExpenceCategory::DebtPayments,
+        ExpenceCategory::Utilities,
+        ExpenceCategory::Insurance,
+        ExpenceCategory::Other,
+    ];
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            ExpenceCategory::Housing => "Housing",
+            ExpenceCategory::Transportation => "Transportation",
+            ExpenceCategory::Groceries => "Groceries",
+            ExpenceCategory::Healthcare => "Healthcare",
+            ExpenceCategory::PersonalCare => "Personal Care",
+            ExpenceCategory::DiningOut => "Dining Out",
+            ExpenceCategory::Entertainment => "Entertainment",
+            ExpenceCategory::Shopping => "Shopping",
+            ExpenceCategory::Savings => "Savings",
+            ExpenceCategory::DebtPayments => "Debt Payments",
+            ExpenceCategory::Utilities => "Utilities",
+            ExpenceCategory::Insurance => "Insurance",
+            ExpenceCategory::Other => "Other",
+        }
+    }
+
+    /// Match either the display label or the compact name, ignoring case and spaces.
+    pub fn from_name(name: &str) -> Option<Self> {
+        let wanted = name.replace(' ', "").to_lowercase();
+        Self::ALL
+            .into_iter()
+            .find(|c| c.label().replace(' ', "").to_lowercase() == wanted)
+    }
+
+    /// Guess a category from free text using the quick add keywords.
+    pub fn guess(text: &str) -> Self {
+        let input = text.to_lowercase();
+        if input.contains("rent") || input.contains("mortgage") || input.contains("utilities") {
+            ExpenceCategory::Housing
+        } else if input.contains("transport")
+            || input.contains("gas")
+            || input.contains("car")
+            || input.contains("bus")
+            || input.contains("ruter")
+        {
+            ExpenceCategory::Transportation
+        } else if input.contains("food") || input.contains("grocery") || input.contains("groceries")
+        {
+            ExpenceCategory::Groceries
+        } else if input.contains("restaurant")
+            || input.contains("dining")
+            || input.contains("coffee")
+            || input.contains("lunch")
+        {
+            ExpenceCategory::DiningOut
+        } else if input.contains("movie")
+            || input.contains("entertainment")
+            || input.contains("netflix")
+        {
+            ExpenceCategory::Entertainment
+        } else if input.contains("doctor") || input.contains("health") || input.contains("medicine")
+        {
+            ExpenceCategory::Healthcare
+        } else if input.contains("save") || input.contains("saving") {
+            ExpenceCategory::Savings
+        } else if input.contains("insurance") || input.contains("insure") {
+            ExpenceCategory::Insurance
+        } else {
+            ExpenceCategory::Other
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct CostItem {
+    pub id: u64,
+    pub what: String,
+    pub cost: f32,
+    pub cost_cycle: CostCycle,
+    pub cost_category: ExpenceCategory,
+    pub tags: Option<Vec<String>>,
+}
+
+impl Default for CostItem {
+    fn default() -> Self {
+        Self {
+            id: 0,
+            what: String::new(),
+            cost: 0.0,
+            cost_cycle: CostCycle::Weekly,
+            cost_category: ExpenceCategory::Other,
+            tags: None,
+        }
+    }
+}
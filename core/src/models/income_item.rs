@@ -0,0 +1,106 @@
+use serde::{Deserialize, Serialize};
+
+use crate::models::cost_item::CostCycle;
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub enum IncomeCategory {
+    Salary,
+    Freelance,
+    Investment,
+    SideHustle,
+    Bonus,
+    Gift,
+    Other,
+}
+
+impl IncomeCategory {
+    pub const ALL: [IncomeCategory; 7] = [
+        IncomeCategory::Salary,
+        IncomeCategory::Freelance,
+        IncomeCategory::Investment,
+        IncomeCategory::SideHustle,
+        IncomeCategory::Bonus,
+        IncomeCategory::Gift,
+        IncomeCategory::Other,
+    ];
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            IncomeCategory::Salary => "Salary",
+            IncomeCategory::Freelance => "Freelance",
+            IncomeCategory::Investment => "Investment",
+            IncomeCategory::SideHustle => "Side Hustle",
+            IncomeCategory::Bonus => "Bonus",
+            IncomeCategory::Gift => "Gift",
+            IncomeCategory::Other => "Other",
+        }
+    }
+
+    /// Match either the display label or the compact name, ignoring case and spaces.
+    pub fn from_name(name: &str) -> Option<Self> {
+        let wanted = name.replace(' ', "").to_lowercase();
+        Self::ALL
+            .into_iter()
+            .find(|c| c.label().replace(' ', "").to_lowercase() == wanted)
+    }
+
+    /// Guess a category from free text using the quick add keywords.
+    pub fn guess(text: &str) -> Self {
+        let input = text.to_lowercase();
+        if input.contains("salary")
+            || input.contains("wage")
+            || input.contains("job")
+            || input.contains("work")
+        {
+            IncomeCategory::Salary
+        } else if input.contains("freelance")
+            || input.contains("contract")
+            || input.contains("consulting")
+        {
+            IncomeCategory::Freelance
+        } else if input.contains("invest")
+            || input.contains("dividend")
+            || input.contains("stock")
+            || input.contains("crypto")
+        {
+            IncomeCategory::Investment
+        } else if input.contains("business") || input.contains("side") || input.contains("hustle") {
+            IncomeCategory::SideHustle
+        } else if input.contains("bonus") || input.contains("commission") || input.contains("tip") {
+            IncomeCategory::Bonus
+        } else if input.contains("gift")
+            || input.contains("present")
+            || input.contains("inheritance")
+        {
+            IncomeCategory::Gift
+        } else {
+            IncomeCategory::Other
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct IncomeItem {
+    /// Budgets saved before income items had ids read as 0, see
+    /// `BudgetData::number_income_items`.
+    #[serde(default)]
+    pub id: u64,
+    pub source: String,
+    pub category: IncomeCategory,
+    pub amount: f32,
+    pub income_cycle: CostCycle,
+    pub tags: Option<Vec<String>>,
+}
+
+impl Default for IncomeItem {
+    fn default() -> Self {
+        Self {
+            id: 0,
+            source: String::new(),
+            category: IncomeCategory::Salary,
+            amount: 0.0,
+            income_cycle: CostCycle::Monthly,
+            tags: None,
+        }
+    }
+}
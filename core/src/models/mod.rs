@@ -0,0 +1,4 @@
+pub mod cost_item;
+pub mod income_item;
+pub mod template;
+pub mod transaction;
@@ -0,0 +1,111 @@
+use crate::models::{
+    cost_item::{CostCycle, CostItem, ExpenceCategory},
+    income_item::{IncomeCategory, IncomeItem},
+};
+
+/// Starting points for a new budget. Templates only lay out the usual items;
+/// every amount starts at zero.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum BudgetTemplate {
+    Empty,
+    Personal,
+    Household,
+    RentalProperty,
+    /// Cost and income items of the open budget, without its transactions.
+    CopyCurrent,
+}
+
+impl BudgetTemplate {
+    pub const ALL: [BudgetTemplate; 5] = [
+        BudgetTemplate::Empty,
+        BudgetTemplate::Personal,
+        BudgetTemplate::Household,
+        BudgetTemplate::RentalProperty,
+        BudgetTemplate::CopyCurrent,
+    ];
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            BudgetTemplate::Empty => "Empty",
+            BudgetTemplate::Personal => "Personal",
+            BudgetTemplate::Household => "Household",
+            BudgetTemplate::RentalProperty => "Rental property",
+            BudgetTemplate::CopyCurrent => "Copy of the current budget",
+        }
+    }
+
+    fn cost_layout(&self) -> &'static [(&'static str, CostCycle, ExpenceCategory)] {
+        use CostCycle::*;
+        use ExpenceCategory::*;
+        match self {
+            BudgetTemplate::Empty | BudgetTemplate::CopyCurrent => &[],
+            BudgetTemplate::Personal => &[
+                ("Rent", Monthly, Housing),
+                ("Groceries", Weekly, Groceries),
+                ("Public transport", Monthly, Transportation),
+                ("Phone", Monthly, Utilities),
+                ("Streaming", Monthly, Entertainment),
+                ("Savings", Monthly, Savings),
+            ],
+            BudgetTemplate::Household => &[
+                ("Mortgage", Monthly, Housing),
+                ("Electricity", Monthly, Utilities),
+                ("Internet", Monthly, Utilities),
+                ("Groceries", Weekly, Groceries),
+                ("Fuel", Weekly, Transportation),
+                ("Home insurance", Yearly, Insurance),
+                ("Car insurance", Yearly, Insurance),
+                ("Childcare", Monthly, Other),
+            ],
+            BudgetTemplate::RentalProperty => &[
+                ("Mortgage", Monthly, Housing),
+                ("Property tax", Yearly, Housing),
+                ("Landlord insurance", Yearly, Insurance),
+                ("Maintenance", Monthly, Other),
+                ("Property management", Monthly, Other),
+                ("Common utilities", Monthly, Utilities),
+            ],
+        }
+    }
+
+    fn income_layout(&self) -> &'static [(&'static str, IncomeCategory)] {
+        match self {
+            BudgetTemplate::Empty | BudgetTemplate::CopyCurrent => &[],
+            BudgetTemplate::Personal => &[("Salary", IncomeCategory::Salary)],
+            BudgetTemplate::Household => &[
+                ("Salary 1", IncomeCategory::Salary),
+                ("Salary 2", IncomeCategory::Salary),
+            ],
+            BudgetTemplate::RentalProperty => &[("Rent", IncomeCategory::Investment)],
+        }
+    }
+
+    /// Cost items with ids counting from zero.
+    pub fn cost_items(&self) -> Vec<CostItem> {
+        self.cost_layout()
+            .iter()
+            .enumerate()
+            .map(|(id, (what, cycle, category))| CostItem {
+                id: id as u64,
+                what: what.to_string(),
+                cost_cycle: cycle.clone(),
+                cost_category: category.clone(),
+                ..CostItem::default()
+            })
+            .collect()
+    }
+
+    /// Income items with ids counting from zero.
+    pub fn income_items(&self) -> Vec<IncomeItem> {
+        self.income_layout()
+            .iter()
+            .enumerate()
+            .map(|(id, (source, category))| IncomeItem {
+                id: id as u64,
+                source: source.to_string(),
+                category: category.clone(),
+                ..IncomeItem::default()
+            })
+            .collect()
+    }
+}
@@ -0,0 +1,48 @@
+use chrono::NaiveDate;
+use serde::{Deserialize, Serialize};
+
+use crate::models::{cost_item::ExpenceCategory, income_item::IncomeCategory};
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub enum TransactionCategory {
+    Expense(ExpenceCategory),
+    Income(IncomeCategory),
+}
+
+impl TransactionCategory {
+    pub fn label(&self) -> &'static str {
+        match self {
+            TransactionCategory::Expense(category) => category.label(),
+            TransactionCategory::Income(category) => category.label(),
+        }
+    }
+
+    pub fn is_income(&self) -> bool {
+        matches!(self, TransactionCategory::Income(_))
+    }
+}
+
+/// A single dated money movement, e.g. a row from a bank statement.
+/// The category decides the direction of `amount`: money spent for an
+/// expense, money received for income. A negative amount goes the other
+/// way, e.g. a refund, and lowers its category's total.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Transaction {
+    pub id: u64,
+    pub date: NaiveDate,
+    pub description: String,
+    pub amount: f32,
+    pub category: TransactionCategory,
+    pub tags: Option<Vec<String>>,
+}
+
+impl Transaction {
+    /// Money in as positive, money out as negative.
+    pub fn signed_amount(&self) -> f32 {
+        if self.category.is_income() {
+            self.amount
+        } else {
+            -self.amount
+        }
+    }
+}
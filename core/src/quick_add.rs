@@ -0,0 +1,584 @@
+//! Quick add: one line of text such as `Coffee 5 \daily @dining` turned into
+//! an entry. The line is read word by word: `\cycle`, `@category` and `#tag`
+//! go by their marker, a number is the amount and a date (`2024-03-01` or
+//! `1.3.2024`) makes it a one-off transaction. The amount is read as the
+//! settings' locale writes it and may span words, as in `1 200 kr`.
+//! Everything after `--` is a note. The other words are the name, kept as
+//! typed. Without `\` or `@` the cycle and category are guessed from
+//! keywords.
+
+use std::ops::Range;
+
+use chrono::NaiveDate;
+
+use crate::{
+    BudgetEvent,
+    amount::{self, Locale},
+    import::ImportEntry,
+    models::{
+        cost_item::{CostCycle, CostItem, ExpenceCategory},
+        income_item::{IncomeCategory, IncomeItem},
+        transaction::{Transaction, TransactionCategory},
+    },
+};
+
+/// Whether the line adds to spending or to income.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Kind {
+    Expense,
+    Income,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum TokenKind {
+    Name,
+    Amount,
+    Cycle,
+    Category,
+    Tag,
+    Date,
+    Note,
+}
+
+/// A word of the line and what it was read as. Spans are byte ranges, for
+/// highlighting the line as it is typed.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Token {
+    pub kind: TokenKind,
+    pub span: Range<usize>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct ParseError {
+    pub span: Range<usize>,
+    pub message: String,
+}
+
+/// A quick add line taken apart. Nothing is added while it has errors.
+#[derive(Debug, Clone, PartialEq)]
+pub struct QuickAdd {
+    pub kind: Kind,
+    pub name: String,
+    pub amount: Option<f32>,
+    pub cycle: CostCycle,
+    pub category: TransactionCategory,
+    pub tags: Vec<String>,
+    pub date: Option<NaiveDate>,
+    pub note: Option<String>,
+    pub tokens: Vec<Token>,
+    pub errors: Vec<ParseError>,
+    /// Amounts were read, and are previewed, as written in this locale.
+    pub locale: Locale,
+}
+
+pub fn parse(text: &str, kind: Kind, locale: Locale) -> QuickAdd {
+    let mut line = QuickAdd {
+        kind,
+        name: String::new(),
+        amount: None,
+        cycle: CostCycle::Monthly,
+        category: match kind {
+            Kind::Expense => TransactionCategory::Expense(ExpenceCategory::Other),
+            Kind::Income => TransactionCategory::Income(IncomeCategory::Other),
+        },
+        tags: Vec::new(),
+        date: None,
+        note: None,
+        tokens: Vec::new(),
+        errors: Vec::new(),
+        locale,
+    };
+    let mut cycle_span = None;
+    let mut category_span = None;
+    // Words like "monthly" set the cycle only when there is no `\cycle`
+    let mut cycle_keywords = Vec::new();
+
+    let words: Vec<(usize, &str)> = words(text).collect();
+    let mut next = 0;
+    while let Some(&(start, word)) = words.get(next) {
+        next += 1;
+        let span = start..start + word.len();
+        if word.starts_with("--") {
+            let note = text[start + 2..].trim();
+            if !note.is_empty() {
+                line.note = Some(note.to_string());
+            }
+            line.push(TokenKind::Note, start..text.len());
+            break;
+        }
+
+        if let Some(name) = word.strip_prefix('\\') {
+            line.push(TokenKind::Cycle, span.clone());
+            match CostCycle::from_name(name) {
+                _ if cycle_span.is_some() => line.error(span.clone(), "more than one cycle"),
+                Some(cycle) => line.cycle = cycle,
+                None if name.is_empty() => line.error(span.clone(), "cycle missing after \\"),
+                None => line.error(span.clone(), format!("unknown cycle \"{}\"", name)),
+            }
+            cycle_span.get_or_insert(span);
+        } else if let Some(name) = word.strip_prefix('@') {
+            line.push(TokenKind::Category, span.clone());
+            let category = match kind {
+                Kind::Expense => expense_category(name).map(TransactionCategory::Expense),
+                Kind::Income => income_category(name).map(TransactionCategory::Income),
+            };
+            match category {
+                _ if category_span.is_some() => line.error(span.clone(), "more than one category"),
+                Some(category) => line.category = category,
+                None if name.is_empty() => line.error(span.clone(), "category missing after @"),
+                None => line.error(span.clone(), format!("unknown category \"{}\"", name)),
+            }
+            category_span.get_or_insert(span);
+        } else if let Some(tag) = word.strip_prefix('#') {
+            line.push(TokenKind::Tag, span.clone());
+            if tag.is_empty() {
+                line.error(span, "tag missing after #");
+            } else if !line.tags.iter().any(|t| t.eq_ignore_ascii_case(tag)) {
+                line.tags.push(tag.to_string());
+            }
+        } else if let Some(date) = parse_date(word) {
+            line.push(TokenKind::Date, span.clone());
+            if line.date.is_some() {
+                line.error(span, "more than one date");
+            }
+            line.date.get_or_insert(date);
+        } else if let Some((amount, used)) = amount_at(text, &words[next - 1..], locale) {
+            let (last, last_word) = words[next + used - 2];
+            let span = start..last + last_word.len();
+            next += used - 1;
+            line.push(TokenKind::Amount, span.clone());
+            if line.amount.is_some() {
+                line.error(span, "more than one amount");
+            }
+            line.amount.get_or_insert(amount);
+        } else if looks_like_date(word) {
+            line.push(TokenKind::Date, span.clone());
+            line.error(span, format!("\"{}\" is not a date", word));
+        } else {
+            let keyword = word.to_lowercase();
+            if matches!(
+                keyword.as_str(),
+                "daily" | "weekly" | "monthly" | "yearly" | "annual"
+            ) {
+                cycle_keywords.push(line.tokens.len());
+            }
+            line.push(TokenKind::Name, span);
+        }
+    }
+
+    // A dated entry happens once, so a cycle word there is just part of the name
+    if let Some(span) = &cycle_span {
+        if line.date.is_some() {
+            line.error(span.clone(), "a dated entry happens once and has no cycle");
+        }
+    } else if line.date.is_none()
+        && let Some(&index) = cycle_keywords.first()
+    {
+        let token = &mut line.tokens[index];
+        token.kind = TokenKind::Cycle;
+        line.cycle = CostCycle::from_name(&text[token.span.clone()]).unwrap_or(CostCycle::Monthly);
+    }
+
+    line.name = line
+        .tokens
+        .iter()
+        .filter(|token| token.kind == TokenKind::Name)
+        .map(|token| &text[token.span.clone()])
+        .collect::<Vec<_>>()
+        .join(" ");
+
+    if category_span.is_none() {
+        line.category = match kind {
+            Kind::Expense => TransactionCategory::Expense(ExpenceCategory::guess(&line.name)),
+            Kind::Income => TransactionCategory::Income(IncomeCategory::guess(&line.name)),
+        };
+    }
+    let end = text.trim_end().len();
+    if line.name.is_empty() {
+        line.error(end..end, "missing name");
+    }
+    if line.amount.is_none() {
+        line.error(end..end, "missing amount");
+    }
+    line.errors.sort_by_key(|error| error.span.start);
+    line
+}
+
+/// An expense that recurs; dated lines are refused, as they are transactions.
+pub fn parse_expense(text: &str, locale: Locale) -> Result<CostItem, String> {
+    parse(text, Kind::Expense, locale).cost_item()
+}
+
+pub fn parse_income(text: &str, locale: Locale) -> Result<IncomeItem, String> {
+    parse(text, Kind::Income, locale).income_item()
+}
+
+impl QuickAdd {
+    fn push(&mut self, kind: TokenKind, span: Range<usize>) {
+        self.tokens.push(Token { kind, span });
+    }
+
+    fn error(&mut self, span: Range<usize>, message: impl Into<String>) {
+        self.errors.push(ParseError {
+            span,
+            message: message.into(),
+        });
+    }
+
+    fn check(&self) -> Result<(), String> {
+        if self.errors.is_empty() {
+            return Ok(());
+        }
+        let messages: Vec<&str> = self.errors.iter().map(|e| e.message.as_str()).collect();
+        Err(messages.join(", "))
+    }
+
+    /// The name, with the note after it in parentheses.
+    fn title(&self) -> String {
+        match &self.note {
+            Some(note) => format!("{} ({})", self.name, note),
+            None => self.name.clone(),
+        }
+    }
+
+    fn tag_list(&self) -> Option<Vec<String>> {
+        (!self.tags.is_empty()).then(|| self.tags.clone())
+    }
+
+    fn undated(&self) -> Result<(), String> {
+        match self.date {
+            Some(_) => Err("a dated entry is a transaction, not a recurring item".to_string()),
+            None => Ok(()),
+        }
+    }
+
+    /// Spell the tags as existing items do, so `#Food` joins `#food`.
+    pub fn match_tags(&mut self, known: &[String]) {
+        for tag in &mut self.tags {
+            if let Some(spelling) = known.iter().find(|k| k.eq_ignore_ascii_case(tag)) {
+                tag.clone_from(spelling);
+            }
+        }
+    }
+
+    pub fn cost_item(&self) -> Result<CostItem, String> {
+        self.check()?;
+        self.undated()?;
+        let TransactionCategory::Expense(category) = &self.category else {
+            return Err("not an expense".to_string());
+        };
+        Ok(CostItem {
+            id: 0,
+            what: self.title(),
+            cost: self.amount.unwrap_or_default(),
+            cost_cycle: self.cycle.clone(),
+            cost_category: category.clone(),
+            tags: self.tag_list(),
+        })
+    }
+
+    pub fn income_item(&self) -> Result<IncomeItem, String> {
+        self.check()?;
+        self.undated()?;
+        let TransactionCategory::Income(category) = &self.category else {
+            return Err("not income".to_string());
+        };
+        Ok(IncomeItem {
+            id: 0,
+            source: self.title(),
+            category: category.clone(),
+            amount: self.amount.unwrap_or_default(),
+            income_cycle: self.cycle.clone(),
+            tags: self.tag_list(),
+        })
+    }
+
+    pub fn transaction(&self) -> Result<Transaction, String> {
+        self.check()?;
+        let Some(date) = self.date else {
+            return Err("a transaction needs a date".to_string());
+        };
+        Ok(Transaction {
+            id: 0,
+            date,
+            description: self.title(),
+            amount: self.amount.unwrap_or_default(),
+            category: self.category.clone(),
+            tags: self.tag_list(),
+        })
+    }
+
+    /// What adding the line does: record a transaction if it is dated, else
+    /// add a recurring item.
+    pub fn event(&self) -> Result<BudgetEvent, String> {
+        if self.date.is_some() {
+            let transaction = self.transaction()?;
+            return Ok(BudgetEvent::CommitImport(vec![ImportEntry::Transaction(
+                transaction,
+            )]));
+        }
+        Ok(match self.kind {
+            Kind::Expense => BudgetEvent::AddCostItem(self.cost_item()?),
+            Kind::Income => BudgetEvent::AddIncomeItem(self.income_item()?),
+        })
+    }
+
+    /// One line saying what would be added, e.g.
+    /// `Coffee · 5.00 daily · Dining Out · #cafe`.
+    pub fn preview(&self) -> String {
+        let amount = amount::format(self.amount.unwrap_or_default(), self.locale);
+        let mut parts = vec![self.title()];
+        parts.push(match self.date {
+            Some(date) => format!("{} on {}", amount, date),
+            None => format!("{} {}", amount, self.cycle.label().to_lowercase()),
+        });
+        parts.push(self.category.label().to_string());
+        if !self.tags.is_empty() {
+            let tags: Vec<String> = self.tags.iter().map(|tag| format!("#{}", tag)).collect();
+            parts.push(tags.join(" "));
+        }
+        parts.join(" · ")
+    }
+}
+
+/// Enough for `1 234 567 kr`.
+const MAX_AMOUNT_WORDS: usize = 4;
+
+/// Words with their byte offsets.
+fn words(text: &str) -> impl Iterator<Item = (usize, &str)> {
+    text.split_whitespace()
+        .map(move |word| (word.as_ptr() as usize - text.as_ptr() as usize, word))
+}
+
+/// Longest amount starting at the first of `words`, such as `1 200,50 kr`,
+/// and how many words it takes.
+fn amount_at(text: &str, words: &[(usize, &str)], locale: Locale) -> Option<(f32, usize)> {
+    let start = words.first()?.0;
+    (1..=words.len().min(MAX_AMOUNT_WORDS))
+        .rev()
+        .find_map(|used| {
+            let (last, word) = words[used - 1];
+            amount::parse(&text[start..last + word.len()], locale).map(|amount| (amount, used))
+        })
+}
+
+fn parse_date(word: &str) -> Option<NaiveDate> {
+    NaiveDate::parse_from_str(word, "%Y-%m-%d")
+        .or_else(|_| NaiveDate::parse_from_str(word, "%d.%m.%Y"))
+        .ok()
+}
+
+/// Digits in the shape of a date, even if they don't make one.
+fn looks_like_date(word: &str) -> bool {
+    let digits = |part: &&str| !part.is_empty() && part.chars().all(|c| c.is_ascii_digit());
+    let iso: Vec<&str> = word.split('-').collect();
+    let dotted: Vec<&str> = word.split('.').collect();
+    (iso.len() == 3 && iso.iter().all(digits) && iso[0].len() == 4)
+        || (dotted.len() == 3 && dotted.iter().all(digits) && dotted[2].len() == 4)
+}
+
+/// Category named by an `@` word: a keyword such as `rent`, or the
+/// category's own name.
+pub fn expense_category(name: &str) -> Option<ExpenceCategory> {
+    Some(match name.to_lowercase().as_str() {
+        "housing" | "house" | "rent" | "mortgage" => ExpenceCategory::Housing,
+        "transportation" | "transport" | "travel" | "car" | "bus" | "train" | "gas" | "fuel" => {
+            ExpenceCategory::Transportation
+        }
+        "groceries" | "grocery" | "food" | "supermarket" => ExpenceCategory::Groceries,
+        "healthcare" | "health" | "medical" | "doctor" | "medicine" | "pharmacy" => {
+            ExpenceCategory::Healthcare
+        }
+        "personalcare" | "personal" | "care" | "hygiene" | "beauty" | "haircut" => {
+            ExpenceCategory::PersonalCare
+        }
+        "diningout" | "dining" | "restaurant" | "takeout" | "coffee" | "lunch" => {
+            ExpenceCategory::DiningOut
+        }
+        "entertainment" | "fun" | "movie" | "games" | "netflix" | "streaming" | "concert" => {
+            ExpenceCategory::Entertainment
+        }
+        "shopping" | "shop" | "clothes" | "clothing" | "retail" => ExpenceCategory::Shopping,
+        "savings" | "save" | "investment" | "invest" => ExpenceCategory::Savings,
+        "debt" | "debtpayments" | "loan" | "credit" | "payment" => ExpenceCategory::DebtPayments,
+        "utilities" | "utility" | "electricity" | "water" | "internet" | "phone" => {
+            ExpenceCategory::Utilities
+        }
+        "insurance" | "insure" | "policy" => ExpenceCategory::Insurance,
+        name => return ExpenceCategory::from_name(name),
+    })
+}
+
+pub fn income_category(name: &str) -> Option<IncomeCategory> {
+    Some(match name.to_lowercase().as_str() {
+        "salary" | "wage" | "job" | "work" | "employment" | "paycheck" => IncomeCategory::Salary,
+        "freelance" | "freelancing" | "contract" | "contractor" | "consulting" | "gig" => {
+            IncomeCategory::Freelance
+        }
+        "investment" | "invest" | "dividend" | "capital" | "stock" | "crypto" | "trading"
+        | "portfolio" => IncomeCategory::Investment,
+        "sidehustle" | "side" | "hustle" | "business" | "startup" | "venture"
+        | "entrepreneurship" => IncomeCategory::SideHustle,
+        "bonus" | "commission" | "incentive" | "reward" | "tip" | "gratuity" => {
+            IncomeCategory::Bonus
+        }
+        "gift" | "present" | "donation" | "inheritance" | "windfall" | "lottery" => {
+            IncomeCategory::Gift
+        }
+        name => return IncomeCategory::from_name(name),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn expense_with_cycle_and_category() {
+        let item = parse_expense("Coffee 5 \\daily @dining", Locale::English).unwrap();
+        assert_eq!(item.what, "Coffee");
+        assert_eq!(item.cost, 5.0);
+        assert_eq!(item.cost_cycle, CostCycle::Daily);
+        assert_eq!(item.cost_category, ExpenceCategory::DiningOut);
+    }
+
+    #[test]
+    fn expense_guesses_from_keywords() {
+        let item = parse_expense("rent 1200 monthly", Locale::English).unwrap();
+        assert_eq!(item.what, "rent");
+        assert_eq!(item.cost, 1200.0);
+        assert_eq!(item.cost_cycle, CostCycle::Monthly);
+        assert_eq!(item.cost_category, ExpenceCategory::Housing);
+
+        let item = parse_expense("Car insurance $600 annual", Locale::English).unwrap();
+        assert_eq!(item.what, "Car insurance");
+        assert_eq!(item.cost_cycle, CostCycle::Yearly);
+    }
+
+    #[test]
+    fn keeps_casing_tags_and_note() {
+        let item = parse_expense(
+            "iPhone plan 35 @Utilities #Phone #phone -- split with Kim #2",
+            Locale::English,
+        )
+        .unwrap();
+        assert_eq!(item.what, "iPhone plan (split with Kim #2)");
+        assert_eq!(item.cost_category, ExpenceCategory::Utilities);
+        assert_eq!(item.tags, Some(vec!["Phone".to_string()]));
+
+        let mut line = parse("Bus 40 #Commute #work", Kind::Expense, Locale::English);
+        line.match_tags(&["commute".to_string()]);
+        assert_eq!(line.tags, ["commute", "work"]);
+    }
+
+    #[test]
+    fn errors_point_at_the_words() {
+        let text = "gym \\fortnightly @sports";
+        let line = parse(text, Kind::Expense, Locale::English);
+        let errors: Vec<(&str, &str)> = line
+            .errors
+            .iter()
+            .map(|e| (&text[e.span.clone()], e.message.as_str()))
+            .collect();
+        assert_eq!(
+            errors,
+            [
+                ("\\fortnightly", "unknown cycle \"fortnightly\""),
+                ("@sports", "unknown category \"sports\""),
+                ("", "missing amount"),
+            ]
+        );
+        assert!(parse_expense(text, Locale::English).is_err());
+        assert!(parse_expense("  ", Locale::English).is_err());
+        assert!(parse_expense("5 20", Locale::English).is_err());
+    }
+
+    #[test]
+    fn dated_lines_are_transactions() {
+        let line = parse(
+            "Monthly pass 45 @transport 2024-03-01",
+            Kind::Expense,
+            Locale::English,
+        );
+        assert!(line.errors.is_empty());
+        let transaction = line.transaction().unwrap();
+        assert_eq!(transaction.description, "Monthly pass");
+        assert_eq!(
+            transaction.date,
+            NaiveDate::from_ymd_opt(2024, 3, 1).unwrap()
+        );
+        assert!(matches!(line.event(), Ok(BudgetEvent::CommitImport(_))));
+        assert!(line.cost_item().is_err());
+
+        assert!(
+            parse("Lunch 12 \\daily 1.3.2024", Kind::Expense, Locale::English)
+                .event()
+                .is_err()
+        );
+        assert!(
+            !parse("Lunch 12 30.02.2024", Kind::Expense, Locale::English)
+                .errors
+                .is_empty()
+        );
+    }
+
+    #[test]
+    fn amounts_as_the_locale_writes_them() {
+        let item = parse_expense("Rent 12 500 kr @housing", Locale::Nordic).unwrap();
+        assert_eq!(item.what, "Rent");
+        assert_eq!(item.cost, 12500.0);
+
+        let text = "Groceries 1.200,50 kr weekly";
+        let line = parse(text, Kind::Expense, Locale::German);
+        let amount = line
+            .tokens
+            .iter()
+            .find(|t| t.kind == TokenKind::Amount)
+            .unwrap();
+        assert_eq!(&text[amount.span.clone()], "1.200,50 kr");
+        assert_eq!(line.amount, Some(1200.5));
+        assert_eq!(line.cycle, CostCycle::Weekly);
+        assert_eq!(line.preview(), "Groceries · 1.200,50 weekly · Groceries");
+
+        assert_eq!(
+            parse_expense("Lunch 12,50", Locale::Nordic).unwrap().cost,
+            12.5
+        );
+        assert_eq!(
+            parse_expense("Laptop 1.2k yearly", Locale::English)
+                .unwrap()
+                .cost,
+            1200.0
+        );
+    }
+
+    #[test]
+    fn categories_by_keyword_or_name() {
+        assert_eq!(expense_category("Rent"), Some(ExpenceCategory::Housing));
+        assert_eq!(
+            expense_category("personal care"),
+            Some(ExpenceCategory::PersonalCare)
+        );
+        assert_eq!(expense_category("other"), Some(ExpenceCategory::Other));
+        assert_eq!(expense_category("sports"), None);
+        assert_eq!(
+            income_category("dividend"),
+            Some(IncomeCategory::Investment)
+        );
+        assert_eq!(income_category("lottery"), Some(IncomeCategory::Gift));
+    }
+
+    #[test]
+    fn income_with_cycle_and_category() {
+        let item = parse_income("Day job €3200 \\m @paycheck", Locale::English).unwrap();
+        assert_eq!(item.source, "Day job");
+        assert_eq!(item.amount, 3200.0);
+        assert_eq!(item.income_cycle, CostCycle::Monthly);
+        assert_eq!(item.category, IncomeCategory::Salary);
+
+        let item = parse_income("dividends 150 yearly", Locale::English).unwrap();
+        assert_eq!(item.source, "dividends");
+        assert_eq!(item.income_cycle, CostCycle::Yearly);
+        assert_eq!(item.category, IncomeCategory::Investment);
+    }
+}
@@ -0,0 +1,248 @@
+use std::fmt::Write as _;
+
+use crate::{
+    BudgetData,
+    amount::{self, Locale},
+    models::cost_item::{CostCycle, CostItem},
+    report::{
+        ReportPeriod, Summary,
+        svg::{self, escape},
+    },
+};
+
+const STYLE: &str = "
+body { font-family: sans-serif; max-width: 960px; margin: 2em auto; color: #222; }
+h1 { margin-bottom: 0; }
+.subtitle { color: #777; margin-top: 0.2em; }
+table { border-collapse: collapse; width: 100%; margin-bottom: 1.5em; }
+th, td { text-align: left; padding: 4px 8px; border-bottom: 1px solid #ddd; }
+td.num, th.num { text-align: right; font-variant-numeric: tabular-nums; }
+tr.total td { font-weight: bold; border-top: 2px solid #999; }
+.charts { display: flex; gap: 2em; flex-wrap: wrap; align-items: flex-start; }
+.charts figure { margin: 0; }
+.charts figcaption { color: #555; margin-bottom: 0.5em; }
+.tag { color: #666; font-family: monospace; font-size: 0.9em; }
+";
+
+/// Render a single self-contained HTML page: no external stylesheets,
+/// scripts or images, so it can be mailed or archived as one file. Amounts
+/// are written as `locale` writes them.
+pub fn render(budget_data: &BudgetData, period: ReportPeriod, locale: Locale) -> String {
+    let summary = Summary::new(budget_data, period);
+    let mut html = String::new();
+
+    let _ = writeln!(html, "<!DOCTYPE html>");
+    let _ = writeln!(html, "<html lang=\"en\"><head><meta charset=\"utf-8\">");
+    let _ = writeln!(
+        html,
+        "<title>Budget report {}</title>",
+        escape(&summary.period.title())
+    );
+    let _ = writeln!(html, "<style>{}</style></head><body>", STYLE);
+    let _ = writeln!(html, "<h1>Budget report</h1>");
+    let _ = writeln!(
+        html,
+        "<p class=\"subtitle\">{} &middot; generated {}</p>",
+        escape(&summary.period.title()),
+        chrono::Local::now().format("%Y-%m-%d")
+    );
+
+    write_totals(&mut html, &summary, locale);
+    write_charts(&mut html, &summary, locale);
+    write_categories(&mut html, &summary, locale);
+    write_cost_items(&mut html, budget_data, period, locale);
+    write_income(&mut html, budget_data, period, locale);
+
+    let _ = writeln!(html, "</body></html>");
+    html
+}
+
+fn write_totals(html: &mut String, summary: &Summary, locale: Locale) {
+    let _ = writeln!(html, "<h2>Income vs expenses</h2>");
+    let _ = writeln!(
+        html,
+        "<table><tr><th></th><th class=\"num\">Planned</th><th class=\"num\">Recorded</th></tr>"
+    );
+    let _ = writeln!(
+        html,
+        "<tr><td>Income</td><td class=\"num\">{}</td><td class=\"num\">{}</td></tr>",
+        amount::format(summary.planned_income, locale),
+        amount::format(summary.actual_income, locale)
+    );
+    let _ = writeln!(
+        html,
+        "<tr><td>Expenses</td><td class=\"num\">{}</td><td class=\"num\">{}</td></tr>",
+        amount::format(summary.planned_expenses, locale),
+        amount::format(summary.actual_expenses, locale)
+    );
+    let _ = writeln!(
+        html,
+        "<tr class=\"total\"><td>Net</td><td class=\"num\">{}</td><td class=\"num\">{}</td></tr>",
+        amount::format(summary.planned_income - summary.planned_expenses, locale),
+        amount::format(summary.actual_income - summary.actual_expenses, locale)
+    );
+    let _ = writeln!(html, "</table>");
+    let _ = writeln!(
+        html,
+        "<p class=\"subtitle\">Planned amounts come from recurring income and cost items; recorded amounts from {} transactions in the period.</p>",
+        summary.transaction_count
+    );
+}
+
+fn write_charts(html: &mut String, summary: &Summary, locale: Locale) {
+    let percent = format!("{}%", (summary.income_used() * 100.0).round() as i32);
+
+    let _ = writeln!(html, "<div class=\"charts\">");
+    let _ = writeln!(
+        html,
+        "<figure><figcaption>Income used</figcaption>{}</figure>",
+        svg::pie_chart(&summary.income_used_slices(), 220.0, Some(&percent))
+    );
+    let _ = writeln!(
+        html,
+        "<figure><figcaption>Expenses by category</figcaption>{}</figure>",
+        svg::pie_chart(&summary.category_slices(), 220.0, None)
+    );
+    let _ = writeln!(
+        html,
+        "<figure><figcaption>Summary by month</figcaption>{}</figure>",
+        svg::bar_chart(&summary.category_slices(), 420.0, locale)
+    );
+    let _ = writeln!(html, "</div>");
+}
+
+fn write_categories(html: &mut String, summary: &Summary, locale: Locale) {
+    let _ = writeln!(html, "<h2>Expenses by category</h2>");
+    let _ = writeln!(
+        html,
+        "<table><tr><th>Category</th><th class=\"num\">Planned</th><th class=\"num\">Recorded</th><th class=\"num\">Share</th></tr>"
+    );
+    for line in &summary.categories {
+        if line.planned <= 0.0 && line.actual <= 0.0 {
+            continue;
+        }
+        let share = if summary.planned_expenses > 0.0 {
+            line.planned / summary.planned_expenses * 100.0
+        } else {
+            0.0
+        };
+        let _ = writeln!(
+            html,
+            "<tr><td>{}</td><td class=\"num\">{}</td><td class=\"num\">{}</td><td class=\"num\">{:.1}%</td></tr>",
+            escape(line.category.label()),
+            amount::format(line.planned, locale),
+            amount::format(line.actual, locale),
+            share
+        );
+    }
+    let _ = writeln!(
+        html,
+        "<tr class=\"total\"><td>Total</td><td class=\"num\">{}</td><td class=\"num\">{}</td><td></td></tr>",
+        amount::format(summary.planned_expenses, locale),
+        amount::format(summary.actual_expenses, locale)
+    );
+    let _ = writeln!(html, "</table>");
+}
+
+fn tags_html(tags: &Option<Vec<String>>) -> String {
+    tags.as_ref()
+        .map(|tags| {
+            tags.iter()
+                .map(|t| format!("<span class=\"tag\">#{}</span>", escape(t)))
+                .collect::<Vec<_>>()
+                .join(" ")
+        })
+        .unwrap_or_default()
+}
+
+fn write_cost_items(
+    html: &mut String,
+    budget_data: &BudgetData,
+    period: ReportPeriod,
+    locale: Locale,
+) {
+    let _ = writeln!(html, "<h2>Cost items</h2>");
+
+    for cycle in CostCycle::ALL {
+        let mut items: Vec<&CostItem> = budget_data
+            .cost_items
+            .values()
+            .filter(|item| item.cost_cycle == cycle)
+            .collect();
+        if items.is_empty() {
+            continue;
+        }
+        items.sort_by(|a, b| a.what.cmp(&b.what));
+
+        let _ = writeln!(html, "<h3>{}</h3>", cycle.label());
+        let _ = writeln!(
+            html,
+            "<table><tr><th>What</th><th>Category</th><th class=\"num\">Cost</th><th class=\"num\">In period</th><th>Tags</th></tr>"
+        );
+        let mut total = 0.0;
+        for item in items {
+            let in_period = item.cost_cycle.to_monthly(item.cost) * period.months();
+            total += in_period;
+            let _ = writeln!(
+                html,
+                "<tr><td>{}</td><td>{}</td><td class=\"num\">{}</td><td class=\"num\">{}</td><td>{}</td></tr>",
+                escape(&item.what),
+                escape(item.cost_category.label()),
+                amount::format(item.cost, locale),
+                amount::format(in_period, locale),
+                tags_html(&item.tags)
+            );
+        }
+        let _ = writeln!(
+            html,
+            "<tr class=\"total\"><td colspan=\"3\">Total</td><td class=\"num\">{}</td><td></td></tr>",
+            amount::format(total, locale)
+        );
+        let _ = writeln!(html, "</table>");
+    }
+}
+
+fn write_income(html: &mut String, budget_data: &BudgetData, period: ReportPeriod, locale: Locale) {
+    if budget_data.income_items.is_empty() {
+        return;
+    }
+
+    let _ = writeln!(html, "<h2>Income</h2>");
+    let _ = writeln!(
+        html,
+        "<table><tr><th>Source</th><th>Category</th><th>Cycle</th><th class=\"num\">Amount</th><th class=\"num\">In period</th></tr>"
+    );
+    for income in &budget_data.income_items {
+        let _ = writeln!(
+            html,
+            "<tr><td>{}</td><td>{}</td><td>{}</td><td class=\"num\">{}</td><td class=\"num\">{}</td></tr>",
+            escape(&income.source),
+            escape(income.category.label()),
+            income.income_cycle.label(),
+            amount::format(income.amount, locale),
+            amount::format(
+                income.income_cycle.to_monthly(income.amount) * period.months(),
+                locale
+            )
+        );
+    }
+    let _ = writeln!(html, "</table>");
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{BudgetEvent, fixtures::cost_item};
+
+    #[test]
+    fn names_are_escaped_and_amounts_follow_the_locale() {
+        let mut budget_data = BudgetData::default();
+        let name = "<script>alert(\"hi\")</script> & co";
+        budget_data.apply(&BudgetEvent::AddCostItem(cost_item(name, 1234.5)));
+
+        let html = render(&budget_data, ReportPeriod::Year(2025), Locale::German);
+        assert!(!html.contains("<script>"));
+        assert!(html.contains("&lt;script&gt;alert(&quot;hi&quot;)&lt;/script&gt; &amp; co"));
+        assert!(html.contains("1.234,50"));
+    }
+}
@@ -0,0 +1,376 @@
+pub mod html;
+pub mod pdf;
+pub mod png;
+pub mod svg;
+
+use chrono::NaiveDate;
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    BudgetData,
+    models::{cost_item::ExpenceCategory, transaction::TransactionCategory},
+    report::svg::Slice,
+};
+
+/// Colors used for expense categories, in `ExpenceCategory::ALL` order.
+pub const CATEGORY_COLORS: [(u8, u8, u8); 13] = [
+    (0xE6, 0x7E, 0x22), // orange
+    (0x1F, 0x77, 0xB4), // blue
+    (0x2C, 0xA0, 0x2C), // green
+    (0xD6, 0x27, 0x28), // red
+    (0x94, 0x67, 0xBD), // purple
+    (0x8C, 0x56, 0x4B), // brown
+    (0xE3, 0x77, 0xC2), // pink
+    (0x7F, 0x7F, 0x7F), // gray
+    (0xBC, 0xBD, 0x22), // olive
+    (0x17, 0xBE, 0xCF), // cyan
+    (0xFF, 0xA5, 0x00), // dark orange
+    (0xFF, 0xA5, 0x0C), // dark orange
+    (0xA0, 0xA0, 0xA0), // light gray
+];
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub enum ReportPeriod {
+    Month { year: i32, month: u32 },
+    Year(i32),
+}
+
+impl ReportPeriod {
+    /// Number of months the planned (recurring) amounts are scaled by.
+    pub fn months(&self) -> f32 {
+        match self {
+            ReportPeriod::Month { .. } => 1.0,
+            ReportPeriod::Year(_) => 12.0,
+        }
+    }
+
+    pub fn contains(&self, date: NaiveDate) -> bool {
+        let (start, end) = self.bounds();
+        start <= date && date < end
+    }
+
+    /// First day of the period and first day after it.
+    pub fn bounds(&self) -> (NaiveDate, NaiveDate) {
+        let (start, end) = match *self {
+            ReportPeriod::Month { year, month } => {
+                let (next_year, next_month) = if month == 12 {
+                    (year + 1, 1)
+                } else {
+                    (year, month + 1)
+                };
+                (
+                    NaiveDate::from_ymd_opt(year, month, 1),
+                    NaiveDate::from_ymd_opt(next_year, next_month, 1),
+                )
+            }
+            ReportPeriod::Year(year) => (
+                NaiveDate::from_ymd_opt(year, 1, 1),
+                NaiveDate::from_ymd_opt(year + 1, 1, 1),
+            ),
+        };
+        (
+            start.unwrap_or(NaiveDate::MIN),
+            end.unwrap_or(NaiveDate::MAX),
+        )
+    }
+
+    pub fn title(&self) -> String {
+        match *self {
+            ReportPeriod::Month { year, month } => NaiveDate::from_ymd_opt(year, month, 1)
+                .map(|date| date.format("%B %Y").to_string())
+                .unwrap_or_else(|| format!("{}-{:02}", year, month)),
+            ReportPeriod::Year(year) => year.to_string(),
+        }
+    }
+}
+
+/// Monthly equivalent of every expense category, in `ExpenceCategory::ALL` order.
+pub fn monthly_category_totals(budget_data: &BudgetData) -> Vec<(ExpenceCategory, f32)> {
+    ExpenceCategory::ALL
+        .into_iter()
+        .map(|category| {
+            let total = budget_data
+                .cost_items
+                .values()
+                .filter(|item| item.cost_category == category)
+                .map(|item| item.cost_cycle.to_monthly(item.cost))
+                .sum();
+            (category, total)
+        })
+        .collect()
+}
+
+/// Dashboard bars and category pie: one slice per category, colored by
+/// `CATEGORY_COLORS`.
+pub fn monthly_category_slices(budget_data: &BudgetData) -> Vec<Slice> {
+    monthly_category_totals(budget_data)
+        .into_iter()
+        .enumerate()
+        .map(|(i, (category, total))| Slice {
+            label: category.label().to_string(),
+            value: total,
+            color: CATEGORY_COLORS[i % CATEGORY_COLORS.len()],
+        })
+        .collect()
+}
+
+/// "Income used" pie: the used share and what is left, 0.0..=1.0.
+pub fn income_used_slices(used: f32) -> Vec<Slice> {
+    vec![
+        Slice {
+            label: "Expenses".to_string(),
+            value: used,
+            color: (196, 9, 198),
+        },
+        Slice {
+            label: "Left".to_string(),
+            value: 1.0 - used,
+            color: (0xD3, 0xD3, 0xD3),
+        },
+    ]
+}
+
+pub fn monthly_income_total(budget_data: &BudgetData) -> f32 {
+    budget_data
+        .income_items
+        .iter()
+        .map(|income| income.income_cycle.to_monthly(income.amount))
+        .sum()
+}
+
+pub fn monthly_expense_total(budget_data: &BudgetData) -> f32 {
+    budget_data
+        .cost_items
+        .values()
+        .map(|item| item.cost_cycle.to_monthly(item.cost))
+        .sum()
+}
+
+#[derive(Serialize)]
+pub struct CategoryLine {
+    pub category: ExpenceCategory,
+    pub planned: f32,
+    pub actual: f32,
+}
+
+/// Planned amounts from the recurring items scaled to the period, next to
+/// what the recorded transactions in that period add up to.
+#[derive(Serialize)]
+pub struct Summary {
+    pub period: ReportPeriod,
+    pub planned_income: f32,
+    pub planned_expenses: f32,
+    pub actual_income: f32,
+    pub actual_expenses: f32,
+    pub transaction_count: usize,
+    pub categories: Vec<CategoryLine>,
+}
+
+impl Summary {
+    pub fn new(budget_data: &BudgetData, period: ReportPeriod) -> Self {
+        let months = period.months();
+        let transactions: Vec<_> = budget_data
+            .transactions
+            .iter()
+            .filter(|t| period.contains(t.date))
+            .collect();
+
+        let categories = monthly_category_totals(budget_data)
+            .into_iter()
+            .map(|(category, monthly)| {
+                let actual = transactions
+                    .iter()
+                    .filter(|t| t.category == TransactionCategory::Expense(category.clone()))
+                    .map(|t| t.amount)
+                    .sum();
+                CategoryLine {
+                    category,
+                    planned: monthly * months,
+                    actual,
+                }
+            })
+            .collect();
+
+        Self {
+            period,
+            planned_income: monthly_income_total(budget_data) * months,
+            planned_expenses: monthly_expense_total(budget_data) * months,
+            actual_income: transactions
+                .iter()
+                .filter(|t| t.category.is_income())
+                .map(|t| t.amount)
+                .sum(),
+            actual_expenses: transactions
+                .iter()
+                .filter(|t| !t.category.is_income())
+                .map(|t| t.amount)
+                .sum(),
+            transaction_count: transactions.len(),
+            categories,
+        }
+    }
+
+    /// Share of planned income taken by planned expenses, 0.0..=1.0.
+    pub fn income_used(&self) -> f32 {
+        if self.planned_income > 0.0 {
+            (self.planned_expenses / self.planned_income).clamp(0.0, 1.0)
+        } else {
+            0.0
+        }
+    }
+
+    pub fn category_slices(&self) -> Vec<Slice> {
+        self.categories
+            .iter()
+            .enumerate()
+            .map(|(i, line)| Slice {
+                label: line.category.label().to_string(),
+                value: line.planned,
+                color: CATEGORY_COLORS[i % CATEGORY_COLORS.len()],
+            })
+            .collect()
+    }
+
+    pub fn income_used_slices(&self) -> Vec<Slice> {
+        income_used_slices(self.income_used())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        BudgetEvent,
+        models::{
+            cost_item::{CostCycle, CostItem},
+            income_item::{IncomeCategory, IncomeItem},
+            transaction::Transaction,
+        },
+    };
+
+    fn budget() -> BudgetData {
+        let mut budget_data = BudgetData::default();
+        for (what, cost, cost_cycle, cost_category) in [
+            ("Rent", 900.0, CostCycle::Monthly, ExpenceCategory::Housing),
+            (
+                "Insurance",
+                1200.0,
+                CostCycle::Yearly,
+                ExpenceCategory::Housing,
+            ),
+            ("Lunch", 10.0, CostCycle::Weekly, ExpenceCategory::DiningOut),
+        ] {
+            budget_data.apply(&BudgetEvent::AddCostItem(CostItem {
+                id: 0,
+                what: what.to_string(),
+                cost,
+                cost_cycle,
+                cost_category,
+                tags: None,
+            }));
+        }
+        for (source, amount, income_cycle) in [
+            ("Salary", 3000.0, CostCycle::Monthly),
+            ("Bonus", 2400.0, CostCycle::Yearly),
+        ] {
+            budget_data.apply(&BudgetEvent::AddIncomeItem(IncomeItem {
+                id: 0,
+                source: source.to_string(),
+                category: IncomeCategory::Salary,
+                amount,
+                income_cycle,
+                tags: None,
+            }));
+        }
+        for (id, (day, month, amount, category)) in [
+            (31, 1, 50.0, ExpenceCategory::DiningOut),
+            (1, 2, 30.0, ExpenceCategory::DiningOut),
+            (28, 2, 900.0, ExpenceCategory::Housing),
+        ]
+        .into_iter()
+        .enumerate()
+        {
+            budget_data.transactions.push(Transaction {
+                id: id as u64,
+                date: NaiveDate::from_ymd_opt(2025, month, day).unwrap(),
+                description: "Card payment".to_string(),
+                amount,
+                category: TransactionCategory::Expense(category),
+                tags: None,
+            });
+        }
+        budget_data
+    }
+
+    fn assert_close(actual: f32, expected: f32) {
+        assert!((actual - expected).abs() < 0.01, "{actual} != {expected}");
+    }
+
+    #[test]
+    fn monthly_totals_scale_every_cycle() {
+        let budget_data = budget();
+        assert_close(monthly_income_total(&budget_data), 3200.0);
+        assert_close(monthly_expense_total(&budget_data), 900.0 + 100.0 + 43.48);
+
+        let totals = monthly_category_totals(&budget_data);
+        assert_eq!(totals.len(), ExpenceCategory::ALL.len());
+        let total = |category| {
+            totals
+                .iter()
+                .find(|(c, _)| *c == category)
+                .map(|(_, total)| *total)
+                .unwrap()
+        };
+        assert_close(total(ExpenceCategory::Housing), 1000.0);
+        assert_close(total(ExpenceCategory::DiningOut), 43.48);
+        assert_close(total(ExpenceCategory::Savings), 0.0);
+    }
+
+    #[test]
+    fn period_bounds() {
+        let december = ReportPeriod::Month {
+            year: 2024,
+            month: 12,
+        };
+        assert_eq!(
+            december.bounds(),
+            (
+                NaiveDate::from_ymd_opt(2024, 12, 1).unwrap(),
+                NaiveDate::from_ymd_opt(2025, 1, 1).unwrap()
+            )
+        );
+        assert!(december.contains(NaiveDate::from_ymd_opt(2024, 12, 31).unwrap()));
+        assert!(!december.contains(NaiveDate::from_ymd_opt(2025, 1, 1).unwrap()));
+        assert!(ReportPeriod::Year(2025).contains(NaiveDate::from_ymd_opt(2025, 6, 15).unwrap()));
+        assert_eq!(december.title(), "December 2024");
+    }
+
+    #[test]
+    fn summary_counts_transactions_in_the_period() {
+        let budget_data = budget();
+        let summary = Summary::new(
+            &budget_data,
+            ReportPeriod::Month {
+                year: 2025,
+                month: 2,
+            },
+        );
+        assert_eq!(summary.transaction_count, 2);
+        assert_close(summary.actual_expenses, 930.0);
+        assert_close(summary.actual_income, 0.0);
+        assert_close(summary.planned_income, 3200.0);
+
+        let housing = summary
+            .categories
+            .iter()
+            .find(|line| line.category == ExpenceCategory::Housing)
+            .unwrap();
+        assert_close(housing.planned, 1000.0);
+        assert_close(housing.actual, 900.0);
+
+        let year = Summary::new(&budget_data, ReportPeriod::Year(2025));
+        assert_eq!(year.transaction_count, 3);
+        assert_close(year.planned_income, 3200.0 * 12.0);
+        assert_close(year.income_used(), 1043.48 / 3200.0);
+    }
+}
@@ -0,0 +1,631 @@
+use std::fmt::Write as _;
+
+use crate::{
+    BudgetData,
+    amount::{self, Locale},
+    models::cost_item::{CostItem, ExpenceCategory},
+    report::{CATEGORY_COLORS, ReportPeriod, Summary, svg::Slice},
+};
+
+// A4 in points
+const PAGE_WIDTH: f32 = 595.0;
+const PAGE_HEIGHT: f32 = 842.0;
+const MARGIN: f32 = 50.0;
+
+/// Minimal PDF writer: the built-in Helvetica fonts, text, filled
+/// rectangles and polygons. Enough for a printable report without pulling
+/// in a layout engine.
+struct PdfWriter {
+    pages: Vec<String>,
+    current: String,
+    y: f32,
+}
+
+#[derive(Clone, Copy)]
+enum Font {
+    Regular,
+    Bold,
+}
+
+impl Font {
+    fn resource(&self) -> &'static str {
+        match self {
+            Font::Regular => "F1",
+            Font::Bold => "F2",
+        }
+    }
+}
+
+impl PdfWriter {
+    fn new() -> Self {
+        Self {
+            pages: Vec::new(),
+            current: String::new(),
+            y: PAGE_HEIGHT - MARGIN,
+        }
+    }
+
+    fn new_page(&mut self) {
+        let page = std::mem::take(&mut self.current);
+        self.pages.push(page);
+        self.y = PAGE_HEIGHT - MARGIN;
+    }
+
+    /// Start a new page if less than `height` points are left.
+    fn ensure_space(&mut self, height: f32) {
+        if self.y - height < MARGIN {
+            self.new_page();
+        }
+    }
+
+    fn text(&mut self, x: f32, y: f32, size: f32, font: Font, text: &str) {
+        let _ = writeln!(
+            self.current,
+            "BT /{} {} Tf {:.2} {:.2} Td ({}) Tj ET",
+            font.resource(),
+            size,
+            x,
+            y,
+            pdf_string(text)
+        );
+    }
+
+    fn text_right(&mut self, right: f32, y: f32, size: f32, font: Font, text: &str) {
+        let width = text_width(text, size);
+        self.text(right - width, y, size, font, text);
+    }
+
+    fn fill_color(&mut self, (r, g, b): (u8, u8, u8)) {
+        let _ = writeln!(
+            self.current,
+            "{:.3} {:.3} {:.3} rg",
+            r as f32 / 255.0,
+            g as f32 / 255.0,
+            b as f32 / 255.0
+        );
+    }
+
+    fn rect(&mut self, x: f32, y: f32, width: f32, height: f32, color: (u8, u8, u8)) {
+        self.fill_color(color);
+        let _ = writeln!(
+            self.current,
+            "{:.2} {:.2} {:.2} {:.2} re f",
+            x, y, width, height
+        );
+        self.fill_color((0, 0, 0));
+    }
+
+    fn line(&mut self, x1: f32, y1: f32, x2: f32, y2: f32) {
+        let _ = writeln!(
+            self.current,
+            "0.7 G 0.5 w {:.2} {:.2} m {:.2} {:.2} l S 0 G",
+            x1, y1, x2, y2
+        );
+    }
+
+    fn polygon(&mut self, points: &[(f32, f32)], color: (u8, u8, u8)) {
+        let Some((first, rest)) = points.split_first() else {
+            return;
+        };
+        self.fill_color(color);
+        let _ = write!(self.current, "{:.2} {:.2} m", first.0, first.1);
+        for (x, y) in rest {
+            let _ = write!(self.current, " {:.2} {:.2} l", x, y);
+        }
+        let _ = writeln!(self.current, " h f");
+        self.fill_color((0, 0, 0));
+    }
+
+    /// Pie chart centered on (cx, cy), same rules as the dashboard pies.
+    fn pie(&mut self, cx: f32, cy: f32, radius: f32, slices: &[Slice]) {
+        let disc: Vec<(f32, f32)> = (0..=96)
+            .map(|i| {
+                let a = i as f32 / 96.0 * std::f32::consts::TAU;
+                (cx + radius * a.cos(), cy + radius * a.sin())
+            })
+            .collect();
+        self.polygon(&disc, (0xD3, 0xD3, 0xD3));
+
+        let total: f32 = slices.iter().map(|s| s.value.max(0.0)).sum();
+        if total <= 0.0 {
+            return;
+        }
+
+        // Clockwise from twelve o'clock; PDF's y axis points up.
+        let mut start = std::f32::consts::FRAC_PI_2;
+        for slice in slices {
+            let frac = (slice.value.max(0.0) / total).clamp(0.0, 1.0);
+            if frac < 0.01 {
+                continue;
+            }
+            let end = start - frac * std::f32::consts::TAU;
+            let steps = ((96.0 * frac).ceil() as usize).clamp(2, 96);
+            let mut points = vec![(cx, cy)];
+            for s in 0..=steps {
+                let a = start + (end - start) * s as f32 / steps as f32;
+                points.push((cx + radius * a.cos(), cy + radius * a.sin()));
+            }
+            self.polygon(&points, slice.color);
+            start = end;
+        }
+    }
+
+    fn heading(&mut self, text: &str) {
+        self.ensure_space(40.0);
+        self.y -= 24.0;
+        self.text(MARGIN, self.y, 15.0, Font::Bold, text);
+        self.y -= 10.0;
+    }
+
+    /// One table row. Columns are (left edge, right-aligned?, text).
+    fn row(&mut self, columns: &[(f32, bool, String)], font: Font) {
+        self.ensure_space(16.0);
+        self.y -= 15.0;
+        for (x, right, text) in columns {
+            if *right {
+                self.text_right(*x, self.y, 10.0, font, text);
+            } else {
+                self.text(*x, self.y, 10.0, font, text);
+            }
+        }
+        self.line(MARGIN, self.y - 4.0, PAGE_WIDTH - MARGIN, self.y - 4.0);
+    }
+
+    fn finish(mut self) -> Vec<u8> {
+        if !self.current.is_empty() || self.pages.is_empty() {
+            self.new_page();
+        }
+
+        let mut out: Vec<u8> = Vec::new();
+        let mut offsets: Vec<usize> = Vec::new();
+        let page_count = self.pages.len();
+        // 1: catalog, 2: pages, 3-4: fonts, then (page, content) pairs
+        let first_page_obj = 5;
+
+        out.extend_from_slice(b"%PDF-1.4\n%\xE2\xE3\xCF\xD3\n");
+
+        let mut object = |out: &mut Vec<u8>, body: &[u8]| {
+            offsets.push(out.len());
+            let id = offsets.len();
+            out.extend_from_slice(format!("{} 0 obj\n", id).as_bytes());
+            out.extend_from_slice(body);
+            out.extend_from_slice(b"\nendobj\n");
+        };
+
+        object(&mut out, b"<< /Type /Catalog /Pages 2 0 R >>");
+        let kids: Vec<String> = (0..page_count)
+            .map(|i| format!("{} 0 R", first_page_obj + i * 2))
+            .collect();
+        object(
+            &mut out,
+            format!(
+                "<< /Type /Pages /Kids [{}] /Count {} >>",
+                kids.join(" "),
+                page_count
+            )
+            .as_bytes(),
+        );
+        object(
+            &mut out,
+            b"<< /Type /Font /Subtype /Type1 /BaseFont /Helvetica /Encoding /WinAnsiEncoding >>",
+        );
+        object(
+            &mut out,
+            b"<< /Type /Font /Subtype /Type1 /BaseFont /Helvetica-Bold /Encoding /WinAnsiEncoding >>",
+        );
+
+        for (i, content) in self.pages.iter().enumerate() {
+            let content_obj = first_page_obj + i * 2 + 1;
+            object(
+                &mut out,
+                format!(
+                    "<< /Type /Page /Parent 2 0 R /MediaBox [0 0 {} {}] /Resources << /Font << /F1 3 0 R /F2 4 0 R >> >> /Contents {} 0 R >>",
+                    PAGE_WIDTH, PAGE_HEIGHT, content_obj
+                )
+                .as_bytes(),
+            );
+            let mut stream = format!("<< /Length {} >>\nstream\n", content.len()).into_bytes();
+            stream.extend_from_slice(content.as_bytes());
+            stream.extend_from_slice(b"\nendstream");
+            object(&mut out, &stream);
+        }
+
+        let xref_start = out.len();
+        let mut xref = format!("xref\n0 {}\n0000000000 65535 f \n", offsets.len() + 1);
+        for offset in &offsets {
+            let _ = writeln!(xref, "{:010} 00000 n ", offset);
+        }
+        let _ = write!(
+            xref,
+            "trailer\n<< /Size {} /Root 1 0 R >>\nstartxref\n{}\n%%EOF\n",
+            offsets.len() + 1,
+            xref_start
+        );
+        out.extend_from_slice(xref.as_bytes());
+        out
+    }
+}
+
+/// Characters WinAnsi keeps at 0x80..0x9F, where Latin-1 has control codes.
+const WIN_ANSI_EXTRA: [(char, u8); 27] = [
+    ('€', 0x80),
+    ('‚', 0x82),
+    ('ƒ', 0x83),
+    ('„', 0x84),
+    ('…', 0x85),
+    ('†', 0x86),
+    ('‡', 0x87),
+    ('ˆ', 0x88),
+    ('‰', 0x89),
+    ('Š', 0x8A),
+    ('‹', 0x8B),
+    ('Œ', 0x8C),
+    ('Ž', 0x8E),
+    ('‘', 0x91),
+    ('’', 0x92),
+    ('“', 0x93),
+    ('”', 0x94),
+    ('•', 0x95),
+    ('–', 0x96),
+    ('—', 0x97),
+    ('˜', 0x98),
+    ('™', 0x99),
+    ('š', 0x9A),
+    ('›', 0x9B),
+    ('œ', 0x9C),
+    ('ž', 0x9E),
+    ('Ÿ', 0x9F),
+];
+
+/// Escape a string for a PDF literal. Characters without a WinAnsi code are
+/// replaced with '?'.
+fn pdf_string(text: &str) -> String {
+    let mut out = String::new();
+    for c in text.chars() {
+        let extra = WIN_ANSI_EXTRA.iter().find(|(known, _)| *known == c);
+        match c {
+            '(' | ')' | '\\' => {
+                out.push('\\');
+                out.push(c);
+            }
+            ' '..='~' => out.push(c),
+            '\u{a0}'..='\u{ff}' => {
+                let _ = write!(out, "\\{:03o}", c as u32);
+            }
+            _ => match extra {
+                Some((_, code)) => {
+                    let _ = write!(out, "\\{:03o}", code);
+                }
+                None => out.push('?'),
+            },
+        }
+    }
+    out
+}
+
+/// Approximate Helvetica advance widths, good enough to right-align numbers.
+fn text_width(text: &str, size: f32) -> f32 {
+    let units: f32 = text
+        .chars()
+        .map(|c| match c {
+            '0'..='9' | '$' => 556.0,
+            '.' | ',' | ' ' => 278.0,
+            '-' => 333.0,
+            '%' => 889.0,
+            'A'..='Z' => 667.0,
+            'i' | 'j' | 'l' => 222.0,
+            'f' | 't' | 'r' => 300.0,
+            'm' | 'w' => 833.0,
+            _ => 556.0,
+        })
+        .sum();
+    units / 1000.0 * size
+}
+
+/// Amounts are written as `locale` writes them.
+pub fn render(budget_data: &BudgetData, period: ReportPeriod, locale: Locale) -> Vec<u8> {
+    let summary = Summary::new(budget_data, period);
+    let mut pdf = PdfWriter::new();
+
+    write_cover(&mut pdf, &summary, locale);
+    write_category_table(&mut pdf, &summary, locale);
+    write_income(&mut pdf, budget_data, locale);
+    write_cost_items(&mut pdf, budget_data, locale);
+
+    pdf.finish()
+}
+
+fn write_cover(pdf: &mut PdfWriter, summary: &Summary, locale: Locale) {
+    pdf.y -= 30.0;
+    pdf.text(MARGIN, pdf.y, 26.0, Font::Bold, "Budget");
+    pdf.y -= 24.0;
+    pdf.text(MARGIN, pdf.y, 14.0, Font::Regular, &summary.period.title());
+    pdf.y -= 16.0;
+    let generated = format!("Generated {}", chrono::Local::now().format("%Y-%m-%d"));
+    pdf.text(MARGIN, pdf.y, 9.0, Font::Regular, &generated);
+
+    pdf.heading("Summary");
+    let right_planned = 400.0;
+    let right_recorded = PAGE_WIDTH - MARGIN;
+    pdf.row(
+        &[
+            (right_planned, true, "Planned".to_string()),
+            (right_recorded, true, "Recorded".to_string()),
+        ],
+        Font::Bold,
+    );
+    for (label, planned, recorded) in [
+        ("Income", summary.planned_income, summary.actual_income),
+        (
+            "Expenses",
+            summary.planned_expenses,
+            summary.actual_expenses,
+        ),
+        (
+            "Net",
+            summary.planned_income - summary.planned_expenses,
+            summary.actual_income - summary.actual_expenses,
+        ),
+    ] {
+        pdf.row(
+            &[
+                (MARGIN, false, label.to_string()),
+                (right_planned, true, amount::format(planned, locale)),
+                (right_recorded, true, amount::format(recorded, locale)),
+            ],
+            if label == "Net" {
+                Font::Bold
+            } else {
+                Font::Regular
+            },
+        );
+    }
+
+    // Charts
+    pdf.heading("Charts");
+    let radius = 80.0;
+    pdf.ensure_space(radius * 2.0 + 40.0);
+    let cy = pdf.y - 20.0 - radius;
+
+    let left_cx = MARGIN + radius;
+    pdf.text(MARGIN, pdf.y - 12.0, 10.0, Font::Regular, "Income used");
+    pdf.pie(left_cx, cy, radius, &summary.income_used_slices());
+    let percent = format!("{}%", (summary.income_used() * 100.0).round() as i32);
+    pdf.text(
+        left_cx - text_width(&percent, 18.0) / 2.0,
+        cy - 6.0,
+        18.0,
+        Font::Bold,
+        &percent,
+    );
+
+    let right_cx = MARGIN + radius * 3.0 + 40.0;
+    pdf.text(
+        right_cx - radius,
+        pdf.y - 12.0,
+        10.0,
+        Font::Regular,
+        "Expenses by category",
+    );
+    let slices = summary.category_slices();
+    pdf.pie(right_cx, cy, radius, &slices);
+
+    // Legend next to the category pie
+    let mut legend_y = pdf.y - 30.0;
+    let legend_x = right_cx + radius + 20.0;
+    for slice in slices.iter().filter(|s| s.value > 0.0) {
+        pdf.rect(legend_x, legend_y, 8.0, 8.0, slice.color);
+        pdf.text(legend_x + 12.0, legend_y, 9.0, Font::Regular, &slice.label);
+        legend_y -= 13.0;
+    }
+
+    pdf.y = cy - radius - 10.0;
+}
+
+fn write_category_table(pdf: &mut PdfWriter, summary: &Summary, locale: Locale) {
+    pdf.heading("Expenses by category");
+
+    let max = summary
+        .categories
+        .iter()
+        .map(|line| line.planned)
+        .fold(0.0_f32, f32::max)
+        .max(1.0);
+
+    pdf.row(
+        &[
+            (MARGIN, false, "Category".to_string()),
+            (330.0, true, "Planned".to_string()),
+            (420.0, true, "Recorded".to_string()),
+        ],
+        Font::Bold,
+    );
+    for (i, line) in summary.categories.iter().enumerate() {
+        pdf.row(
+            &[
+                (MARGIN, false, line.category.label().to_string()),
+                (330.0, true, amount::format(line.planned, locale)),
+                (420.0, true, amount::format(line.actual, locale)),
+            ],
+            Font::Regular,
+        );
+        let bar_width = (PAGE_WIDTH - MARGIN - 435.0) * (line.planned / max).clamp(0.0, 1.0);
+        let y = pdf.y;
+        pdf.rect(
+            435.0,
+            y,
+            PAGE_WIDTH - MARGIN - 435.0,
+            8.0,
+            (0xE6, 0xE6, 0xE6),
+        );
+        pdf.rect(
+            435.0,
+            y,
+            bar_width,
+            8.0,
+            CATEGORY_COLORS[i % CATEGORY_COLORS.len()],
+        );
+    }
+    pdf.row(
+        &[
+            (MARGIN, false, "Total".to_string()),
+            (
+                330.0,
+                true,
+                amount::format(summary.planned_expenses, locale),
+            ),
+            (420.0, true, amount::format(summary.actual_expenses, locale)),
+        ],
+        Font::Bold,
+    );
+}
+
+fn write_income(pdf: &mut PdfWriter, budget_data: &BudgetData, locale: Locale) {
+    pdf.heading("Income");
+    if budget_data.income_items.is_empty() {
+        pdf.row(
+            &[(MARGIN, false, "No income sources".to_string())],
+            Font::Regular,
+        );
+        return;
+    }
+
+    pdf.row(&amount_header("Source"), Font::Bold);
+    for income in &budget_data.income_items {
+        pdf.row(
+            &amount_columns(
+                format!("{} ({})", income.source, income.category.label()),
+                income.income_cycle.label(),
+                income.amount,
+                income.income_cycle.to_monthly(income.amount),
+                locale,
+            ),
+            Font::Regular,
+        );
+    }
+}
+
+fn write_cost_items(pdf: &mut PdfWriter, budget_data: &BudgetData, locale: Locale) {
+    pdf.heading("Cost items by category");
+
+    for category in ExpenceCategory::ALL {
+        let mut items: Vec<&CostItem> = budget_data
+            .cost_items
+            .values()
+            .filter(|item| item.cost_category == category)
+            .collect();
+        if items.is_empty() {
+            continue;
+        }
+        items.sort_by(|a, b| a.what.cmp(&b.what));
+
+        pdf.ensure_space(60.0);
+        pdf.y -= 10.0;
+        pdf.row(&amount_header(category.label()), Font::Bold);
+
+        let mut monthly_total = 0.0;
+        for item in items {
+            let monthly = item.cost_cycle.to_monthly(item.cost);
+            monthly_total += monthly;
+            pdf.row(
+                &amount_columns(
+                    item.what.clone(),
+                    item.cost_cycle.label(),
+                    item.cost,
+                    monthly,
+                    locale,
+                ),
+                Font::Regular,
+            );
+        }
+        pdf.row(
+            &[
+                (MARGIN, false, "Subtotal".to_string()),
+                (440.0, true, amount::format(monthly_total, locale)),
+                (
+                    PAGE_WIDTH - MARGIN,
+                    true,
+                    amount::format(monthly_total * 12.0, locale),
+                ),
+            ],
+            Font::Bold,
+        );
+    }
+}
+
+fn amount_header(first: &str) -> Vec<(f32, bool, String)> {
+    vec![
+        (MARGIN, false, first.to_string()),
+        (280.0, false, "Cycle".to_string()),
+        (370.0, true, "Amount".to_string()),
+        (440.0, true, "Monthly".to_string()),
+        (PAGE_WIDTH - MARGIN, true, "Yearly".to_string()),
+    ]
+}
+
+fn amount_columns(
+    name: String,
+    cycle: &str,
+    amount: f32,
+    monthly: f32,
+    locale: Locale,
+) -> Vec<(f32, bool, String)> {
+    vec![
+        (MARGIN, false, name),
+        (280.0, false, cycle.to_string()),
+        (370.0, true, amount::format(amount, locale)),
+        (440.0, true, amount::format(monthly, locale)),
+        (
+            PAGE_WIDTH - MARGIN,
+            true,
+            amount::format(monthly * 12.0, locale),
+        ),
+    ]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{BudgetEvent, fixtures::cost_item};
+
+    #[test]
+    fn the_xref_table_points_at_each_object() {
+        let mut budget_data = BudgetData::default();
+        for i in 0..80 {
+            // Enough rows for a second page
+            let event = BudgetEvent::AddCostItem(cost_item(&format!("Item {}", i), 10.0));
+            budget_data.apply(&event);
+        }
+        let pdf = render(&budget_data, ReportPeriod::Year(2025), Locale::English);
+        // The header has binary bytes, so offsets are counted in `pdf`
+        let text = String::from_utf8_lossy(&pdf);
+        let start: usize = text
+            .rsplit("startxref\n")
+            .next()
+            .and_then(|tail| tail.lines().next())
+            .and_then(|line| line.parse().ok())
+            .unwrap();
+        assert!(pdf[start..].starts_with(b"xref\n"));
+        let offsets: Vec<usize> = String::from_utf8_lossy(&pdf[start..])
+            .lines()
+            .skip(3)
+            .take_while(|line| !line.starts_with("trailer"))
+            .map(|line| line[..10].parse().unwrap())
+            .collect();
+        assert!(offsets.len() > 6, "only {} objects", offsets.len());
+        for (i, offset) in offsets.into_iter().enumerate() {
+            let header = format!("{} 0 obj\n", i + 1);
+            assert!(
+                pdf[offset..].starts_with(header.as_bytes()),
+                "object {}",
+                i + 1
+            );
+        }
+    }
+
+    #[test]
+    fn strings_use_win_ansi_codes() {
+        assert_eq!(pdf_string("5 € (café)"), "5 \\200 \\(caf\\351\\)");
+        assert_eq!(pdf_string("日本"), "??");
+    }
+}
@@ -0,0 +1,19 @@
+use resvg::{tiny_skia, usvg};
+
+/// Rasterize an SVG document at its own size. System fonts are loaded so
+/// chart labels render the same way they do in a browser.
+pub fn render(svg: &str) -> Result<Vec<u8>, String> {
+    let mut options = usvg::Options::default();
+    options.fontdb_mut().load_system_fonts();
+
+    let tree = usvg::Tree::from_str(svg, &options).map_err(|e| e.to_string())?;
+    let size = tree.size().to_int_size();
+    let mut pixmap = tiny_skia::Pixmap::new(size.width(), size.height())
+        .ok_or_else(|| format!("invalid image size {}x{}", size.width(), size.height()))?;
+
+    // White background; a transparent PNG is hard to read in most viewers.
+    pixmap.fill(tiny_skia::Color::WHITE);
+    resvg::render(&tree, tiny_skia::Transform::default(), &mut pixmap.as_mut());
+
+    pixmap.encode_png().map_err(|e| e.to_string())
+}
@@ -0,0 +1,217 @@
+use std::fmt::Write as _;
+
+use crate::amount::{self, Locale};
+
+/// One bar or pie slice.
+pub struct Slice {
+    pub label: String,
+    pub value: f32,
+    pub color: (u8, u8, u8),
+}
+
+fn rgb((r, g, b): (u8, u8, u8)) -> String {
+    format!("#{:02x}{:02x}{:02x}", r, g, b)
+}
+
+pub fn escape(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+/// Scale a chart made by this module to `width` pixels, keeping the aspect
+/// ratio. Only the root `width`/`height` change; the viewBox scales the rest.
+pub fn resize(svg: &str, width: f32) -> String {
+    let Some(root_end) = svg.find('>') else {
+        return svg.to_string();
+    };
+    let root = &svg[..root_end];
+
+    let attr = |name: &str| -> Option<String> {
+        let start = root.find(&format!(" {}=\"", name))? + name.len() + 3;
+        let len = root[start..].find('"')?;
+        Some(root[start..start + len].to_string())
+    };
+    let (Some(old_width), Some(old_height), Some(view_box)) =
+        (attr("width"), attr("height"), attr("viewBox"))
+    else {
+        return svg.to_string();
+    };
+    let view: Vec<f32> = view_box
+        .split_whitespace()
+        .filter_map(|v| v.parse().ok())
+        .collect();
+    if view.len() != 4 || view[2] <= 0.0 {
+        return svg.to_string();
+    }
+    let height = width * view[3] / view[2];
+
+    let root = root
+        .replacen(
+            &format!(" width=\"{}\"", old_width),
+            &format!(" width=\"{}\"", width),
+            1,
+        )
+        .replacen(
+            &format!(" height=\"{}\"", old_height),
+            &format!(" height=\"{}\"", height.round()),
+            1,
+        );
+    format!("{}{}", root, &svg[root_end..])
+}
+
+/// Horizontal bars with the label and value above each bar, laid out like
+/// the dashboard's "Summary by month" list. Values are written as `locale`
+/// writes them.
+pub fn bar_chart(bars: &[Slice], width: f32, locale: Locale) -> String {
+    let row_height = 40.0;
+    let bar_height = 14.0;
+    // An image needs some height, even without bars
+    let height = row_height * bars.len().max(1) as f32;
+    let max_value = bars
+        .iter()
+        .map(|b| b.value)
+        .fold(0.0_f32, f32::max)
+        .max(1.0);
+
+    let mut svg = String::new();
+    let _ = writeln!(
+        svg,
+        r#"<svg xmlns="http://www.w3.org/2000/svg" width="{w}" height="{h}" viewBox="0 0 {w} {h}" font-family="sans-serif" font-size="13">"#,
+        w = width,
+        h = height
+    );
+
+    for (i, bar) in bars.iter().enumerate() {
+        let y = i as f32 * row_height;
+        let filled = width * (bar.value / max_value).clamp(0.0, 1.0);
+        let _ = writeln!(
+            svg,
+            r#"  <text x="0" y="{ty}" font-weight="bold">{label}</text><text x="{w}" y="{ty}" text-anchor="end">{value}</text>"#,
+            ty = y + 14.0,
+            label = escape(&bar.label),
+            w = width,
+            value = amount::format(bar.value, locale)
+        );
+        let _ = writeln!(
+            svg,
+            r##"  <rect x="0" y="{by}" width="{w}" height="{bh}" rx="3" fill="#e6e6e6"/><rect x="0" y="{by}" width="{fw:.1}" height="{bh}" rx="3" fill="{color}"/>"##,
+            by = y + 20.0,
+            w = width,
+            bh = bar_height,
+            fw = filled,
+            color = rgb(bar.color)
+        );
+    }
+
+    svg.push_str("</svg>\n");
+    svg
+}
+
+/// Pie chart on a light gray disc. Slices under 1% are skipped, as on the
+/// dashboard. `center_label` is drawn on top, e.g. a percentage.
+pub fn pie_chart(slices: &[Slice], size: f32, center_label: Option<&str>) -> String {
+    let center = size / 2.0;
+    let radius = size * 0.45;
+    let total: f32 = slices.iter().map(|s| s.value.max(0.0)).sum();
+
+    let mut svg = String::new();
+    let _ = writeln!(
+        svg,
+        r#"<svg xmlns="http://www.w3.org/2000/svg" width="{s}" height="{s}" viewBox="0 0 {s} {s}" font-family="sans-serif">"#,
+        s = size
+    );
+    let _ = writeln!(
+        svg,
+        r##"  <circle cx="{c}" cy="{c}" r="{r}" fill="#d3d3d3"/>"##,
+        c = center,
+        r = radius
+    );
+
+    if total > 0.0 {
+        let mut start = -std::f32::consts::FRAC_PI_2;
+        for slice in slices {
+            let frac = (slice.value.max(0.0) / total).clamp(0.0, 1.0);
+            if frac < 0.01 {
+                continue;
+            }
+            let color = rgb(slice.color);
+
+            if frac >= 0.999 {
+                let _ = writeln!(
+                    svg,
+                    r#"  <circle cx="{c}" cy="{c}" r="{r}" fill="{color}"><title>{label}</title></circle>"#,
+                    c = center,
+                    r = radius,
+                    label = escape(&slice.label)
+                );
+                break;
+            }
+
+            let end = start + frac * std::f32::consts::TAU;
+            let large_arc = if frac > 0.5 { 1 } else { 0 };
+            let _ = writeln!(
+                svg,
+                r#"  <path d="M{c},{c} L{x1:.2},{y1:.2} A{r},{r} 0 {large_arc} 1 {x2:.2},{y2:.2} Z" fill="{color}"><title>{label}</title></path>"#,
+                c = center,
+                r = radius,
+                x1 = center + radius * start.cos(),
+                y1 = center + radius * start.sin(),
+                x2 = center + radius * end.cos(),
+                y2 = center + radius * end.sin(),
+                label = escape(&slice.label)
+            );
+            start = end;
+        }
+    }
+
+    if let Some(label) = center_label {
+        let _ = writeln!(
+            svg,
+            r#"  <text x="{c}" y="{c}" text-anchor="middle" dominant-baseline="central" font-size="22" font-weight="bold">{label}</text>"#,
+            c = center,
+            label = escape(label)
+        );
+    }
+
+    svg.push_str("</svg>\n");
+    svg
+}
+
+#[cfg(test)]
+mod tests {
+    use resvg::usvg;
+
+    use super::*;
+
+    fn slices(values: &[f32]) -> Vec<Slice> {
+        values
+            .iter()
+            .map(|&value| Slice {
+                label: "Food & <drinks>".to_string(),
+                value,
+                color: (0x12, 0x34, 0x56),
+            })
+            .collect()
+    }
+
+    fn assert_well_formed(svg: &str) {
+        assert!(!svg.contains("NaN") && !svg.contains("inf"), "{}", svg);
+        if let Err(e) = usvg::Tree::from_str(svg, &usvg::Options::default()) {
+            panic!("{}: {}", e, svg);
+        }
+    }
+
+    #[test]
+    fn charts_are_well_formed_when_empty_or_negative() {
+        for values in [&[][..], &[0.0, 0.0], &[-50.0, 20.0], &[-10.0]] {
+            assert_well_formed(&bar_chart(&slices(values), 420.0, Locale::English));
+            assert_well_formed(&pie_chart(&slices(values), 220.0, Some("-5%")));
+            assert_well_formed(&resize(
+                &bar_chart(&slices(values), 420.0, Locale::Nordic),
+                200.0,
+            ));
+        }
+    }
+}
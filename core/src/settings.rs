@@ -0,0 +1,203 @@
+use std::{
+    collections::BTreeMap,
+    path::{Path, PathBuf},
+};
+
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    amount::Locale,
+    export::journal::AccountMapping,
+    storage::{BackendKind, files},
+};
+
+/// Preferences that outlive a single budget, kept in the user's config
+/// directory.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct AppSettings {
+    pub storage: BackendKind,
+    pub file_path: PathBuf,
+    pub sqlite_path: PathBuf,
+    /// Lock an encrypted budget after this many idle minutes; 0 never locks.
+    pub auto_lock_minutes: u32,
+    /// Budgets opened before, most recent first.
+    pub recent: Vec<RecentBudget>,
+    /// Names this installation in sync folders.
+    pub device_id: String,
+    /// Sync folder of each budget, by budget path. Kept per device, as the
+    /// shared folder may be mounted elsewhere on each machine.
+    pub sync_folders: BTreeMap<PathBuf, PathBuf>,
+    pub api: ApiSettings,
+    /// How amounts are typed and shown.
+    pub locale: Locale,
+    /// Accounts of journal exports and imports, in the GUI and the CLI.
+    pub accounts: AccountMapping,
+}
+
+/// The local HTTP API for scripts and home automation.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ApiSettings {
+    pub enabled: bool,
+    pub port: u16,
+    /// Requests must send it as `Authorization: Bearer <token>`.
+    pub token: String,
+}
+
+impl Default for ApiSettings {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            port: 7878,
+            token: String::new(),
+        }
+    }
+}
+
+impl ApiSettings {
+    /// A fresh random token. Unlike device ids it must not be guessable,
+    /// so there is no fallback.
+    pub fn new_token() -> Result<String, String> {
+        let mut bytes = [0u8; 24];
+        getrandom::fill(&mut bytes).map_err(|e| e.to_string())?;
+        Ok(bytes.iter().map(|b| format!("{:02x}", b)).collect())
+    }
+}
+
+/// A budget listed in the switcher.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct RecentBudget {
+    pub kind: BackendKind,
+    pub path: PathBuf,
+    pub name: String,
+}
+
+impl RecentBudget {
+    /// The budget's own name, or its file name for budgets without one.
+    pub fn label(&self) -> String {
+        if !self.name.is_empty() {
+            return self.name.clone();
+        }
+        self.path
+            .file_stem()
+            .map(|stem| stem.to_string_lossy().into_owned())
+            .unwrap_or_else(|| self.path.display().to_string())
+    }
+}
+
+/// Settings that belong to one budget and are stored inside it.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct BudgetSettings {
+    pub name: String,
+}
+
+const MAX_RECENT: usize = 10;
+
+impl Default for AppSettings {
+    fn default() -> Self {
+        Self {
+            storage: BackendKind::File,
+            file_path: BackendKind::File.default_path(),
+            sqlite_path: BackendKind::Sqlite.default_path(),
+            auto_lock_minutes: 10,
+            recent: Vec::new(),
+            device_id: String::new(),
+            sync_folders: BTreeMap::new(),
+            api: ApiSettings::default(),
+            locale: Locale::default(),
+            accounts: AccountMapping::default(),
+        }
+    }
+}
+
+fn settings_path() -> Option<PathBuf> {
+    // The browser keeps them by name, without a config directory
+    if cfg!(target_arch = "wasm32") {
+        return Some(PathBuf::from("rusted_budget/settings.json"));
+    }
+    dirs::config_dir().map(|dir| dir.join("rusted_budget").join("settings.json"))
+}
+
+/// Random id, so devices never clash in a shared sync folder.
+fn new_device_id() -> String {
+    let mut bytes = [0u8; 8];
+    if getrandom::fill(&mut bytes).is_err() {
+        // Fall back to the clock; still unique enough between a few devices
+        let nanos = web_time::SystemTime::now()
+            .duration_since(web_time::UNIX_EPOCH)
+            .map(|d| d.as_nanos())
+            .unwrap_or_default();
+        bytes = (nanos as u64).to_le_bytes();
+    }
+    bytes.iter().map(|b| format!("{:02x}", b)).collect()
+}
+
+impl AppSettings {
+    /// Saved settings, or the defaults if there are none or they can't be read.
+    /// Call `ensure_device_id` before using `device_id`.
+    pub fn load() -> Self {
+        settings_path()
+            .and_then(|path| files::read_to_string(&path).ok())
+            .and_then(|text| serde_json::from_str(&text).ok())
+            .unwrap_or_default()
+    }
+
+    /// Give this installation a device id on first start and save it, so it
+    /// stays the same across runs.
+    pub fn ensure_device_id(&mut self) -> Result<(), String> {
+        if !self.device_id.is_empty() {
+            return Ok(());
+        }
+        self.device_id = new_device_id();
+        self.save()
+            .map_err(|err| format!("Could not save settings: {}", err))
+    }
+
+    pub fn save(&self) -> Result<(), String> {
+        let Some(path) = settings_path() else {
+            return Err("no config directory on this system".to_string());
+        };
+        let json = serde_json::to_string_pretty(self).map_err(|e| e.to_string())?;
+        files::write(&path, json.as_bytes())
+    }
+
+    pub fn path_for(&self, kind: BackendKind) -> &Path {
+        match kind {
+            BackendKind::File => &self.file_path,
+            BackendKind::Sqlite => &self.sqlite_path,
+        }
+    }
+
+    pub fn set_path_for(&mut self, kind: BackendKind, path: PathBuf) {
+        match kind {
+            BackendKind::File => self.file_path = path,
+            BackendKind::Sqlite => self.sqlite_path = path,
+        }
+    }
+
+    /// Move the budget to the top of the recent list.
+    pub fn remember(&mut self, kind: BackendKind, path: &Path, name: &str) {
+        self.forget(kind, path);
+        self.recent.insert(
+            0,
+            RecentBudget {
+                kind,
+                path: path.to_path_buf(),
+                name: name.to_string(),
+            },
+        );
+        self.recent.truncate(MAX_RECENT);
+    }
+
+    pub fn forget(&mut self, kind: BackendKind, path: &Path) {
+        self.recent
+            .retain(|recent| recent.kind != kind || recent.path != path);
+    }
+
+    /// Path of the backend currently in use.
+    pub fn storage_path(&self) -> &Path {
+        self.path_for(self.storage)
+    }
+}
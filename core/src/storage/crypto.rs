@@ -0,0 +1,233 @@
+use std::fmt;
+
+use argon2::{Algorithm, Argon2, Params, Version};
+use chacha20poly1305::{
+    XChaCha20Poly1305, XNonce,
+    aead::{Aead, KeyInit, Payload},
+};
+use zeroize::Zeroizing;
+
+/// Start of every encrypted budget file.
+const MAGIC: &[u8; 8] = b"RBUDENC1";
+const SALT_LEN: usize = 16;
+const NONCE_LEN: usize = 24;
+/// magic, three u32 Argon2 costs, salt, nonce
+const HEADER_LEN: usize = MAGIC.len() + 12 + SALT_LEN + NONCE_LEN;
+
+// Argon2id costs for new keys. They are stored in each file, so raising them
+// later still opens older files.
+const MEMORY_KIB: u32 = 64 * 1024;
+const ITERATIONS: u32 = 3;
+const PARALLELISM: u32 = 1;
+/// Highest costs a file may ask for, so a damaged or crafted header can't
+/// make unlocking take gigabytes of memory or minutes of work.
+const MAX_COSTS: [u32; 3] = [1024 * 1024, 64, 16];
+
+/// Prefix of lines in text files, like the history, encrypted with a key.
+const ENCRYPTED_LINE: &str = "enc:";
+
+/// A passphrase typed by the user. Wiped from memory when dropped and never
+/// printed by `Debug`.
+#[derive(Clone)]
+pub struct Passphrase(Zeroizing<String>);
+
+impl Passphrase {
+    pub fn new(text: String) -> Self {
+        Self(Zeroizing::new(text))
+    }
+
+    pub fn as_str(&self) -> &str {
+        &self.0
+    }
+}
+
+impl fmt::Debug for Passphrase {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("Passphrase(..)")
+    }
+}
+
+pub fn is_encrypted(data: &[u8]) -> bool {
+    data.starts_with(MAGIC)
+}
+
+pub fn is_encrypted_line(line: &str) -> bool {
+    line.starts_with(ENCRYPTED_LINE)
+}
+
+/// Key derived from a passphrase with Argon2id. Deriving is deliberately
+/// slow, so the key is kept while the budget is unlocked and reused for
+/// every save; each save still gets a fresh random nonce.
+pub struct FileKey {
+    salt: [u8; SALT_LEN],
+    costs: [u32; 3],
+    key: Zeroizing<[u8; 32]>,
+}
+
+impl FileKey {
+    /// New key with a fresh salt, for encrypting a budget for the first time
+    /// or after a passphrase change.
+    pub fn new(passphrase: &Passphrase) -> Result<Self, String> {
+        let mut salt = [0u8; SALT_LEN];
+        getrandom::fill(&mut salt).map_err(|e| e.to_string())?;
+        Self::derive(passphrase, salt, [MEMORY_KIB, ITERATIONS, PARALLELISM])
+    }
+
+    /// New key with a fresh salt and the same costs as this one.
+    pub fn new_like(&self, passphrase: &Passphrase) -> Result<Self, String> {
+        let mut salt = [0u8; SALT_LEN];
+        getrandom::fill(&mut salt).map_err(|e| e.to_string())?;
+        Self::derive(passphrase, salt, self.costs)
+    }
+
+    /// Cheap to derive, so tests don't spend seconds in Argon2.
+    #[cfg(test)]
+    pub(crate) fn for_tests(passphrase: &Passphrase) -> Self {
+        Self::derive(passphrase, [7; SALT_LEN], [8, 1, 1]).unwrap()
+    }
+
+    /// Key for an existing encrypted file, using the salt and costs from its header.
+    pub fn for_file(data: &[u8], passphrase: &Passphrase) -> Result<Self, String> {
+        let header = Header::parse(data)?;
+        Self::derive(passphrase, header.salt, header.costs)
+    }
+
+    fn derive(
+        passphrase: &Passphrase,
+        salt: [u8; SALT_LEN],
+        costs: [u32; 3],
+    ) -> Result<Self, String> {
+        let params =
+            Params::new(costs[0], costs[1], costs[2], Some(32)).map_err(|e| e.to_string())?;
+        let mut key = Zeroizing::new([0u8; 32]);
+        Argon2::new(Algorithm::Argon2id, Version::V0x13, params)
+            .hash_password_into(passphrase.as_str().as_bytes(), &salt, key.as_mut())
+            .map_err(|e| e.to_string())?;
+        Ok(Self { salt, costs, key })
+    }
+
+    /// Whether `passphrase` is the one this key was made from.
+    pub fn matches(&self, passphrase: &Passphrase) -> bool {
+        Self::derive(passphrase, self.salt, self.costs)
+            .map(|other| *other.key == *self.key)
+            .unwrap_or(false)
+    }
+
+    pub fn encrypt(&self, plaintext: &[u8]) -> Result<Vec<u8>, String> {
+        let mut nonce = [0u8; NONCE_LEN];
+        getrandom::fill(&mut nonce).map_err(|e| e.to_string())?;
+
+        let mut out = Vec::with_capacity(HEADER_LEN + plaintext.len() + 16);
+        out.extend_from_slice(MAGIC);
+        for cost in self.costs {
+            out.extend_from_slice(&cost.to_le_bytes());
+        }
+        out.extend_from_slice(&self.salt);
+        out.extend_from_slice(&nonce);
+
+        // The header is authenticated too, so its costs and salt can't be swapped
+        let cipher = XChaCha20Poly1305::new(self.key.as_ref().into());
+        let ciphertext = cipher
+            .encrypt(
+                XNonce::from_slice(&nonce),
+                Payload {
+                    msg: plaintext,
+                    aad: &out,
+                },
+            )
+            .map_err(|_| "encryption failed".to_string())?;
+        out.extend_from_slice(&ciphertext);
+        Ok(out)
+    }
+
+    /// `text` as one line of a text file, marked as encrypted.
+    pub fn encrypt_line(&self, text: &str) -> Result<String, String> {
+        let bytes = self.encrypt(text.as_bytes())?;
+        let hex: String = bytes.iter().map(|b| format!("{:02x}", b)).collect();
+        Ok(format!("{ENCRYPTED_LINE}{hex}"))
+    }
+
+    /// The text of a line written by `encrypt_line`, or `None` if this key
+    /// can't read it.
+    pub fn decrypt_line(&self, line: &str) -> Option<Zeroizing<Vec<u8>>> {
+        let hex = line.strip_prefix(ENCRYPTED_LINE)?;
+        let bytes: Vec<u8> = (0..hex.len())
+            .step_by(2)
+            .map(|i| u8::from_str_radix(hex.get(i..i + 2)?, 16).ok())
+            .collect::<Option<_>>()?;
+        self.decrypt(&bytes).ok()
+    }
+
+    pub fn decrypt(&self, data: &[u8]) -> Result<Zeroizing<Vec<u8>>, String> {
+        let header = Header::parse(data)?;
+        if header.salt != self.salt || header.costs != self.costs {
+            return Err("the file was encrypted with a different passphrase".to_string());
+        }
+
+        let cipher = XChaCha20Poly1305::new(self.key.as_ref().into());
+        cipher
+            .decrypt(
+                XNonce::from_slice(&header.nonce),
+                Payload {
+                    msg: &data[HEADER_LEN..],
+                    aad: &data[..HEADER_LEN],
+                },
+            )
+            .map(Zeroizing::new)
+            .map_err(|_| "wrong passphrase or damaged file".to_string())
+    }
+}
+
+struct Header {
+    costs: [u32; 3],
+    salt: [u8; SALT_LEN],
+    nonce: [u8; NONCE_LEN],
+}
+
+impl Header {
+    fn parse(data: &[u8]) -> Result<Self, String> {
+        if !is_encrypted(data) || data.len() < HEADER_LEN {
+            return Err("not an encrypted budget file".to_string());
+        }
+        let mut offset = MAGIC.len();
+
+        let mut costs = [0u32; 3];
+        for cost in &mut costs {
+            let mut bytes = [0u8; 4];
+            bytes.copy_from_slice(&data[offset..offset + 4]);
+            *cost = u32::from_le_bytes(bytes);
+            offset += 4;
+        }
+
+        if costs.iter().zip(MAX_COSTS).any(|(cost, max)| *cost > max) {
+            return Err("the file asks for more work to unlock than any budget needs".to_string());
+        }
+
+        let mut salt = [0u8; SALT_LEN];
+        salt.copy_from_slice(&data[offset..offset + SALT_LEN]);
+        offset += SALT_LEN;
+
+        let mut nonce = [0u8; NONCE_LEN];
+        nonce.copy_from_slice(&data[offset..offset + NONCE_LEN]);
+
+        Ok(Self { costs, salt, nonce })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn costs_in_the_header_are_checked() {
+        let passphrase = Passphrase::new("hunter2".to_string());
+        let key = FileKey::for_tests(&passphrase);
+        let mut data = key.encrypt(b"{}").unwrap();
+        assert!(FileKey::for_file(&data, &passphrase).is_ok());
+
+        // Four terabytes of memory
+        data[MAGIC.len()..MAGIC.len() + 4].copy_from_slice(&u32::MAX.to_le_bytes());
+        assert!(FileKey::for_file(&data, &passphrase).is_err());
+        assert!(key.decrypt(&data).is_err());
+    }
+}
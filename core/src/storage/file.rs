@@ -0,0 +1,68 @@
+use std::path::Path;
+
+use zeroize::Zeroizing;
+
+use crate::{
+    BudgetData,
+    storage::{
+        crypto::{self, FileKey, Passphrase},
+        files,
+    },
+};
+
+/// Whether the file at `path` holds an encrypted budget. Missing or
+/// unreadable files count as not encrypted.
+pub fn is_encrypted(path: &Path) -> bool {
+    files::read(path).is_ok_and(|data| crypto::is_encrypted(&data))
+}
+
+/// Read a budget saved by `save`. A missing file is an empty budget;
+/// encrypted files need the key they were saved with.
+pub fn load(path: &Path, key: Option<&FileKey>) -> Result<BudgetData, String> {
+    if !files::exists(path) {
+        return Ok(BudgetData::default());
+    }
+    let data = files::read(path)?;
+
+    if crypto::is_encrypted(&data) {
+        let Some(key) = key else {
+            return Err(format!("{}: the budget is encrypted", path.display()));
+        };
+        let plaintext = key
+            .decrypt(&data)
+            .map_err(|e| format!("{}: {}", path.display(), e))?;
+        parse(path, &plaintext)
+    } else {
+        parse(path, &data)
+    }
+}
+
+/// Derive the key for an encrypted file from `passphrase` and read it.
+pub fn unlock(path: &Path, passphrase: &Passphrase) -> Result<(BudgetData, FileKey), String> {
+    let data = files::read(path)?;
+    let key =
+        FileKey::for_file(&data, passphrase).map_err(|e| format!("{}: {}", path.display(), e))?;
+    let budget_data = load(path, Some(&key))?;
+    Ok((budget_data, key))
+}
+
+fn parse(path: &Path, data: &[u8]) -> Result<BudgetData, String> {
+    let mut budget_data: BudgetData =
+        serde_json::from_slice(data).map_err(|e| format!("{}: {}", path.display(), e))?;
+    budget_data.number_income_items();
+    Ok(budget_data)
+}
+
+/// Write the whole budget as JSON, encrypted when a key is given.
+pub fn save(path: &Path, budget_data: &BudgetData, key: Option<&FileKey>) -> Result<(), String> {
+    let json = Zeroizing::new(
+        serde_json::to_vec_pretty(budget_data).map_err(|e| format!("{}: {}", path.display(), e))?,
+    );
+    let contents = match key {
+        Some(key) => key
+            .encrypt(&json)
+            .map_err(|e| format!("{}: {}", path.display(), e))?,
+        None => json.to_vec(),
+    };
+    files::write(path, &contents)
+}
@@ -0,0 +1,118 @@
+//! Whole files in and out of storage. The web build has no file system, so
+//! there the same paths name entries in the browser's local storage, and
+//! budgets, their history and the settings are kept just as on the desktop.
+
+use std::path::Path;
+
+#[cfg(not(target_arch = "wasm32"))]
+pub use native::*;
+#[cfg(target_arch = "wasm32")]
+pub use web::*;
+
+#[cfg(not(target_arch = "wasm32"))]
+mod native {
+    use std::{fs::OpenOptions, io::Write, path::Path};
+
+    pub fn exists(path: &Path) -> bool {
+        path.exists()
+    }
+
+    pub fn read(path: &Path) -> Result<Vec<u8>, String> {
+        std::fs::read(path).map_err(|e| format!("{}: {}", path.display(), e))
+    }
+
+    /// Replace the file with `contents`. It is written next to the target
+    /// and renamed over it, so a crash never leaves half a file behind.
+    pub fn write(path: &Path, contents: &[u8]) -> Result<(), String> {
+        create_parent(path)?;
+        let tmp = path.with_extension("tmp");
+        std::fs::write(&tmp, contents).map_err(|e| format!("{}: {}", tmp.display(), e))?;
+        std::fs::rename(&tmp, path).map_err(|e| format!("{}: {}", path.display(), e))
+    }
+
+    pub fn append(path: &Path, text: &str) -> Result<(), String> {
+        create_parent(path)?;
+        let mut file = OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(path)
+            .map_err(|e| format!("{}: {}", path.display(), e))?;
+        file.write_all(text.as_bytes())
+            .map_err(|e| format!("{}: {}", path.display(), e))
+    }
+
+    /// Create the folder `path` goes in.
+    pub fn create_parent(path: &Path) -> Result<(), String> {
+        if let Some(parent) = path.parent().filter(|p| !p.as_os_str().is_empty()) {
+            std::fs::create_dir_all(parent).map_err(|e| format!("{}: {}", parent.display(), e))?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(target_arch = "wasm32")]
+mod web {
+    use std::path::Path;
+
+    use base64::{Engine, engine::general_purpose::STANDARD};
+    use web_sys::Storage;
+
+    /// Marks entries that aren't text, like encrypted budgets.
+    const BASE64: &str = "base64:";
+
+    fn local_storage(path: &Path) -> Result<Storage, String> {
+        web_sys::window()
+            .and_then(|window| window.local_storage().ok().flatten())
+            .ok_or_else(|| format!("{}: the browser has no local storage", path.display()))
+    }
+
+    fn get(path: &Path) -> Result<Option<String>, String> {
+        local_storage(path)?
+            .get_item(&path.to_string_lossy())
+            .map_err(|e| format!("{}: {:?}", path.display(), e))
+    }
+
+    fn set(path: &Path, value: &str) -> Result<(), String> {
+        local_storage(path)?
+            .set_item(&path.to_string_lossy(), value)
+            .map_err(|_| format!("{}: the browser's storage is full", path.display()))
+    }
+
+    pub fn exists(path: &Path) -> bool {
+        get(path).is_ok_and(|value| value.is_some())
+    }
+
+    pub fn read(path: &Path) -> Result<Vec<u8>, String> {
+        let value = get(path)?.ok_or_else(|| format!("{}: not found", path.display()))?;
+        match value.strip_prefix(BASE64) {
+            Some(encoded) => STANDARD
+                .decode(encoded)
+                .map_err(|e| format!("{}: {}", path.display(), e)),
+            None => Ok(value.into_bytes()),
+        }
+    }
+
+    /// Replace the entry with `contents`. Local storage writes are atomic.
+    pub fn write(path: &Path, contents: &[u8]) -> Result<(), String> {
+        match std::str::from_utf8(contents) {
+            Ok(text) => set(path, text),
+            Err(_) => set(path, &format!("{}{}", BASE64, STANDARD.encode(contents))),
+        }
+    }
+
+    pub fn append(path: &Path, text: &str) -> Result<(), String> {
+        let mut value = get(path)?.unwrap_or_default();
+        value.push_str(text);
+        set(path, &value)
+    }
+
+    /// Entries need no folders.
+    pub fn create_parent(_path: &Path) -> Result<(), String> {
+        Ok(())
+    }
+}
+
+/// The file as text.
+pub fn read_to_string(path: &Path) -> Result<String, String> {
+    String::from_utf8(read(path)?).map_err(|e| format!("{}: {}", path.display(), e))
+}
@@ -0,0 +1,275 @@
+use std::path::{Path, PathBuf};
+
+use chrono::{DateTime, Local};
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    BudgetData, BudgetEvent,
+    models::{cost_item::CostItem, income_item::IncomeItem, transaction::Transaction},
+    storage::{
+        crypto::{self, FileKey},
+        files,
+        merge::ItemKey,
+    },
+};
+
+/// A change as kept in the history.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub enum Recorded {
+    /// The whole budget, where it changed other than through an event,
+    /// e.g. when history starts or changes arrive from another device.
+    Snapshot { reason: String, budget: BudgetData },
+    /// Written as the bare event, e.g. `{"DeleteCostItem":3}`.
+    #[serde(untagged)]
+    Event(BudgetEvent),
+}
+
+/// A cost item, income item or transaction as it was before or after a change.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub enum Item {
+    Cost(CostItem),
+    Income(IncomeItem),
+    Transaction(Transaction),
+}
+
+impl Item {
+    pub fn name(&self) -> &str {
+        match self {
+            Item::Cost(item) => &item.what,
+            Item::Income(item) => &item.source,
+            Item::Transaction(transaction) => &transaction.description,
+        }
+    }
+
+    pub fn describe(&self) -> String {
+        match self {
+            Item::Cost(item) => format!(
+                "${:.2} {} ({})",
+                item.cost,
+                item.cost_cycle.label(),
+                item.cost_category.label()
+            ),
+            Item::Income(item) => format!(
+                "${:.2} {} ({})",
+                item.amount,
+                item.income_cycle.label(),
+                item.category.label()
+            ),
+            Item::Transaction(transaction) => format!(
+                "${:.2} on {} ({})",
+                transaction.amount,
+                transaction.date,
+                transaction.category.label()
+            ),
+        }
+    }
+}
+
+/// What one event did to one item; `None` means absent.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ItemChange {
+    pub key: ItemKey,
+    pub before: Option<Item>,
+    pub after: Option<Item>,
+}
+
+impl ItemChange {
+    /// The event that undoes this change.
+    pub fn revert_event(&self) -> Option<BudgetEvent> {
+        Some(match (self.key, &self.before, &self.after) {
+            (ItemKey::CostItem(id), None, Some(_)) => BudgetEvent::DeleteCostItem(id),
+            (ItemKey::CostItem(id), Some(Item::Cost(item)), Some(_)) => {
+                BudgetEvent::UpdateCostItem {
+                    id,
+                    item: item.clone(),
+                }
+            }
+            (ItemKey::CostItem(_), Some(Item::Cost(item)), None) => {
+                BudgetEvent::RestoreCostItem(item.clone())
+            }
+            (ItemKey::IncomeItem(id), None, Some(_)) => BudgetEvent::DeleteIncomeItem(id),
+            (ItemKey::IncomeItem(id), Some(Item::Income(item)), Some(_)) => {
+                BudgetEvent::UpdateIncomeItem {
+                    id,
+                    item: item.clone(),
+                }
+            }
+            (ItemKey::IncomeItem(id), Some(Item::Income(item)), None) => {
+                BudgetEvent::RestoreIncomeItem(IncomeItem { id, ..item.clone() })
+            }
+            (ItemKey::Transaction(id), None, Some(_)) => BudgetEvent::DeleteTransaction(id),
+            (ItemKey::Transaction(_), Some(Item::Transaction(transaction)), None) => {
+                BudgetEvent::RestoreTransaction(transaction.clone())
+            }
+            _ => return None,
+        })
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct HistoryEntry {
+    pub time: DateTime<Local>,
+    /// User and device that made the change.
+    pub who: String,
+    pub change: Recorded,
+    /// Cost and income items the change touched.
+    pub items: Vec<ItemChange>,
+}
+
+impl HistoryEntry {
+    pub fn new(who: &str, change: Recorded, items: Vec<ItemChange>) -> Self {
+        Self {
+            time: Local::now(),
+            who: who.to_string(),
+            change,
+            items,
+        }
+    }
+}
+
+/// Names the author of changes made here.
+pub fn who(device_id: &str) -> String {
+    let user = std::env::var("USER")
+        .or_else(|_| std::env::var("USERNAME"))
+        .unwrap_or_else(|_| "unknown".to_string());
+    format!("{}@{}", user, device_id)
+}
+
+/// History of the budget stored at `budget_path`, kept next to it.
+pub fn path_for(budget_path: &Path) -> PathBuf {
+    let mut name = budget_path.as_os_str().to_os_string();
+    name.push(".history.jsonl");
+    PathBuf::from(name)
+}
+
+/// Lines are encrypted with the budget's key, if it has one.
+fn encode(entry: &HistoryEntry, key: Option<&FileKey>) -> Result<String, String> {
+    let json = serde_json::to_string(entry).map_err(|e| e.to_string())?;
+    match key {
+        Some(key) => key.encrypt_line(&json),
+        None => Ok(json),
+    }
+}
+
+fn decode(line: &str, key: Option<&FileKey>) -> Option<HistoryEntry> {
+    if !crypto::is_encrypted_line(line) {
+        return serde_json::from_str(line).ok();
+    }
+    let json = key?.decrypt_line(line)?;
+    serde_json::from_slice(&json).ok()
+}
+
+/// Add entries to the end of the history.
+pub fn append(path: &Path, entries: &[HistoryEntry], key: Option<&FileKey>) -> Result<(), String> {
+    let mut lines = String::new();
+    for entry in entries {
+        lines.push_str(&encode(entry, key)?);
+        lines.push('\n');
+    }
+    files::append(path, &lines)
+}
+
+/// All readable entries, oldest first. Lines that can't be read with `key`
+/// are skipped.
+pub fn read(path: &Path, key: Option<&FileKey>) -> Result<Vec<HistoryEntry>, String> {
+    if !files::exists(path) {
+        return Ok(Vec::new());
+    }
+    let text = files::read_to_string(path)?;
+    Ok(text.lines().filter_map(|line| decode(line, key)).collect())
+}
+
+/// Write the history again under another key, after a passphrase change.
+pub fn rekey(path: &Path, old: Option<&FileKey>, new: Option<&FileKey>) -> Result<(), String> {
+    if !files::exists(path) {
+        return Ok(());
+    }
+    let entries = read(path, old)?;
+    let mut text = String::new();
+    for entry in &entries {
+        text.push_str(&encode(entry, new)?);
+        text.push('\n');
+    }
+    files::write(path, text.as_bytes())
+}
+
+/// Rebuild the budget by applying every recorded change in order.
+pub fn replay(entries: &[HistoryEntry]) -> BudgetData {
+    let mut budget_data = BudgetData::default();
+    for entry in entries {
+        match &entry.change {
+            Recorded::Snapshot { budget, .. } => budget_data = budget.clone(),
+            Recorded::Event(event) => budget_data.apply(event),
+        }
+    }
+    budget_data
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::fixtures::cost_item;
+
+    #[test]
+    fn events_are_written_bare() {
+        let change = Recorded::Event(BudgetEvent::DeleteCostItem(3));
+        assert_eq!(
+            serde_json::to_string(&change).unwrap(),
+            r#"{"DeleteCostItem":3}"#
+        );
+        let read: Recorded = serde_json::from_str(r#"{"DeleteCostItem":3}"#).unwrap();
+        assert!(matches!(
+            read,
+            Recorded::Event(BudgetEvent::DeleteCostItem(3))
+        ));
+
+        let snapshot = Recorded::Snapshot {
+            reason: "History started".to_string(),
+            budget: BudgetData::default(),
+        };
+        let json = serde_json::to_string(&snapshot).unwrap();
+        assert!(json.starts_with(r#"{"Snapshot":"#));
+        let read: Recorded = serde_json::from_str(&json).unwrap();
+        assert!(matches!(read, Recorded::Snapshot { reason, .. } if reason == "History started"));
+    }
+
+    #[test]
+    fn replay_rebuilds_the_budget() {
+        let mut start = BudgetData::default();
+        start.apply(&BudgetEvent::AddCostItem(cost_item("Rent", 900.0)));
+        let events = [
+            BudgetEvent::AddCostItem(cost_item("Rent", 100.0)),
+            BudgetEvent::UpdateCostItem {
+                id: 0,
+                item: cost_item("Rent", 950.0),
+            },
+            BudgetEvent::DeleteCostItem(1),
+            BudgetEvent::RenameBudget("Household".to_string()),
+        ];
+
+        let mut live = start.clone();
+        let mut entries = vec![HistoryEntry::new(
+            "me",
+            Recorded::Snapshot {
+                reason: "History started".to_string(),
+                budget: start,
+            },
+            Vec::new(),
+        )];
+        for event in events {
+            let applied = live.apply_tracked(&event);
+            entries.push(HistoryEntry::new(
+                "me",
+                Recorded::Event(event),
+                applied.items,
+            ));
+        }
+        assert_eq!(replay(&entries), live);
+
+        let update = &entries[2].items[0];
+        assert_eq!(update.key, ItemKey::CostItem(0));
+        let revert = update.revert_event().unwrap();
+        live.apply(&revert);
+        assert_eq!(live.cost_items[&0].cost, 900.0);
+    }
+}
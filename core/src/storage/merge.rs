@@ -0,0 +1,421 @@
+use std::collections::{BTreeMap, BTreeSet};
+
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    BudgetData,
+    models::{cost_item::CostItem, income_item::IncomeItem, transaction::Transaction},
+    settings::BudgetSettings,
+};
+
+/// An item in a budget, by id.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub enum ItemKey {
+    CostItem(u64),
+    IncomeItem(u64),
+    Transaction(u64),
+}
+
+impl ItemKey {
+    pub fn kind(&self) -> &'static str {
+        match self {
+            ItemKey::CostItem(_) => "Cost item",
+            ItemKey::IncomeItem(_) => "Income",
+            ItemKey::Transaction(_) => "Transaction",
+        }
+    }
+}
+
+/// An item changed differently here and on disk. `None` means deleted.
+#[derive(Debug, Clone)]
+pub struct Conflict {
+    pub key: ItemKey,
+    pub mine: Option<String>,
+    pub theirs: Option<String>,
+}
+
+/// Three-way merge of the open budget with the one found on disk, using
+/// the budget as last loaded or saved as the common base. Changes made on
+/// only one side are taken as they are; items changed on both sides are
+/// conflicts, which keep the local version until resolved.
+pub struct Merge {
+    cost_items: BTreeMap<u64, CostItem>,
+    income_items: BTreeMap<u64, IncomeItem>,
+    transactions: BTreeMap<u64, Transaction>,
+    next_cost_id: u64,
+    next_income_id: u64,
+    next_transaction_id: u64,
+    name: String,
+    theirs: BudgetData,
+    pub conflicts: Vec<Conflict>,
+}
+
+/// One collection merged item by item.
+struct Merged<K, V> {
+    items: BTreeMap<K, V>,
+    conflicts: Vec<K>,
+    /// Different items both sides added under the same key; theirs is kept
+    /// here to be added again under a fresh key.
+    added_twice: Vec<V>,
+}
+
+fn merge_items<K: Ord + Copy, V: Clone + PartialEq>(
+    base: &BTreeMap<K, &V>,
+    mine: &BTreeMap<K, &V>,
+    theirs: &BTreeMap<K, &V>,
+) -> Merged<K, V> {
+    let keys: BTreeSet<K> = base
+        .keys()
+        .chain(mine.keys())
+        .chain(theirs.keys())
+        .copied()
+        .collect();
+
+    let mut merged = Merged {
+        items: BTreeMap::new(),
+        conflicts: Vec::new(),
+        added_twice: Vec::new(),
+    };
+    for key in keys {
+        let base = base.get(&key).copied();
+        let mine = mine.get(&key).copied();
+        let theirs = theirs.get(&key).copied();
+
+        let pick = if mine == theirs || theirs == base {
+            mine
+        } else if mine == base {
+            theirs
+        } else {
+            match (base, theirs) {
+                (None, Some(added)) => merged.added_twice.push(added.clone()),
+                _ => merged.conflicts.push(key),
+            }
+            mine
+        };
+        if let Some(item) = pick {
+            merged.items.insert(key, item.clone());
+        }
+    }
+    merged
+}
+
+fn cost_items(budget_data: &BudgetData) -> BTreeMap<u64, &CostItem> {
+    budget_data
+        .cost_items
+        .iter()
+        .map(|(id, item)| (*id, item))
+        .collect()
+}
+
+fn income_items(budget_data: &BudgetData) -> BTreeMap<u64, &IncomeItem> {
+    budget_data
+        .income_items
+        .iter()
+        .map(|item| (item.id, item))
+        .collect()
+}
+
+fn transactions(budget_data: &BudgetData) -> BTreeMap<u64, &Transaction> {
+    budget_data
+        .transactions
+        .iter()
+        .map(|transaction| (transaction.id, transaction))
+        .collect()
+}
+
+fn describe_cost_item(item: &CostItem) -> String {
+    format!(
+        "{}: ${:.2} {} ({})",
+        item.what,
+        item.cost,
+        item.cost_cycle.label(),
+        item.cost_category.label()
+    )
+}
+
+fn describe_income_item(item: &IncomeItem) -> String {
+    format!(
+        "{}: ${:.2} {} ({})",
+        item.source,
+        item.amount,
+        item.income_cycle.label(),
+        item.category.label()
+    )
+}
+
+fn describe_transaction(transaction: &Transaction) -> String {
+    format!(
+        "{} {}: ${:.2} ({})",
+        transaction.date,
+        transaction.description,
+        transaction.amount,
+        transaction.category.label()
+    )
+}
+
+impl Merge {
+    pub fn new(base: &BudgetData, mine: &BudgetData, theirs: BudgetData) -> Self {
+        let costs = merge_items(&cost_items(base), &cost_items(mine), &cost_items(&theirs));
+        let incomes = merge_items(
+            &income_items(base),
+            &income_items(mine),
+            &income_items(&theirs),
+        );
+        let moves = merge_items(
+            &transactions(base),
+            &transactions(mine),
+            &transactions(&theirs),
+        );
+
+        let mut conflicts = Vec::new();
+        for id in costs.conflicts {
+            conflicts.push(Conflict {
+                key: ItemKey::CostItem(id),
+                mine: mine.cost_items.get(&id).map(describe_cost_item),
+                theirs: theirs.cost_items.get(&id).map(describe_cost_item),
+            });
+        }
+        let find_income = |budget_data: &BudgetData, id: u64| {
+            budget_data
+                .income_items
+                .iter()
+                .find(|item| item.id == id)
+                .map(describe_income_item)
+        };
+        for id in incomes.conflicts {
+            conflicts.push(Conflict {
+                key: ItemKey::IncomeItem(id),
+                mine: find_income(mine, id),
+                theirs: find_income(&theirs, id),
+            });
+        }
+        let find = |budget_data: &BudgetData, id: u64| {
+            budget_data
+                .transactions
+                .iter()
+                .find(|transaction| transaction.id == id)
+                .map(describe_transaction)
+        };
+        for id in moves.conflicts {
+            conflicts.push(Conflict {
+                key: ItemKey::Transaction(id),
+                mine: find(mine, id),
+                theirs: find(&theirs, id),
+            });
+        }
+
+        let mut merge = Self {
+            next_cost_id: mine
+                .next_cost_id
+                .max(theirs.next_cost_id)
+                .max(costs.items.keys().last().map_or(0, |id| id + 1)),
+            next_income_id: mine
+                .next_income_id
+                .max(theirs.next_income_id)
+                .max(incomes.items.keys().last().map_or(0, |id| id + 1)),
+            next_transaction_id: mine
+                .next_transaction_id
+                .max(theirs.next_transaction_id)
+                .max(moves.items.keys().last().map_or(0, |id| id + 1)),
+            cost_items: costs.items,
+            income_items: incomes.items,
+            transactions: moves.items,
+            name: if mine.settings.name == base.settings.name {
+                theirs.settings.name.clone()
+            } else {
+                mine.settings.name.clone()
+            },
+            theirs,
+            conflicts,
+        };
+
+        // Both sides added items under the same new key; keep both
+        for mut item in costs.added_twice {
+            item.id = merge.next_cost_id;
+            merge.next_cost_id += 1;
+            merge.cost_items.insert(item.id, item);
+        }
+        for mut item in incomes.added_twice {
+            item.id = merge.next_income_id;
+            merge.next_income_id += 1;
+            merge.income_items.insert(item.id, item);
+        }
+        for mut transaction in moves.added_twice {
+            transaction.id = merge.next_transaction_id;
+            merge.next_transaction_id += 1;
+            merge.transactions.insert(transaction.id, transaction);
+        }
+        merge
+    }
+
+    /// The merged budget, taking the version on disk for every conflict
+    /// whose entry in `take_theirs` is true.
+    pub fn resolve(mut self, take_theirs: &[bool]) -> BudgetData {
+        for (conflict, _) in self
+            .conflicts
+            .iter()
+            .zip(take_theirs)
+            .filter(|(_, theirs)| **theirs)
+        {
+            match conflict.key {
+                ItemKey::CostItem(id) => match self.theirs.cost_items.get(&id) {
+                    Some(item) => {
+                        self.cost_items.insert(id, item.clone());
+                    }
+                    None => {
+                        self.cost_items.remove(&id);
+                    }
+                },
+                ItemKey::IncomeItem(id) => {
+                    match self.theirs.income_items.iter().find(|i| i.id == id) {
+                        Some(item) => {
+                            self.income_items.insert(id, item.clone());
+                        }
+                        None => {
+                            self.income_items.remove(&id);
+                        }
+                    }
+                }
+                ItemKey::Transaction(id) => {
+                    match self.theirs.transactions.iter().find(|t| t.id == id) {
+                        Some(transaction) => {
+                            self.transactions.insert(id, transaction.clone());
+                        }
+                        None => {
+                            self.transactions.remove(&id);
+                        }
+                    }
+                }
+            }
+        }
+
+        BudgetData {
+            cost_items: self.cost_items.into_iter().collect(),
+            income_items: self.income_items.into_values().collect(),
+            transactions: self.transactions.into_values().collect(),
+            next_cost_id: self.next_cost_id,
+            next_transaction_id: self.next_transaction_id,
+            next_income_id: self.next_income_id,
+            settings: BudgetSettings { name: self.name },
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{event::BudgetEvent, fixtures::income_item};
+
+    fn base() -> BudgetData {
+        let mut budget_data = BudgetData::default();
+        for (source, amount) in [("Salary", 3000.0), ("Bonus", 500.0), ("Rent", 800.0)] {
+            budget_data.apply(&BudgetEvent::AddIncomeItem(income_item(source, amount)));
+        }
+        budget_data
+    }
+
+    fn sources(budget_data: &BudgetData) -> Vec<(u64, &str, f32)> {
+        budget_data
+            .income_items
+            .iter()
+            .map(|item| (item.id, item.source.as_str(), item.amount))
+            .collect()
+    }
+
+    #[test]
+    fn edits_on_both_sides_conflict() {
+        let base = base();
+        let mut mine = base.clone();
+        mine.apply(&BudgetEvent::UpdateIncomeItem {
+            id: 1,
+            item: income_item("Bonus", 600.0),
+        });
+        let mut theirs = base.clone();
+        theirs.apply(&BudgetEvent::UpdateIncomeItem {
+            id: 1,
+            item: income_item("Bonus", 700.0),
+        });
+        theirs.apply(&BudgetEvent::UpdateIncomeItem {
+            id: 2,
+            item: income_item("Rent", 850.0),
+        });
+
+        let merge = Merge::new(&base, &mine, theirs.clone());
+        assert_eq!(merge.conflicts.len(), 1);
+        assert_eq!(merge.conflicts[0].key, ItemKey::IncomeItem(1));
+
+        let kept = Merge::new(&base, &mine, theirs.clone()).resolve(&[false]);
+        assert_eq!(
+            sources(&kept),
+            [
+                (0, "Salary", 3000.0),
+                (1, "Bonus", 600.0),
+                (2, "Rent", 850.0)
+            ]
+        );
+        let taken = merge.resolve(&[true]);
+        assert_eq!(sources(&taken)[1], (1, "Bonus", 700.0));
+    }
+
+    #[test]
+    fn a_delete_does_not_shift_edits() {
+        let base = base();
+        let mut mine = base.clone();
+        mine.apply(&BudgetEvent::DeleteIncomeItem(0));
+        let mut theirs = base.clone();
+        theirs.apply(&BudgetEvent::UpdateIncomeItem {
+            id: 2,
+            item: income_item("Rent", 850.0),
+        });
+
+        let merge = Merge::new(&base, &mine, theirs);
+        assert!(merge.conflicts.is_empty());
+        assert_eq!(
+            sources(&merge.resolve(&[])),
+            [(1, "Bonus", 500.0), (2, "Rent", 850.0)]
+        );
+    }
+
+    #[test]
+    fn deleting_an_edited_item_conflicts() {
+        let base = base();
+        let mut mine = base.clone();
+        mine.apply(&BudgetEvent::DeleteIncomeItem(1));
+        let mut theirs = base.clone();
+        theirs.apply(&BudgetEvent::UpdateIncomeItem {
+            id: 1,
+            item: income_item("Bonus", 700.0),
+        });
+
+        let merge = Merge::new(&base, &mine, theirs);
+        assert_eq!(merge.conflicts.len(), 1);
+        assert_eq!(merge.conflicts[0].mine, None);
+        assert_eq!(
+            sources(&merge.resolve(&[true])),
+            [
+                (0, "Salary", 3000.0),
+                (1, "Bonus", 700.0),
+                (2, "Rent", 800.0)
+            ]
+        );
+    }
+
+    #[test]
+    fn adds_on_both_sides_are_kept() {
+        let base = base();
+        let mut mine = base.clone();
+        mine.apply(&BudgetEvent::AddIncomeItem(income_item("Gift", 100.0)));
+        let mut theirs = base.clone();
+        theirs.apply(&BudgetEvent::AddIncomeItem(income_item("Dividend", 40.0)));
+
+        let merge = Merge::new(&base, &mine, theirs);
+        assert!(merge.conflicts.is_empty());
+        let mut merged = merge.resolve(&[]);
+        assert_eq!(
+            sources(&merged)[3..],
+            [(3, "Gift", 100.0), (4, "Dividend", 40.0)]
+        );
+        merged.apply(&BudgetEvent::AddIncomeItem(income_item("Tips", 20.0)));
+        assert_eq!(merged.income_items[5].id, 5);
+    }
+}
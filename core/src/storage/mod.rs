@@ -0,0 +1,93 @@
+pub mod crypto;
+pub mod file;
+pub mod files;
+pub mod history;
+pub mod merge;
+#[cfg(feature = "sqlite")]
+pub mod sqlite;
+pub mod sync;
+
+use std::{
+    path::{Path, PathBuf},
+    time::SystemTime,
+};
+
+use serde::{Deserialize, Serialize};
+
+use crate::{BudgetData, storage::crypto::FileKey};
+
+/// Where the budget is kept between runs.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub enum BackendKind {
+    /// One JSON file, rewritten on every save.
+    File,
+    /// SQLite database with one table per kind of record.
+    Sqlite,
+}
+
+impl BackendKind {
+    /// Backends compiled into this build.
+    pub fn available() -> Vec<BackendKind> {
+        let mut kinds = vec![BackendKind::File];
+        if cfg!(feature = "sqlite") {
+            kinds.push(BackendKind::Sqlite);
+        }
+        kinds
+    }
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            BackendKind::File => "File (JSON)",
+            BackendKind::Sqlite => "SQLite database",
+        }
+    }
+
+    pub fn default_path(&self) -> PathBuf {
+        let dir = dirs::data_dir()
+            .map(|dir| dir.join("rusted_budget"))
+            .unwrap_or_default();
+        match self {
+            BackendKind::File => dir.join("budget.json"),
+            BackendKind::Sqlite => dir.join("budget.sqlite3"),
+        }
+    }
+}
+
+/// Only the file backend can be encrypted.
+pub fn is_encrypted(kind: BackendKind, path: &Path) -> bool {
+    kind == BackendKind::File && file::is_encrypted(path)
+}
+
+/// When the stored budget was last written, by us or anyone else.
+pub fn modified(path: &Path) -> Option<SystemTime> {
+    std::fs::metadata(path)
+        .and_then(|meta| meta.modified())
+        .ok()
+}
+
+pub fn load(kind: BackendKind, path: &Path, key: Option<&FileKey>) -> Result<BudgetData, String> {
+    match kind {
+        BackendKind::File => file::load(path, key),
+        #[cfg(feature = "sqlite")]
+        BackendKind::Sqlite => sqlite::load(path),
+        #[cfg(not(feature = "sqlite"))]
+        BackendKind::Sqlite => Err("this build has no SQLite support".to_string()),
+    }
+}
+
+pub fn save(
+    kind: BackendKind,
+    path: &Path,
+    budget_data: &BudgetData,
+    key: Option<&FileKey>,
+) -> Result<(), String> {
+    files::create_parent(path)?;
+    match (kind, key) {
+        (BackendKind::File, _) => file::save(path, budget_data, key),
+        (BackendKind::Sqlite, Some(_)) => Err("only the file backend can be encrypted".to_string()),
+        #[cfg(feature = "sqlite")]
+        (BackendKind::Sqlite, None) => sqlite::save(path, budget_data),
+        #[cfg(not(feature = "sqlite"))]
+        (BackendKind::Sqlite, None) => Err("this build has no SQLite support".to_string()),
+    }
+}
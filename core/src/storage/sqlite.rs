@@ -0,0 +1,582 @@
+use std::{collections::HashMap, path::Path};
+
+use chrono::NaiveDate;
+use rusqlite::{Connection, OptionalExtension, Transaction as SqlTransaction, params};
+
+use crate::{
+    BudgetData,
+    models::{
+        cost_item::{CostCycle, CostItem, ExpenceCategory},
+        income_item::{IncomeCategory, IncomeItem},
+        transaction::{Transaction, TransactionCategory},
+    },
+    report::ReportPeriod,
+};
+
+/// Schema changes, applied in order. `PRAGMA user_version` records how many
+/// have run; append new steps here and never edit old ones.
+const MIGRATIONS: &[&str] = &[
+    // 1: initial schema
+    "
+    CREATE TABLE categories (
+        id INTEGER PRIMARY KEY,
+        kind TEXT NOT NULL CHECK (kind IN ('expense', 'income')),
+        name TEXT NOT NULL,
+        UNIQUE (kind, name)
+    );
+    CREATE TABLE tags (
+        id INTEGER PRIMARY KEY,
+        name TEXT NOT NULL UNIQUE
+    );
+    CREATE TABLE cost_items (
+        id INTEGER PRIMARY KEY,
+        what TEXT NOT NULL,
+        cost REAL NOT NULL,
+        cycle TEXT NOT NULL,
+        category_id INTEGER NOT NULL REFERENCES categories (id)
+    );
+    CREATE TABLE cost_item_tags (
+        cost_item_id INTEGER NOT NULL REFERENCES cost_items (id) ON DELETE CASCADE,
+        tag_id INTEGER NOT NULL REFERENCES tags (id),
+        PRIMARY KEY (cost_item_id, tag_id)
+    );
+    CREATE TABLE income_items (
+        id INTEGER PRIMARY KEY,
+        source TEXT NOT NULL,
+        amount REAL NOT NULL,
+        cycle TEXT NOT NULL,
+        category_id INTEGER NOT NULL REFERENCES categories (id)
+    );
+    CREATE TABLE income_item_tags (
+        income_item_id INTEGER NOT NULL REFERENCES income_items (id) ON DELETE CASCADE,
+        tag_id INTEGER NOT NULL REFERENCES tags (id),
+        PRIMARY KEY (income_item_id, tag_id)
+    );
+    CREATE TABLE transactions (
+        id INTEGER PRIMARY KEY,
+        date TEXT NOT NULL,
+        description TEXT NOT NULL,
+        amount REAL NOT NULL,
+        category_id INTEGER NOT NULL REFERENCES categories (id)
+    );
+    CREATE INDEX transactions_date ON transactions (date);
+    CREATE TABLE transaction_tags (
+        transaction_id INTEGER NOT NULL REFERENCES transactions (id) ON DELETE CASCADE,
+        tag_id INTEGER NOT NULL REFERENCES tags (id),
+        PRIMARY KEY (transaction_id, tag_id)
+    );
+    -- Per-budget settings and the next ids to hand out, so ids of
+    -- deleted items aren't reused; a single row
+    CREATE TABLE budget_settings (
+        id INTEGER PRIMARY KEY CHECK (id = 1),
+        name TEXT NOT NULL,
+        next_cost_id INTEGER,
+        next_income_id INTEGER,
+        next_transaction_id INTEGER
+    );
+    ",
+];
+
+fn sql_error(path: &Path, e: rusqlite::Error) -> String {
+    format!("{}: {}", path.display(), e)
+}
+
+/// Open (or create) the database and bring its schema up to date.
+fn open(path: &Path) -> Result<Connection, String> {
+    let mut conn = Connection::open(path).map_err(|e| sql_error(path, e))?;
+    conn.pragma_update(None, "foreign_keys", true)
+        .map_err(|e| sql_error(path, e))?;
+    migrate(&mut conn).map_err(|e| sql_error(path, e))?;
+    Ok(conn)
+}
+
+fn migrate(conn: &mut Connection) -> rusqlite::Result<()> {
+    let version: usize = conn.pragma_query_value(None, "user_version", |row| row.get(0))?;
+
+    for (i, migration) in MIGRATIONS.iter().enumerate().skip(version) {
+        let tx = conn.transaction()?;
+        tx.execute_batch(migration)?;
+        tx.pragma_update(None, "user_version", i + 1)?;
+        tx.commit()?;
+    }
+    Ok(())
+}
+
+pub fn load(path: &Path) -> Result<BudgetData, String> {
+    if !path.exists() {
+        return Ok(BudgetData::default());
+    }
+    let conn = open(path)?;
+    read_all(&conn).map_err(|e| sql_error(path, e))
+}
+
+/// Replace the stored budget with `budget_data` in a single transaction,
+/// writing only the items that changed.
+pub fn save(path: &Path, budget_data: &BudgetData) -> Result<(), String> {
+    let mut conn = open(path)?;
+    let tx = conn.transaction().map_err(|e| sql_error(path, e))?;
+    write_all(&tx, budget_data).map_err(|e| sql_error(path, e))?;
+    tx.commit().map_err(|e| sql_error(path, e))
+}
+
+/// Recorded totals per category for one period, straight from the database.
+pub struct PeriodTotals {
+    pub transaction_count: usize,
+    pub income: f32,
+    pub expenses: f32,
+    pub categories: Vec<(TransactionCategory, f32)>,
+}
+
+pub fn period_totals(path: &Path, period: ReportPeriod) -> Result<PeriodTotals, String> {
+    let conn = open(path)?;
+    let (start, end) = period.bounds();
+
+    let mut stmt = conn
+        .prepare(
+            "SELECT c.kind, c.name, SUM(t.amount), COUNT(*)
+             FROM transactions t JOIN categories c ON c.id = t.category_id
+             WHERE t.date >= ?1 AND t.date < ?2
+             GROUP BY c.id
+             ORDER BY SUM(t.amount) DESC",
+        )
+        .map_err(|e| sql_error(path, e))?;
+    let rows = stmt
+        .query_map(params![start.to_string(), end.to_string()], |row| {
+            Ok((
+                row.get::<_, String>(0)?,
+                row.get::<_, String>(1)?,
+                row.get::<_, f64>(2)?,
+                row.get::<_, i64>(3)?,
+            ))
+        })
+        .map_err(|e| sql_error(path, e))?;
+
+    let mut totals = PeriodTotals {
+        transaction_count: 0,
+        income: 0.0,
+        expenses: 0.0,
+        categories: Vec::new(),
+    };
+    for row in rows {
+        let (kind, name, sum, count) = row.map_err(|e| sql_error(path, e))?;
+        let category = category_from_row(&kind, &name);
+        let sum = sum as f32;
+        if category.is_income() {
+            totals.income += sum;
+        } else {
+            totals.expenses += sum;
+        }
+        totals.transaction_count += count as usize;
+        totals.categories.push((category, sum));
+    }
+    Ok(totals)
+}
+
+fn category_key(category: &TransactionCategory) -> (&'static str, &'static str) {
+    match category {
+        TransactionCategory::Expense(expense) => ("expense", expense.label()),
+        TransactionCategory::Income(income) => ("income", income.label()),
+    }
+}
+
+fn category_from_row(kind: &str, name: &str) -> TransactionCategory {
+    if kind == "income" {
+        TransactionCategory::Income(
+            IncomeCategory::from_name(name).unwrap_or(IncomeCategory::Other),
+        )
+    } else {
+        TransactionCategory::Expense(
+            ExpenceCategory::from_name(name).unwrap_or(ExpenceCategory::Other),
+        )
+    }
+}
+
+/// Ids of categories and tags, created on first use while saving.
+struct Lookup<'a> {
+    tx: &'a SqlTransaction<'a>,
+    categories: HashMap<(&'static str, &'static str), i64>,
+    tags: HashMap<String, i64>,
+}
+
+impl<'a> Lookup<'a> {
+    fn category(&mut self, category: &TransactionCategory) -> rusqlite::Result<i64> {
+        let key = category_key(category);
+        if let Some(id) = self.categories.get(&key) {
+            return Ok(*id);
+        }
+        self.tx.execute(
+            "INSERT OR IGNORE INTO categories (kind, name) VALUES (?1, ?2)",
+            params![key.0, key.1],
+        )?;
+        let id = self.tx.query_row(
+            "SELECT id FROM categories WHERE kind = ?1 AND name = ?2",
+            params![key.0, key.1],
+            |row| row.get(0),
+        )?;
+        self.categories.insert(key, id);
+        Ok(id)
+    }
+
+    fn tag(&mut self, name: &str) -> rusqlite::Result<i64> {
+        if let Some(id) = self.tags.get(name) {
+            return Ok(*id);
+        }
+        self.tx.execute(
+            "INSERT OR IGNORE INTO tags (name) VALUES (?1)",
+            params![name],
+        )?;
+        let id = self.tx.query_row(
+            "SELECT id FROM tags WHERE name = ?1",
+            params![name],
+            |row| row.get(0),
+        )?;
+        self.tags.insert(name.to_string(), id);
+        Ok(id)
+    }
+}
+
+fn write_all(tx: &SqlTransaction, budget_data: &BudgetData) -> rusqlite::Result<()> {
+    let stored = read_all(tx)?;
+    tx.execute(
+        "INSERT OR REPLACE INTO budget_settings
+             (id, name, next_cost_id, next_income_id, next_transaction_id)
+         VALUES (1, ?1, ?2, ?3, ?4)",
+        params![
+            budget_data.settings.name,
+            budget_data.next_cost_id as i64,
+            budget_data.next_income_id as i64,
+            budget_data.next_transaction_id as i64
+        ],
+    )?;
+
+    let mut lookup = Lookup {
+        tx,
+        categories: HashMap::new(),
+        tags: HashMap::new(),
+    };
+
+    for id in stored.cost_items.keys() {
+        if !budget_data.cost_items.contains_key(id) {
+            tx.execute("DELETE FROM cost_items WHERE id = ?1", params![*id as i64])?;
+        }
+    }
+    for item in budget_data.cost_items.values() {
+        if stored.cost_items.get(&item.id) == Some(item) {
+            continue;
+        }
+        let category =
+            lookup.category(&TransactionCategory::Expense(item.cost_category.clone()))?;
+        tx.execute(
+            "INSERT INTO cost_items (id, what, cost, cycle, category_id) VALUES (?1, ?2, ?3, ?4, ?5)
+             ON CONFLICT (id) DO UPDATE SET what = ?2, cost = ?3, cycle = ?4, category_id = ?5",
+            params![item.id as i64, item.what, item.cost, item.cost_cycle.label(), category],
+        )?;
+        write_tags(
+            &mut lookup,
+            "cost_item_tags",
+            "cost_item_id",
+            item.id,
+            &item.tags,
+        )?;
+    }
+
+    for stored_item in &stored.income_items {
+        if !budget_data
+            .income_items
+            .iter()
+            .any(|i| i.id == stored_item.id)
+        {
+            tx.execute(
+                "DELETE FROM income_items WHERE id = ?1",
+                params![stored_item.id as i64],
+            )?;
+        }
+    }
+    for income in &budget_data.income_items {
+        if stored.income_items.contains(income) {
+            continue;
+        }
+        let category = lookup.category(&TransactionCategory::Income(income.category.clone()))?;
+        tx.execute(
+            "INSERT INTO income_items (id, source, amount, cycle, category_id) VALUES (?1, ?2, ?3, ?4, ?5)
+             ON CONFLICT (id) DO UPDATE SET source = ?2, amount = ?3, cycle = ?4, category_id = ?5",
+            params![
+                income.id as i64,
+                income.source,
+                income.amount,
+                income.income_cycle.label(),
+                category
+            ],
+        )?;
+        write_tags(
+            &mut lookup,
+            "income_item_tags",
+            "income_item_id",
+            income.id,
+            &income.tags,
+        )?;
+    }
+
+    for stored_transaction in &stored.transactions {
+        if !budget_data
+            .transactions
+            .iter()
+            .any(|t| t.id == stored_transaction.id)
+        {
+            tx.execute(
+                "DELETE FROM transactions WHERE id = ?1",
+                params![stored_transaction.id as i64],
+            )?;
+        }
+    }
+    for transaction in &budget_data.transactions {
+        if stored.transactions.contains(transaction) {
+            continue;
+        }
+        let category = lookup.category(&transaction.category)?;
+        tx.execute(
+            "INSERT INTO transactions (id, date, description, amount, category_id) VALUES (?1, ?2, ?3, ?4, ?5)
+             ON CONFLICT (id) DO UPDATE SET date = ?2, description = ?3, amount = ?4, category_id = ?5",
+            params![
+                transaction.id as i64,
+                transaction.date.to_string(),
+                transaction.description,
+                transaction.amount,
+                category
+            ],
+        )?;
+        write_tags(
+            &mut lookup,
+            "transaction_tags",
+            "transaction_id",
+            transaction.id,
+            &transaction.tags,
+        )?;
+    }
+
+    // Tags nothing refers to any more
+    tx.execute_batch(
+        "DELETE FROM tags WHERE id NOT IN (
+             SELECT tag_id FROM cost_item_tags
+             UNION SELECT tag_id FROM income_item_tags
+             UNION SELECT tag_id FROM transaction_tags
+         );",
+    )?;
+    Ok(())
+}
+
+/// Replace the tags of one item in one of the `*_tags` tables.
+fn write_tags(
+    lookup: &mut Lookup,
+    table: &str,
+    owner: &str,
+    id: u64,
+    tags: &Option<Vec<String>>,
+) -> rusqlite::Result<()> {
+    lookup.tx.execute(
+        &format!("DELETE FROM {table} WHERE {owner} = ?1"),
+        params![id as i64],
+    )?;
+    for tag in tags.iter().flatten() {
+        let tag_id = lookup.tag(tag)?;
+        lookup.tx.execute(
+            &format!("INSERT OR IGNORE INTO {table} ({owner}, tag_id) VALUES (?1, ?2)"),
+            params![id as i64, tag_id],
+        )?;
+    }
+    Ok(())
+}
+
+/// Tags per owner id for one of the `*_tags` tables.
+fn read_tags(
+    conn: &Connection,
+    table: &str,
+    owner: &str,
+) -> rusqlite::Result<HashMap<i64, Vec<String>>> {
+    let mut stmt = conn.prepare(&format!(
+        "SELECT x.{owner}, t.name FROM {table} x JOIN tags t ON t.id = x.tag_id ORDER BY x.rowid"
+    ))?;
+    let mut tags: HashMap<i64, Vec<String>> = HashMap::new();
+    for row in stmt.query_map([], |row| {
+        Ok((row.get::<_, i64>(0)?, row.get::<_, String>(1)?))
+    })? {
+        let (owner_id, name) = row?;
+        tags.entry(owner_id).or_default().push(name);
+    }
+    Ok(tags)
+}
+
+fn read_all(conn: &Connection) -> rusqlite::Result<BudgetData> {
+    let mut budget_data = BudgetData::default();
+
+    let mut cost_tags = read_tags(conn, "cost_item_tags", "cost_item_id")?;
+    let mut stmt = conn.prepare(
+        "SELECT i.id, i.what, i.cost, i.cycle, c.kind, c.name
+         FROM cost_items i JOIN categories c ON c.id = i.category_id",
+    )?;
+    let rows = stmt.query_map([], |row| {
+        Ok((
+            row.get::<_, i64>(0)?,
+            row.get::<_, String>(1)?,
+            row.get::<_, f32>(2)?,
+            row.get::<_, String>(3)?,
+            row.get::<_, String>(4)?,
+            row.get::<_, String>(5)?,
+        ))
+    })?;
+    for row in rows {
+        let (id, what, cost, cycle, kind, name) = row?;
+        let cost_category = match category_from_row(&kind, &name) {
+            TransactionCategory::Expense(category) => category,
+            TransactionCategory::Income(_) => ExpenceCategory::Other,
+        };
+        budget_data.cost_items.insert(
+            id as u64,
+            CostItem {
+                id: id as u64,
+                what,
+                cost,
+                cost_cycle: CostCycle::from_name(&cycle).unwrap_or(CostCycle::Monthly),
+                cost_category,
+                tags: cost_tags.remove(&id),
+            },
+        );
+    }
+
+    let mut income_tags = read_tags(conn, "income_item_tags", "income_item_id")?;
+    let mut stmt = conn.prepare(
+        "SELECT i.id, i.source, i.amount, i.cycle, c.name
+         FROM income_items i JOIN categories c ON c.id = i.category_id
+         ORDER BY i.id",
+    )?;
+    let rows = stmt.query_map([], |row| {
+        Ok((
+            row.get::<_, i64>(0)?,
+            row.get::<_, String>(1)?,
+            row.get::<_, f32>(2)?,
+            row.get::<_, String>(3)?,
+            row.get::<_, String>(4)?,
+        ))
+    })?;
+    for row in rows {
+        let (id, source, amount, cycle, name) = row?;
+        budget_data.income_items.push(IncomeItem {
+            id: id as u64,
+            source,
+            category: IncomeCategory::from_name(&name).unwrap_or(IncomeCategory::Other),
+            amount,
+            income_cycle: CostCycle::from_name(&cycle).unwrap_or(CostCycle::Monthly),
+            tags: income_tags.remove(&id),
+        });
+    }
+
+    let mut transaction_tags = read_tags(conn, "transaction_tags", "transaction_id")?;
+    let mut stmt = conn.prepare(
+        "SELECT t.id, t.date, t.description, t.amount, c.kind, c.name
+         FROM transactions t JOIN categories c ON c.id = t.category_id
+         ORDER BY t.date, t.id",
+    )?;
+    let rows = stmt.query_map([], |row| {
+        Ok((
+            row.get::<_, i64>(0)?,
+            row.get::<_, String>(1)?,
+            row.get::<_, String>(2)?,
+            row.get::<_, f32>(3)?,
+            row.get::<_, String>(4)?,
+            row.get::<_, String>(5)?,
+        ))
+    })?;
+    for row in rows {
+        let (id, date, description, amount, kind, name) = row?;
+        let Ok(date) = NaiveDate::parse_from_str(&date, "%Y-%m-%d") else {
+            continue;
+        };
+        budget_data.transactions.push(Transaction {
+            id: id as u64,
+            date,
+            description,
+            amount,
+            category: category_from_row(&kind, &name),
+            tags: transaction_tags.remove(&id),
+        });
+    }
+
+    let settings = conn
+        .query_row(
+            "SELECT name, next_cost_id, next_income_id, next_transaction_id
+             FROM budget_settings WHERE id = 1",
+            [],
+            |row| {
+                Ok((
+                    row.get::<_, String>(0)?,
+                    row.get::<_, Option<i64>>(1)?,
+                    row.get::<_, Option<i64>>(2)?,
+                    row.get::<_, Option<i64>>(3)?,
+                ))
+            },
+        )
+        .optional()?;
+    let (mut next_cost, mut next_income, mut next_transaction) = (None, None, None);
+    if let Some((name, cost, income, transaction)) = settings {
+        budget_data.settings.name = name;
+        (next_cost, next_income, next_transaction) = (cost, income, transaction);
+    }
+
+    // Databases from before the counters were kept only have the items
+    let stored = |next: Option<i64>| next.map_or(0, |id| id as u64);
+    budget_data.next_cost_id = next_id(conn, "cost_items")?.max(stored(next_cost));
+    budget_data.next_income_id = next_id(conn, "income_items")?.max(stored(next_income));
+    budget_data.next_transaction_id = next_id(conn, "transactions")?.max(stored(next_transaction));
+    Ok(budget_data)
+}
+
+fn next_id(conn: &Connection, table: &str) -> rusqlite::Result<u64> {
+    let max: Option<i64> = conn
+        .query_row(&format!("SELECT MAX(id) FROM {table}"), [], |row| {
+            row.get(0)
+        })
+        .optional()?
+        .flatten();
+    Ok(max.map(|id| id as u64 + 1).unwrap_or(0))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::event::BudgetEvent;
+
+    #[test]
+    fn saves_only_change_what_changed() {
+        let path = std::env::temp_dir().join(format!(
+            "rusted_budget_sqlite_{}.sqlite3",
+            std::process::id()
+        ));
+        let _ = std::fs::remove_file(&path);
+
+        let mut budget_data = BudgetData::default();
+        for what in ["Rent", "Bus", "Gym"] {
+            budget_data.apply(&BudgetEvent::AddCostItem(CostItem {
+                what: what.to_string(),
+                tags: Some(vec!["fixed".to_string()]),
+                ..CostItem::default()
+            }));
+        }
+        budget_data.apply(&BudgetEvent::AddIncomeItem(IncomeItem::default()));
+        save(&path, &budget_data).unwrap();
+
+        budget_data.apply(&BudgetEvent::DeleteCostItem(2));
+        budget_data.apply(&BudgetEvent::DeleteIncomeItem(0));
+        let mut bus = budget_data.cost_items[&1].clone();
+        bus.cost = 40.0;
+        bus.tags = None;
+        budget_data.apply(&BudgetEvent::UpdateCostItem { id: 1, item: bus });
+        save(&path, &budget_data).unwrap();
+
+        let loaded = load(&path).unwrap();
+        let _ = std::fs::remove_file(&path);
+        assert_eq!(loaded, budget_data);
+        // Ids of deleted items aren't handed out again
+        assert_eq!(loaded.next_cost_id, 3);
+        assert_eq!(loaded.next_income_id, 1);
+    }
+}
@@ -0,0 +1,903 @@
+use std::{
+    collections::{BTreeMap, HashMap},
+    fs::OpenOptions,
+    io::Write,
+    path::{Path, PathBuf},
+};
+
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    BudgetData, BudgetEvent,
+    models::{cost_item::CostItem, income_item::IncomeItem, transaction::Transaction},
+    storage::crypto::{self, FileKey, Passphrase},
+};
+
+/// Holds the salt of the key for an encrypted shared budget. Every device
+/// derives the key from the passphrase and this salt, so all can read each
+/// other's logs.
+const KEY_FILE: &str = "sync.key";
+/// Encrypted into the key file, to tell a wrong passphrase.
+const KEY_CHECK: &[u8] = b"rusted_budget sync";
+
+/// One change to a synced budget. Items are named by uids that are the same
+/// on every device, as local ids and positions differ between devices.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub enum Change {
+    PutCostItem {
+        uid: String,
+        item: CostItem,
+    },
+    DeleteCostItem {
+        uid: String,
+    },
+    PutIncomeItem {
+        uid: String,
+        item: IncomeItem,
+    },
+    DeleteIncomeItem {
+        uid: String,
+    },
+    PutTransaction {
+        uid: String,
+        transaction: Transaction,
+    },
+    DeleteTransaction {
+        uid: String,
+    },
+    Rename {
+        name: String,
+    },
+}
+
+/// The uid of each local item, kept next to the budget so its items keep
+/// their ids from one start to the next. The GUI and the CLI share it, as
+/// they share the budget.
+#[derive(Debug, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+struct LocalIds {
+    cost_items: HashMap<u64, String>,
+    income_items: HashMap<u64, String>,
+    transactions: HashMap<u64, String>,
+}
+
+/// Where the uids of the items of the budget at `budget_path` are kept.
+pub fn ids_path_for(budget_path: &Path) -> PathBuf {
+    let mut name = budget_path.as_os_str().to_os_string();
+    name.push(".sync-ids.json");
+    PathBuf::from(name)
+}
+
+/// Add the ids in `stored` that `ids` has no word on yet.
+fn merge_ids(ids: &mut HashMap<u64, String>, stored: HashMap<u64, String>) {
+    for (id, uid) in stored {
+        if !ids.contains_key(&id) && !ids.values().any(|known| *known == uid) {
+            ids.insert(id, uid);
+        }
+    }
+}
+
+/// A line in a device's change log.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct LogEntry {
+    /// Milliseconds since the Unix epoch on the writing device.
+    time: i64,
+    device: String,
+    seq: u64,
+    change: Change,
+}
+
+/// Items by uid in the order they first appeared; `None` once deleted.
+struct Table<V> {
+    order: Vec<String>,
+    items: HashMap<String, Option<V>>,
+}
+
+impl<V> Table<V> {
+    fn new() -> Self {
+        Self {
+            order: Vec::new(),
+            items: HashMap::new(),
+        }
+    }
+
+    fn set(&mut self, uid: String, value: Option<V>) {
+        if !self.items.contains_key(&uid) {
+            self.order.push(uid.clone());
+        }
+        self.items.insert(uid, value);
+    }
+
+    /// Live items in order.
+    fn into_live(mut self) -> Vec<(String, V)> {
+        self.order
+            .into_iter()
+            .filter_map(|uid| {
+                let value = self.items.remove(&uid).flatten()?;
+                Some((uid, value))
+            })
+            .collect()
+    }
+}
+
+/// Every device's changes applied in one order. All devices sort by
+/// (time, device, seq), so they arrive at the same budget; for changes to
+/// the same item the later one wins.
+struct Replay {
+    cost_items: Table<CostItem>,
+    income_items: Table<IncomeItem>,
+    transactions: Table<Transaction>,
+    name: Option<String>,
+}
+
+impl Replay {
+    fn new(mut entries: Vec<LogEntry>) -> Self {
+        entries.sort_by(|a, b| (a.time, &a.device, a.seq).cmp(&(b.time, &b.device, b.seq)));
+
+        let mut replay = Self {
+            cost_items: Table::new(),
+            income_items: Table::new(),
+            transactions: Table::new(),
+            name: None,
+        };
+        for entry in entries {
+            match entry.change {
+                Change::PutCostItem { uid, item } => replay.cost_items.set(uid, Some(item)),
+                Change::DeleteCostItem { uid } => replay.cost_items.set(uid, None),
+                Change::PutIncomeItem { uid, item } => replay.income_items.set(uid, Some(item)),
+                Change::DeleteIncomeItem { uid } => replay.income_items.set(uid, None),
+                Change::PutTransaction { uid, transaction } => {
+                    replay.transactions.set(uid, Some(transaction))
+                }
+                Change::DeleteTransaction { uid } => replay.transactions.set(uid, None),
+                Change::Rename { name } => replay.name = Some(name),
+            }
+        }
+        replay
+    }
+}
+
+/// Folder sync of the open budget. Each device appends its changes to
+/// `<device id>.jsonl` in the shared folder and never touches the other
+/// logs, so any file sync tool can carry them without conflicts. The logs of
+/// an encrypted budget are encrypted line by line.
+pub struct SyncState {
+    folder: PathBuf,
+    device: String,
+    /// See `ids_path_for`.
+    ids_path: PathBuf,
+    /// Key of the folder when the budget is encrypted.
+    key: Option<FileKey>,
+    /// Entries in this device's log.
+    seq: u64,
+    /// Size of each log when last read, to notice new changes.
+    seen: BTreeMap<PathBuf, u64>,
+    cost_uids: HashMap<u64, String>,
+    income_uids: HashMap<u64, String>,
+    transaction_uids: HashMap<u64, String>,
+    /// Uids made this session; keeps uids made in the same millisecond apart.
+    uids_made: u64,
+}
+
+fn log_error(path: &Path, e: impl std::fmt::Display) -> String {
+    format!("{}: {}", path.display(), e)
+}
+
+/// Whether the folder holds any change logs.
+fn has_logs(folder: &Path) -> Result<bool, String> {
+    let dir = std::fs::read_dir(folder).map_err(|e| log_error(folder, e))?;
+    Ok(dir
+        .flatten()
+        .any(|entry| entry.path().extension().is_some_and(|ext| ext == "jsonl")))
+}
+
+/// The key for the folder's logs. The first encrypted device to use the
+/// folder makes it; the others derive it from the same passphrase.
+fn folder_key(
+    folder: &Path,
+    budget_key: &FileKey,
+    passphrase: &Passphrase,
+) -> Result<FileKey, String> {
+    let path = folder.join(KEY_FILE);
+    if path.exists() {
+        let header = std::fs::read(&path).map_err(|e| log_error(&path, e))?;
+        let key = FileKey::for_file(&header, passphrase)?;
+        key.decrypt(&header)
+            .map_err(|_| "the budget in the sync folder has another passphrase".to_string())?;
+        return Ok(key);
+    }
+
+    if has_logs(folder)? {
+        return Err(
+            "the budget in the sync folder is not encrypted; an encrypted budget can't join it"
+                .to_string(),
+        );
+    }
+    if !budget_key.matches(passphrase) {
+        return Err("wrong passphrase".to_string());
+    }
+    let key = budget_key.new_like(passphrase)?;
+    std::fs::write(&path, key.encrypt(KEY_CHECK)?).map_err(|e| log_error(&path, e))?;
+    Ok(key)
+}
+
+/// `item` with the local id left out, to tell whether two devices hold the same item.
+fn without_id<T: Clone>(item: &T, clear: impl Fn(&mut T)) -> T {
+    let mut item = item.clone();
+    clear(&mut item);
+    item
+}
+
+impl SyncState {
+    /// Start syncing `budget_data`, stored at `budget_path`, through
+    /// `folder`. An empty folder is
+    /// seeded with the current budget. A folder that already holds changes
+    /// is joined: the first time, items only this device has are added to
+    /// the shared budget, which then replaces `budget_data`. Returns whether
+    /// it was joined.
+    ///
+    /// An encrypted budget, one with a `key`, only syncs with its
+    /// `passphrase`, which the folder's key is derived from.
+    pub fn start(
+        folder: &Path,
+        device: &str,
+        budget_path: &Path,
+        budget_data: &mut BudgetData,
+        key: Option<&FileKey>,
+        passphrase: Option<&Passphrase>,
+    ) -> Result<(Self, bool), String> {
+        std::fs::create_dir_all(folder).map_err(|e| log_error(folder, e))?;
+        let key = match (key, passphrase) {
+            (Some(key), Some(passphrase)) => Some(folder_key(folder, key, passphrase)?),
+            (Some(_), None) => {
+                return Err("an encrypted budget needs its passphrase to sync".to_string());
+            }
+            (None, _) if folder.join(KEY_FILE).exists() => {
+                return Err(
+                    "the budget in the sync folder is encrypted; encrypt this budget with the \
+                     same passphrase to join it"
+                        .to_string(),
+                );
+            }
+            (None, _) => None,
+        };
+        let mut sync = Self {
+            folder: folder.to_path_buf(),
+            device: device.to_string(),
+            ids_path: ids_path_for(budget_path),
+            key,
+            seq: 0,
+            seen: BTreeMap::new(),
+            cost_uids: HashMap::new(),
+            income_uids: HashMap::new(),
+            transaction_uids: HashMap::new(),
+            uids_made: 0,
+        };
+
+        let mut entries = sync.read_logs()?;
+        sync.seq = entries.iter().filter(|e| e.device == device).count() as u64;
+        if !entries.is_empty() {
+            if sync.seq == 0 {
+                sync.add_local_items(entries.clone(), budget_data)?;
+                entries = sync.read_logs()?;
+            }
+            sync.apply(Replay::new(entries), budget_data)?;
+            return Ok((sync, true));
+        }
+
+        let mut changes = vec![Change::Rename {
+            name: budget_data.settings.name.clone(),
+        }];
+        let mut cost_items: Vec<&CostItem> = budget_data.cost_items.values().collect();
+        cost_items.sort_by_key(|item| item.id);
+        for item in cost_items {
+            let uid = sync.new_uid();
+            sync.cost_uids.insert(item.id, uid.clone());
+            changes.push(Change::PutCostItem {
+                uid,
+                item: item.clone(),
+            });
+        }
+        for item in &budget_data.income_items {
+            let uid = sync.new_uid();
+            sync.income_uids.insert(item.id, uid.clone());
+            changes.push(Change::PutIncomeItem {
+                uid,
+                item: item.clone(),
+            });
+        }
+        for transaction in &budget_data.transactions {
+            let uid = sync.new_uid();
+            sync.transaction_uids.insert(transaction.id, uid.clone());
+            changes.push(Change::PutTransaction {
+                uid,
+                transaction: transaction.clone(),
+            });
+        }
+        sync.append(changes)?;
+        sync.save_ids()?;
+        Ok((sync, false))
+    }
+
+    /// Log the items of `budget_data` the shared budget doesn't have yet,
+    /// so joining never loses them. They keep their local ids.
+    fn add_local_items(
+        &mut self,
+        entries: Vec<LogEntry>,
+        budget_data: &BudgetData,
+    ) -> Result<(), String> {
+        let shared = Replay::new(entries);
+        let shared_cost: Vec<CostItem> = shared
+            .cost_items
+            .into_live()
+            .iter()
+            .map(|(_, item)| without_id(item, |item| item.id = 0))
+            .collect();
+        let shared_income: Vec<IncomeItem> = shared
+            .income_items
+            .into_live()
+            .iter()
+            .map(|(_, item)| without_id(item, |item| item.id = 0))
+            .collect();
+        let shared_transactions: Vec<Transaction> = shared
+            .transactions
+            .into_live()
+            .iter()
+            .map(|(_, transaction)| without_id(transaction, |t| t.id = 0))
+            .collect();
+
+        let mut changes = Vec::new();
+        let mut cost_items: Vec<&CostItem> = budget_data.cost_items.values().collect();
+        cost_items.sort_by_key(|item| item.id);
+        for item in cost_items {
+            if !shared_cost.contains(&without_id(item, |item| item.id = 0)) {
+                let uid = self.new_uid();
+                self.cost_uids.insert(item.id, uid.clone());
+                changes.push(Change::PutCostItem {
+                    uid,
+                    item: item.clone(),
+                });
+            }
+        }
+        for item in &budget_data.income_items {
+            if !shared_income.contains(&without_id(item, |item| item.id = 0)) {
+                let uid = self.new_uid();
+                self.income_uids.insert(item.id, uid.clone());
+                changes.push(Change::PutIncomeItem {
+                    uid,
+                    item: item.clone(),
+                });
+            }
+        }
+        for transaction in &budget_data.transactions {
+            if !shared_transactions.contains(&without_id(transaction, |t| t.id = 0)) {
+                let uid = self.new_uid();
+                self.transaction_uids.insert(transaction.id, uid.clone());
+                changes.push(Change::PutTransaction {
+                    uid,
+                    transaction: transaction.clone(),
+                });
+            }
+        }
+        self.append(changes)
+    }
+
+    pub fn folder(&self) -> &Path {
+        &self.folder
+    }
+
+    /// Number of devices with a log in the folder, this one included.
+    pub fn device_count(&self) -> usize {
+        self.seen.len()
+    }
+
+    fn own_log(&self) -> PathBuf {
+        self.folder.join(format!("{}.jsonl", self.device))
+    }
+
+    /// Uids start with the device id, so no two devices make the same one.
+    fn new_uid(&mut self) -> String {
+        self.uids_made += 1;
+        format!(
+            "{}-{}-{}",
+            self.device,
+            chrono::Utc::now().timestamp_millis(),
+            self.uids_made
+        )
+    }
+
+    /// Pick up uids the other program on this device gave to items since
+    /// they were last read.
+    fn load_ids(&mut self) -> Result<(), String> {
+        let text = match std::fs::read_to_string(&self.ids_path) {
+            Ok(text) => text,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(()),
+            Err(e) => return Err(log_error(&self.ids_path, e)),
+        };
+        let stored: LocalIds =
+            serde_json::from_str(&text).map_err(|e| log_error(&self.ids_path, e))?;
+        merge_ids(&mut self.cost_uids, stored.cost_items);
+        merge_ids(&mut self.income_uids, stored.income_items);
+        merge_ids(&mut self.transaction_uids, stored.transactions);
+        Ok(())
+    }
+
+    fn ids(&self) -> LocalIds {
+        LocalIds {
+            cost_items: self.cost_uids.clone(),
+            income_items: self.income_uids.clone(),
+            transactions: self.transaction_uids.clone(),
+        }
+    }
+
+    fn save_ids(&self) -> Result<(), String> {
+        let json = serde_json::to_string(&self.ids()).map_err(|e| e.to_string())?;
+        std::fs::write(&self.ids_path, json).map_err(|e| log_error(&self.ids_path, e))
+    }
+
+    /// Every entry of every log in the folder. A line that doesn't parse,
+    /// e.g. one still being synced, is skipped until it is complete.
+    fn read_logs(&mut self) -> Result<Vec<LogEntry>, String> {
+        let dir = std::fs::read_dir(&self.folder).map_err(|e| log_error(&self.folder, e))?;
+        let mut entries = Vec::new();
+        self.seen.clear();
+        for path in dir.flatten().map(|entry| entry.path()) {
+            if path.extension().is_none_or(|ext| ext != "jsonl") {
+                continue;
+            }
+            let text = std::fs::read_to_string(&path).map_err(|e| log_error(&path, e))?;
+            entries.extend(text.lines().filter_map(|line| self.decode(line)));
+            self.seen.insert(path, text.len() as u64);
+        }
+        Ok(entries)
+    }
+
+    /// An encrypted folder only takes lines encrypted with its key, so
+    /// nobody without the passphrase can slip changes in.
+    fn decode(&self, line: &str) -> Option<LogEntry> {
+        match &self.key {
+            Some(key) => serde_json::from_slice(&key.decrypt_line(line)?).ok(),
+            None if crypto::is_encrypted_line(line) => None,
+            None => serde_json::from_str(line).ok(),
+        }
+    }
+
+    fn append(&mut self, changes: Vec<Change>) -> Result<(), String> {
+        if changes.is_empty() {
+            return Ok(());
+        }
+        let path = self.own_log();
+        let mut lines = String::new();
+        for change in changes {
+            let entry = LogEntry {
+                time: chrono::Utc::now().timestamp_millis(),
+                device: self.device.clone(),
+                seq: self.seq,
+                change,
+            };
+            self.seq += 1;
+            let json = serde_json::to_string(&entry).map_err(|e| e.to_string())?;
+            match &self.key {
+                Some(key) => lines.push_str(&key.encrypt_line(&json)?),
+                None => lines.push_str(&json),
+            }
+            lines.push('\n');
+        }
+
+        let mut file = OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(&path)
+            .map_err(|e| log_error(&path, e))?;
+        file.write_all(lines.as_bytes())
+            .map_err(|e| log_error(&path, e))?;
+        let len = file.metadata().map_err(|e| log_error(&path, e))?.len();
+        self.seen.insert(path, len);
+        Ok(())
+    }
+
+    /// Log a data event that has just been applied to `budget_data`.
+    pub fn record(&mut self, event: &BudgetEvent, budget_data: &BudgetData) -> Result<(), String> {
+        let mut changes = Vec::new();
+        match event {
+            BudgetEvent::AddCostItem(_)
+            | BudgetEvent::RestoreCostItem(_)
+            | BudgetEvent::AddIncomeItem(_)
+            | BudgetEvent::RestoreIncomeItem(_)
+            | BudgetEvent::RestoreTransaction(_)
+            | BudgetEvent::CommitImport(_) => {
+                // New items are the ones without a uid yet
+                let mut ids: Vec<u64> = budget_data
+                    .cost_items
+                    .keys()
+                    .filter(|id| !self.cost_uids.contains_key(id))
+                    .copied()
+                    .collect();
+                ids.sort();
+                for id in ids {
+                    let uid = self.new_uid();
+                    self.cost_uids.insert(id, uid.clone());
+                    changes.push(Change::PutCostItem {
+                        uid,
+                        item: budget_data.cost_items[&id].clone(),
+                    });
+                }
+                for item in &budget_data.income_items {
+                    if !self.income_uids.contains_key(&item.id) {
+                        let uid = self.new_uid();
+                        self.income_uids.insert(item.id, uid.clone());
+                        changes.push(Change::PutIncomeItem {
+                            uid,
+                            item: item.clone(),
+                        });
+                    }
+                }
+                for transaction in &budget_data.transactions {
+                    if !self.transaction_uids.contains_key(&transaction.id) {
+                        let uid = self.new_uid();
+                        self.transaction_uids.insert(transaction.id, uid.clone());
+                        changes.push(Change::PutTransaction {
+                            uid,
+                            transaction: transaction.clone(),
+                        });
+                    }
+                }
+            }
+            BudgetEvent::UpdateCostItem { id, .. } => {
+                if let (Some(uid), Some(item)) =
+                    (self.cost_uids.get(id), budget_data.cost_items.get(id))
+                {
+                    changes.push(Change::PutCostItem {
+                        uid: uid.clone(),
+                        item: item.clone(),
+                    });
+                }
+            }
+            BudgetEvent::DeleteCostItem(id) => {
+                if let Some(uid) = self.cost_uids.remove(id) {
+                    changes.push(Change::DeleteCostItem { uid });
+                }
+            }
+            BudgetEvent::DeleteTransaction(id) => {
+                if let Some(uid) = self.transaction_uids.remove(id) {
+                    changes.push(Change::DeleteTransaction { uid });
+                }
+            }
+            BudgetEvent::UpdateIncomeItem { id, .. } => {
+                let item = budget_data.income_items.iter().find(|item| item.id == *id);
+                if let (Some(uid), Some(item)) = (self.income_uids.get(id), item) {
+                    changes.push(Change::PutIncomeItem {
+                        uid: uid.clone(),
+                        item: item.clone(),
+                    });
+                }
+            }
+            BudgetEvent::DeleteIncomeItem(id) => {
+                if let Some(uid) = self.income_uids.remove(id) {
+                    changes.push(Change::DeleteIncomeItem { uid });
+                }
+            }
+            BudgetEvent::RenameBudget(name) => {
+                self.append(vec![Change::Rename { name: name.clone() }])?;
+                return Ok(());
+            }
+        }
+        let new_uids = !changes.is_empty();
+        self.append(changes)?;
+        if new_uids {
+            self.save_ids()?;
+        }
+        Ok(())
+    }
+
+    /// Replace `budget_data`, just read again from storage, with the shared
+    /// budget.
+    pub fn reload(&mut self, budget_data: &mut BudgetData) -> Result<(), String> {
+        let entries = self.read_logs()?;
+        self.apply(Replay::new(entries), budget_data)
+    }
+
+    /// Apply changes other devices logged since the last look. Returns
+    /// whether the budget changed.
+    pub fn poll(&mut self, budget_data: &mut BudgetData) -> Result<bool, String> {
+        let dir = std::fs::read_dir(&self.folder).map_err(|e| log_error(&self.folder, e))?;
+        let grown = dir.flatten().any(|entry| {
+            let path = entry.path();
+            path.extension().is_some_and(|ext| ext == "jsonl")
+                && entry.metadata().map(|meta| meta.len()).ok() != self.seen.get(&path).copied()
+        });
+        if !grown {
+            return Ok(false);
+        }
+
+        let entries = self.read_logs()?;
+        let before = budget_data.clone();
+        self.apply(Replay::new(entries), budget_data)?;
+        Ok(*budget_data != before)
+    }
+
+    /// Rebuild the budget from a replay, keeping the local ids of items it
+    /// already had so open editors, history and undo stay on the same item.
+    fn apply(&mut self, replay: Replay, budget_data: &mut BudgetData) -> Result<(), String> {
+        self.load_ids()?;
+        let known = self.ids();
+        let cost_ids: HashMap<String, u64> =
+            self.cost_uids.drain().map(|(id, uid)| (uid, id)).collect();
+        let mut next_cost_id = budget_data
+            .next_cost_id
+            .max(cost_ids.values().max().map_or(0, |id| id + 1));
+        budget_data.cost_items.clear();
+        for (uid, mut item) in replay.cost_items.into_live() {
+            item.id = cost_ids.get(&uid).copied().unwrap_or_else(|| {
+                next_cost_id += 1;
+                next_cost_id - 1
+            });
+            self.cost_uids.insert(item.id, uid);
+            budget_data.cost_items.insert(item.id, item);
+        }
+        budget_data.next_cost_id = next_cost_id;
+
+        let income_ids: HashMap<String, u64> = self
+            .income_uids
+            .drain()
+            .map(|(id, uid)| (uid, id))
+            .collect();
+        let mut next_income_id = budget_data
+            .next_income_id
+            .max(income_ids.values().max().map_or(0, |id| id + 1));
+        budget_data.income_items.clear();
+        for (uid, mut item) in replay.income_items.into_live() {
+            item.id = income_ids.get(&uid).copied().unwrap_or_else(|| {
+                next_income_id += 1;
+                next_income_id - 1
+            });
+            self.income_uids.insert(item.id, uid);
+            budget_data.income_items.push(item);
+        }
+        budget_data.next_income_id = next_income_id;
+
+        let transaction_ids: HashMap<String, u64> = self
+            .transaction_uids
+            .drain()
+            .map(|(id, uid)| (uid, id))
+            .collect();
+        let mut next_transaction_id = budget_data
+            .next_transaction_id
+            .max(transaction_ids.values().max().map_or(0, |id| id + 1));
+        budget_data.transactions.clear();
+        for (uid, mut transaction) in replay.transactions.into_live() {
+            transaction.id = transaction_ids.get(&uid).copied().unwrap_or_else(|| {
+                next_transaction_id += 1;
+                next_transaction_id - 1
+            });
+            self.transaction_uids.insert(transaction.id, uid);
+            budget_data.transactions.push(transaction);
+        }
+        budget_data.next_transaction_id = next_transaction_id;
+
+        if let Some(name) = replay.name {
+            budget_data.settings.name = name;
+        }
+        if self.ids() != known {
+            self.save_ids()?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::fixtures::cost_item;
+
+    fn budget(items: &[CostItem]) -> BudgetData {
+        let mut budget_data = BudgetData::default();
+        for item in items {
+            budget_data.apply(&BudgetEvent::AddCostItem(item.clone()));
+        }
+        budget_data
+    }
+
+    fn names(budget_data: &BudgetData) -> Vec<String> {
+        let mut names: Vec<String> = budget_data
+            .cost_items
+            .values()
+            .map(|item| format!("{} {}", item.what, item.cost))
+            .collect();
+        names.sort();
+        names
+    }
+
+    /// An empty folder of its own for each test.
+    fn folder(name: &str) -> PathBuf {
+        let folder = std::env::temp_dir().join(format!(
+            "rusted_budget_sync_{}_{}",
+            name,
+            std::process::id()
+        ));
+        let _ = std::fs::remove_dir_all(&folder);
+        folder
+    }
+
+    fn entry(time: i64, device: &str, seq: u64, change: Change) -> LogEntry {
+        LogEntry {
+            time,
+            device: device.to_string(),
+            seq,
+            change,
+        }
+    }
+
+    #[test]
+    fn replay_is_the_same_in_any_order() {
+        let put = |uid: &str, cost| Change::PutCostItem {
+            uid: uid.to_string(),
+            item: cost_item(uid, cost),
+        };
+        let entries = vec![
+            entry(1, "a", 0, put("rent", 900.0)),
+            entry(1, "b", 0, put("gym", 30.0)),
+            entry(2, "b", 1, put("rent", 950.0)),
+            entry(2, "a", 1, put("rent", 1000.0)),
+            entry(
+                3,
+                "a",
+                2,
+                Change::DeleteCostItem {
+                    uid: "gym".to_string(),
+                },
+            ),
+        ];
+        // Made at the same time, so the device id decides: b's rent wins
+        let expected = vec![("rent".to_string(), cost_item("rent", 950.0))];
+
+        let mut reversed = entries.clone();
+        reversed.reverse();
+        let mut by_device = entries.clone();
+        by_device.sort_by(|a, b| (&b.device, b.seq).cmp(&(&a.device, a.seq)));
+        for order in [entries, reversed, by_device] {
+            assert_eq!(Replay::new(order).cost_items.into_live(), expected);
+        }
+    }
+
+    #[test]
+    fn devices_end_with_the_same_budget() {
+        let folder = folder("devices");
+        let mut a = budget(&[cost_item("Rent", 900.0)]);
+        let (mut sync_a, joined) =
+            SyncState::start(&folder, "a", &folder.join("a"), &mut a, None, None).unwrap();
+        assert!(!joined);
+
+        let mut b = BudgetData::default();
+        let (mut sync_b, joined) =
+            SyncState::start(&folder, "b", &folder.join("b"), &mut b, None, None).unwrap();
+        assert!(joined);
+        assert_eq!(names(&b), ["Rent 900"]);
+
+        let event = BudgetEvent::AddCostItem(cost_item("Gym", 30.0));
+        b.apply(&event);
+        sync_b.record(&event, &b).unwrap();
+        let id = *a.cost_items.keys().next().unwrap();
+        let event = BudgetEvent::UpdateCostItem {
+            id,
+            item: cost_item("Rent", 950.0),
+        };
+        a.apply(&event);
+        sync_a.record(&event, &a).unwrap();
+
+        sync_a.poll(&mut a).unwrap();
+        sync_b.poll(&mut b).unwrap();
+        assert_eq!(names(&a), ["Gym 30", "Rent 950"]);
+        assert_eq!(names(&a), names(&b));
+        std::fs::remove_dir_all(&folder).unwrap();
+    }
+
+    #[test]
+    fn joining_keeps_local_items() {
+        let folder = folder("join");
+        let mut a = budget(&[cost_item("Rent", 900.0)]);
+        let (mut sync_a, _) =
+            SyncState::start(&folder, "a", &folder.join("a"), &mut a, None, None).unwrap();
+
+        // The same rent is not added twice
+        let mut b = budget(&[cost_item("Rent", 900.0), cost_item("Coffee", 5.0)]);
+        let (_, joined) =
+            SyncState::start(&folder, "b", &folder.join("b"), &mut b, None, None).unwrap();
+        assert!(joined);
+        assert_eq!(names(&b), ["Coffee 5", "Rent 900"]);
+
+        sync_a.poll(&mut a).unwrap();
+        assert_eq!(names(&a), names(&b));
+
+        // Coming back later does not add them again
+        let mut again = b.clone();
+        SyncState::start(&folder, "b", &folder.join("b"), &mut again, None, None).unwrap();
+        assert_eq!(names(&again), names(&b));
+        std::fs::remove_dir_all(&folder).unwrap();
+    }
+
+    #[test]
+    fn items_keep_their_ids_from_one_start_to_the_next() {
+        let folder = folder("restart");
+        let mut a = budget(&[cost_item("Rent", 900.0), cost_item("Gym", 30.0)]);
+        SyncState::start(&folder, "a", &folder.join("a"), &mut a, None, None).unwrap();
+        let mut b = BudgetData::default();
+        SyncState::start(&folder, "b", &folder.join("b"), &mut b, None, None).unwrap();
+
+        for _ in 0..3 {
+            let mut again = a.clone();
+            SyncState::start(&folder, "a", &folder.join("a"), &mut again, None, None).unwrap();
+            assert_eq!(again, a);
+            let mut again = b.clone();
+            SyncState::start(&folder, "b", &folder.join("b"), &mut again, None, None).unwrap();
+            assert_eq!(again, b);
+        }
+
+        // Another program on b, e.g. the CLI, shares b's budget and ids
+        let (mut sync_b, _) =
+            SyncState::start(&folder, "b", &folder.join("b"), &mut b, None, None).unwrap();
+        let mut cli = b.clone();
+        let (mut sync_cli, _) =
+            SyncState::start(&folder, "b-cli", &folder.join("b"), &mut cli, None, None).unwrap();
+        assert_eq!(cli, b);
+        let event = BudgetEvent::AddCostItem(cost_item("Coffee", 5.0));
+        cli.apply(&event);
+        sync_cli.record(&event, &cli).unwrap();
+        sync_b.poll(&mut b).unwrap();
+        assert_eq!(b, cli);
+        std::fs::remove_dir_all(&folder).unwrap();
+    }
+
+    #[test]
+    fn encrypted_budgets_write_encrypted_logs() {
+        let folder = folder("encrypted");
+        let passphrase = Passphrase::new("correct horse".to_string());
+        let key = FileKey::for_tests(&passphrase);
+        let mut a = budget(&[cost_item("Rent", 900.0)]);
+        SyncState::start(
+            &folder,
+            "a",
+            &folder.join("a"),
+            &mut a,
+            Some(&key),
+            Some(&passphrase),
+        )
+        .unwrap();
+
+        let log = std::fs::read_to_string(folder.join("a.jsonl")).unwrap();
+        assert!(log.lines().all(crypto::is_encrypted_line));
+        assert!(!log.contains("Rent"));
+
+        let mut b = BudgetData::default();
+        assert!(SyncState::start(&folder, "b", &folder.join("b"), &mut b, None, None).is_err());
+        assert!(
+            SyncState::start(&folder, "b", &folder.join("b"), &mut b, Some(&key), None).is_err()
+        );
+        let wrong = Passphrase::new("wrong".to_string());
+        assert!(
+            SyncState::start(
+                &folder,
+                "b",
+                &folder.join("b"),
+                &mut b,
+                Some(&key),
+                Some(&wrong)
+            )
+            .is_err()
+        );
+
+        // Another device's key has its own salt, but the same passphrase opens the folder
+        let other_key = key.new_like(&passphrase).unwrap();
+        SyncState::start(
+            &folder,
+            "b",
+            &folder.join("b"),
+            &mut b,
+            Some(&other_key),
+            Some(&passphrase),
+        )
+        .unwrap();
+        assert_eq!(names(&b), ["Rent 900"]);
+        std::fs::remove_dir_all(&folder).unwrap();
+    }
+}
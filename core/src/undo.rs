@@ -0,0 +1,208 @@
+// `std::time::Instant` panics in the browser
+use web_time::{Duration, Instant};
+
+use crate::{BudgetEvent, storage::merge::ItemKey};
+
+/// Edits to the same item this close together are undone as one step, so
+/// typing into a cell or dragging a value doesn't take an undo per frame.
+const GROUP_DELAY: Duration = Duration::from_secs(1);
+/// How long the toast offering to undo a delete stays up.
+const TOAST_DURATION: Duration = Duration::from_secs(6);
+const MAX_STEPS: usize = 100;
+
+/// Something the user did, kept as the events that take it back.
+pub struct Step {
+    /// What the step did, e.g. `Delete "Rent"`.
+    pub label: String,
+    /// Events that reverse the step, in the order they are applied.
+    pub events: Vec<BudgetEvent>,
+    /// The single item an edit changed; later edits to it may join the step.
+    group: Option<ItemKey>,
+    /// Name of what the step deleted, for the undo toast.
+    deleted: Option<String>,
+    at: Instant,
+}
+
+impl Step {
+    pub fn new(label: String, events: Vec<BudgetEvent>) -> Self {
+        Self {
+            label,
+            events,
+            group: None,
+            deleted: None,
+            at: Instant::now(),
+        }
+    }
+
+    pub fn grouped(mut self, key: Option<ItemKey>) -> Self {
+        self.group = key;
+        self
+    }
+
+    pub fn deleted(mut self, name: Option<String>) -> Self {
+        self.deleted = name;
+        self
+    }
+}
+
+/// Undo and redo stacks of the open budget.
+#[derive(Default)]
+pub struct UndoStack {
+    undo: Vec<Step>,
+    redo: Vec<Step>,
+}
+
+impl UndoStack {
+    /// Remember a new edit. It clears the redo stack, and joins the last
+    /// step when both edit the same item in quick succession.
+    pub fn push(&mut self, step: Step) {
+        if step.events.is_empty() {
+            return;
+        }
+        self.redo.clear();
+        if let Some(last) = self.undo.last_mut()
+            && step.group.is_some()
+            && last.group == step.group
+            && last.at.elapsed() < GROUP_DELAY
+        {
+            // The last step already goes back to before the first edit
+            last.at = step.at;
+            return;
+        }
+        self.undo.push(step);
+        if self.undo.len() > MAX_STEPS {
+            self.undo.remove(0);
+        }
+    }
+
+    pub fn undo(&mut self) -> Option<Step> {
+        self.undo.pop()
+    }
+
+    pub fn redo(&mut self) -> Option<Step> {
+        self.redo.pop()
+    }
+
+    /// Keep an undone step for redo, now holding the events that redo it.
+    pub fn undone(&mut self, step: Step) {
+        self.redo.push(step);
+    }
+
+    /// Put a redone step back for undo, keeping the redo stack.
+    pub fn redone(&mut self, step: Step) {
+        self.undo.push(step);
+    }
+
+    pub fn clear(&mut self) {
+        self.undo.clear();
+        self.redo.clear();
+    }
+
+    pub fn undo_label(&self) -> Option<&str> {
+        self.undo.last().map(|step| step.label.as_str())
+    }
+
+    pub fn redo_label(&self) -> Option<&str> {
+        self.redo.last().map(|step| step.label.as_str())
+    }
+
+    /// What was just deleted and for how much longer to offer undoing it.
+    pub fn toast(&self) -> Option<(&str, Duration)> {
+        let step = self.undo.last()?;
+        let name = step.deleted.as_deref()?;
+        let left = TOAST_DURATION.checked_sub(step.at.elapsed())?;
+        Some((name, left))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn step(label: &str, key: Option<u64>) -> Step {
+        Step::new(label.to_string(), vec![BudgetEvent::DeleteCostItem(0)])
+            .grouped(key.map(ItemKey::CostItem))
+    }
+
+    /// `step` as if made `seconds` ago.
+    fn earlier(mut step: Step, seconds: u64) -> Step {
+        step.at = Instant::now() - Duration::from_secs(seconds);
+        step
+    }
+
+    fn labels(stack: &mut UndoStack) -> Vec<String> {
+        let mut labels = Vec::new();
+        while let Some(step) = stack.undo() {
+            labels.push(step.label);
+        }
+        labels
+    }
+
+    #[test]
+    fn quick_edits_to_one_item_are_one_step() {
+        let mut stack = UndoStack::default();
+        stack.push(step("Edit rent", Some(1)));
+        stack.push(step("Edit rent again", Some(1)));
+        stack.push(step("Edit gym", Some(2)));
+        stack.push(step("Add coffee", None));
+        stack.push(step("Add tea", None));
+        assert_eq!(
+            labels(&mut stack),
+            ["Add tea", "Add coffee", "Edit gym", "Edit rent"]
+        );
+
+        stack.push(earlier(step("Edit rent", Some(1)), 2));
+        stack.push(step("Edit rent later", Some(1)));
+        assert_eq!(labels(&mut stack), ["Edit rent later", "Edit rent"]);
+
+        stack.push(Step::new("Nothing".to_string(), Vec::new()));
+        assert_eq!(stack.undo_label(), None);
+    }
+
+    #[test]
+    fn a_new_edit_clears_redo() {
+        let mut stack = UndoStack::default();
+        stack.push(step("Edit rent", Some(1)));
+        stack.push(step("Edit gym", Some(2)));
+        let undone = stack.undo().unwrap();
+        stack.undone(undone);
+        assert_eq!(stack.redo_label(), Some("Edit gym"));
+
+        // Redoing keeps what is left to redo
+        let undone = stack.undo().unwrap();
+        stack.undone(undone);
+        let redone = stack.redo().unwrap();
+        stack.redone(redone);
+        assert_eq!(stack.undo_label(), Some("Edit rent"));
+        assert_eq!(stack.redo_label(), Some("Edit gym"));
+
+        stack.push(step("Add coffee", None));
+        assert_eq!(stack.redo_label(), None);
+    }
+
+    #[test]
+    fn only_the_last_steps_are_kept() {
+        let mut stack = UndoStack::default();
+        for i in 0..MAX_STEPS + 5 {
+            stack.push(step(&format!("Step {}", i), None));
+        }
+        let labels = labels(&mut stack);
+        assert_eq!(labels.len(), MAX_STEPS);
+        assert_eq!(labels.last().map(String::as_str), Some("Step 5"));
+    }
+
+    #[test]
+    fn the_toast_offers_undoing_a_delete_for_a_while() {
+        let mut stack = UndoStack::default();
+        stack.push(step("Delete rent", None).deleted(Some("Rent".to_string())));
+        let (name, left) = stack.toast().unwrap();
+        assert_eq!(name, "Rent");
+        assert!(left <= TOAST_DURATION);
+
+        stack.push(earlier(step("Delete gym", None), 7).deleted(Some("Gym".to_string())));
+        assert!(stack.toast().is_none());
+
+        stack.push(step("Edit rent", Some(1)));
+        assert!(stack.toast().is_none());
+    }
+}
@@ -0,0 +1,708 @@
+//! Opt-in HTTP API on localhost, so scripts and home automation can read and
+//! change the budget while the app runs. Requests wait on a background
+//! thread and are answered by the UI thread, so changes go through the same
+//! events as edits made in the window: they are saved, synced, kept in the
+//! history and can be undone.
+//!
+//! Every request needs `Authorization: Bearer <token>` with the token from
+//! Settings. Bodies and answers are JSON shaped like the budget file.
+//!
+//! ```text
+//! GET    /api/budget                 name and planned monthly totals
+//! GET    /api/cost-items
+//! POST   /api/cost-items             fields, or {"text": "Coffee 5 \\daily @dining"}
+//! GET    /api/cost-items/{id}
+//! PATCH  /api/cost-items/{id}        only the fields given change
+//! DELETE /api/cost-items/{id}
+//! ...    /api/income-items/{id}      the same for income
+//! GET    /api/transactions
+//! POST   /api/transactions           fields; the date is today if not given
+//! GET    /api/transactions/{id}
+//! DELETE /api/transactions/{id}
+//! GET    /api/summary                this month, ?month=YYYY-MM or ?year=YYYY
+//! ```
+
+use std::{
+    io::Read,
+    sync::{
+        Arc, Mutex, PoisonError,
+        mpsc::{self, Receiver, Sender},
+    },
+    thread::JoinHandle,
+    time::Duration,
+};
+
+use chrono::{Datelike, Local, NaiveDate};
+use eframe::egui;
+use serde::{Serialize, de::DeserializeOwned};
+use serde_json::{Value, json};
+use tiny_http::{Header, Method, Response, Server};
+
+use crate::{
+    BudgetData, BudgetEvent,
+    amount::Locale,
+    import::ImportEntry,
+    models::{cost_item::CostItem, income_item::IncomeItem, transaction::Transaction},
+    quick_add,
+    report::{ReportPeriod, Summary, monthly_expense_total, monthly_income_total},
+};
+
+/// How long a request waits for the UI thread before giving up.
+const ANSWER_TIMEOUT: Duration = Duration::from_secs(5);
+/// Larger bodies are refused; no single change needs more.
+const MAX_BODY: u64 = 1 << 20;
+
+/// Where the server thread is with the port.
+enum Listening {
+    /// A server stopped a moment ago may still hold the port.
+    Waiting,
+    Serving(Arc<Server>),
+    Failed(String),
+    Stopped,
+}
+
+pub struct ApiServer {
+    listening: Arc<Mutex<Listening>>,
+    requests: Receiver<ApiRequest>,
+    thread: Option<JoinHandle<()>>,
+    pub port: u16,
+}
+
+impl ApiServer {
+    /// Listen on localhost at `port`, waking `ctx` for every request. The
+    /// port is taken on the server's own thread; see `status`.
+    pub fn start(port: u16, token: String, ctx: egui::Context) -> Result<Self, String> {
+        if token.is_empty() {
+            return Err("the API needs a token".to_string());
+        }
+        let listening = Arc::new(Mutex::new(Listening::Waiting));
+        let (sender, requests) = mpsc::channel();
+        let shared = Arc::clone(&listening);
+        let thread = std::thread::Builder::new()
+            .name("budget-api".to_string())
+            .spawn(move || {
+                let Some(server) = listen(port, &shared, &ctx) else {
+                    return;
+                };
+                for request in server.incoming_requests() {
+                    forward(request, &token, &sender, &ctx);
+                }
+            })
+            .map_err(|e| e.to_string())?;
+        Ok(Self {
+            listening,
+            requests,
+            thread: Some(thread),
+            port,
+        })
+    }
+
+    /// Whether the server listens yet, or why it couldn't.
+    pub fn status(&self) -> Result<bool, String> {
+        match &*self
+            .listening
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+        {
+            Listening::Serving(_) => Ok(true),
+            Listening::Failed(err) => Err(err.clone()),
+            Listening::Waiting | Listening::Stopped => Ok(false),
+        }
+    }
+
+    /// Requests waiting for an answer.
+    pub fn pending(&self) -> Vec<ApiRequest> {
+        self.requests.try_iter().collect()
+    }
+}
+
+impl Drop for ApiServer {
+    fn drop(&mut self) {
+        let mut listening = self
+            .listening
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner);
+        if let Listening::Serving(server) = std::mem::replace(&mut *listening, Listening::Stopped) {
+            server.unblock();
+        }
+        drop(listening);
+        // Dropping the queue fails a request still waiting for its answer,
+        // so the thread can stop and free the port
+        self.requests = mpsc::channel().1;
+        if let Some(thread) = self.thread.take() {
+            let _ = thread.join();
+        }
+    }
+}
+
+/// Take `port`, trying again for a moment while it is still in use, and
+/// record how it went in `listening`. `None` if the server was stopped
+/// meanwhile or the port stayed taken.
+fn listen(port: u16, listening: &Mutex<Listening>, ctx: &egui::Context) -> Option<Arc<Server>> {
+    let mut attempts = 0;
+    loop {
+        let bound = Server::http(("127.0.0.1", port));
+        let mut listening = listening.lock().unwrap_or_else(PoisonError::into_inner);
+        if let Listening::Stopped = *listening {
+            return None;
+        }
+        match bound {
+            Ok(server) => {
+                let server = Arc::new(server);
+                *listening = Listening::Serving(Arc::clone(&server));
+                ctx.request_repaint();
+                return Some(server);
+            }
+            Err(_) if attempts < 10 => attempts += 1,
+            Err(e) => {
+                *listening = Listening::Failed(format!("Could not listen on port {}: {}", port, e));
+                ctx.request_repaint();
+                return None;
+            }
+        }
+        drop(listening);
+        std::thread::sleep(Duration::from_millis(50));
+    }
+}
+
+/// Status code and JSON body.
+pub struct Answer {
+    status: u16,
+    body: Value,
+}
+
+impl Answer {
+    fn ok(body: &impl Serialize) -> Self {
+        Self::with_status(200, body)
+    }
+
+    fn with_status(status: u16, body: &impl Serialize) -> Self {
+        Self {
+            status,
+            body: serde_json::to_value(body).unwrap_or(Value::Null),
+        }
+    }
+
+    pub fn error(status: u16, message: impl Into<String>) -> Self {
+        Self {
+            status,
+            body: json!({ "error": message.into() }),
+        }
+    }
+
+    fn not_found(what: &str) -> Self {
+        Self::error(404, format!("no {} there", what))
+    }
+
+    fn into_response(self) -> Response<std::io::Cursor<Vec<u8>>> {
+        let mut response =
+            Response::from_string(self.body.to_string()).with_status_code(self.status);
+        if let Ok(header) = Header::from_bytes("Content-Type", "application/json") {
+            response.add_header(header);
+        }
+        if self.status == 401
+            && let Ok(header) = Header::from_bytes("WWW-Authenticate", "Bearer")
+        {
+            response.add_header(header);
+        }
+        response
+    }
+}
+
+/// What a request does: the answer, and the change to make first if any.
+type Handled = Result<(Answer, Option<BudgetEvent>), Answer>;
+
+/// An authorized request waiting for the UI thread.
+pub struct ApiRequest {
+    method: Method,
+    path: String,
+    query: String,
+    body: String,
+    answer: Sender<Answer>,
+}
+
+impl ApiRequest {
+    /// The answer, describing the budget as it will be once the returned
+    /// change is applied. Quick add text is read in `locale`.
+    pub fn handle(
+        &self,
+        budget_data: &BudgetData,
+        locale: Locale,
+    ) -> (Answer, Option<BudgetEvent>) {
+        self.route(budget_data, locale)
+            .unwrap_or_else(|answer| (answer, None))
+    }
+
+    pub fn respond(self, answer: Answer) {
+        // The client may have given up already
+        let _ = self.answer.send(answer);
+    }
+
+    fn route(&self, budget_data: &BudgetData, locale: Locale) -> Handled {
+        let segments: Vec<&str> = self.path.trim_matches('/').split('/').collect();
+        match (&self.method, segments.as_slice()) {
+            (Method::Get, ["api", "budget"]) => {
+                let income = monthly_income_total(budget_data);
+                let expenses = monthly_expense_total(budget_data);
+                read(&json!({
+                    "name": budget_data.settings.name,
+                    "monthly_income": income,
+                    "monthly_expenses": expenses,
+                    "monthly_left": income - expenses,
+                }))
+            }
+
+            (Method::Get, ["api", "cost-items"]) => {
+                let mut items: Vec<&CostItem> = budget_data.cost_items.values().collect();
+                items.sort_by_key(|item| item.id);
+                read(&items)
+            }
+            (Method::Post, ["api", "cost-items"]) => {
+                let body = self.body()?;
+                let mut item = match body.get("text").and_then(Value::as_str) {
+                    Some(text) => {
+                        quick_add::parse_expense(text, locale).map_err(|e| Answer::error(400, e))?
+                    }
+                    None => merged(&CostItem::default(), &body)?,
+                };
+                check_name(&item.what)?;
+                check_amount(item.cost)?;
+                // Ids are handed out in order when the item is added
+                item.id = budget_data.next_cost_id;
+                Ok((
+                    Answer::with_status(201, &item),
+                    Some(BudgetEvent::AddCostItem(item)),
+                ))
+            }
+            (method, ["api", "cost-items", id]) => {
+                let (id, item) = id
+                    .parse()
+                    .ok()
+                    .and_then(|id| budget_data.cost_items.get_key_value(&id))
+                    .ok_or_else(|| Answer::not_found("cost item"))?;
+                match method {
+                    Method::Get => read(item),
+                    Method::Patch => {
+                        let mut edited: CostItem = merged(item, &self.body()?)?;
+                        edited.id = *id;
+                        check_name(&edited.what)?;
+                        check_amount(edited.cost)?;
+                        let event = (edited != *item).then(|| BudgetEvent::UpdateCostItem {
+                            id: *id,
+                            item: edited.clone(),
+                        });
+                        Ok((Answer::ok(&edited), event))
+                    }
+                    Method::Delete => {
+                        Ok((Answer::ok(item), Some(BudgetEvent::DeleteCostItem(*id))))
+                    }
+                    _ => Err(not_allowed()),
+                }
+            }
+
+            (Method::Get, ["api", "income-items"]) => read(&budget_data.income_items),
+            (Method::Post, ["api", "income-items"]) => {
+                let body = self.body()?;
+                let mut item = match body.get("text").and_then(Value::as_str) {
+                    Some(text) => {
+                        quick_add::parse_income(text, locale).map_err(|e| Answer::error(400, e))?
+                    }
+                    None => merged(&IncomeItem::default(), &body)?,
+                };
+                check_name(&item.source)?;
+                check_amount(item.amount)?;
+                item.id = budget_data.next_income_id;
+                Ok((
+                    Answer::with_status(201, &item),
+                    Some(BudgetEvent::AddIncomeItem(item)),
+                ))
+            }
+            (method, ["api", "income-items", id]) => {
+                let item = id
+                    .parse()
+                    .ok()
+                    .and_then(|id: u64| budget_data.income_items.iter().find(|i| i.id == id))
+                    .ok_or_else(|| Answer::not_found("income item"))?;
+                match method {
+                    Method::Get => read(item),
+                    Method::Patch => {
+                        let mut edited: IncomeItem = merged(item, &self.body()?)?;
+                        edited.id = item.id;
+                        check_name(&edited.source)?;
+                        check_amount(edited.amount)?;
+                        let event = (edited != *item).then(|| BudgetEvent::UpdateIncomeItem {
+                            id: item.id,
+                            item: edited.clone(),
+                        });
+                        Ok((Answer::ok(&edited), event))
+                    }
+                    Method::Delete => Ok((
+                        Answer::ok(item),
+                        Some(BudgetEvent::DeleteIncomeItem(item.id)),
+                    )),
+                    _ => Err(not_allowed()),
+                }
+            }
+
+            (Method::Get, ["api", "transactions"]) => read(&budget_data.transactions),
+            (Method::Post, ["api", "transactions"]) => {
+                let mut body = self.body()?;
+                if body.get("date").is_none() {
+                    body["date"] = json!(Local::now().date_naive());
+                }
+                let mut transaction: Transaction =
+                    merged(&json!({ "id": 0, "tags": null }), &body)?;
+                if transaction.amount == 0.0 {
+                    return Err(Answer::error(
+                        400,
+                        "the amount must not be zero; the category decides the direction \
+                         and a negative amount, e.g. a refund, goes the other way",
+                    ));
+                }
+                transaction.id = budget_data.next_transaction_id;
+                Ok((
+                    Answer::with_status(201, &transaction),
+                    Some(BudgetEvent::CommitImport(vec![ImportEntry::Transaction(
+                        transaction.clone(),
+                    )])),
+                ))
+            }
+            (method, ["api", "transactions", id]) => {
+                let transaction = id
+                    .parse()
+                    .ok()
+                    .and_then(|id: u64| budget_data.transactions.iter().find(|t| t.id == id))
+                    .ok_or_else(|| Answer::not_found("transaction"))?;
+                match method {
+                    Method::Get => read(transaction),
+                    Method::Delete => Ok((
+                        Answer::ok(transaction),
+                        Some(BudgetEvent::DeleteTransaction(transaction.id)),
+                    )),
+                    _ => Err(not_allowed()),
+                }
+            }
+
+            (Method::Get, ["api", "summary"]) => read(&Summary::new(budget_data, self.period()?)),
+
+            (
+                _,
+                [
+                    "api",
+                    "budget" | "cost-items" | "income-items" | "transactions" | "summary",
+                ],
+            ) => Err(not_allowed()),
+            _ => Err(Answer::error(
+                404,
+                format!("no such endpoint: {}", self.path),
+            )),
+        }
+    }
+
+    /// The body as a JSON object.
+    fn body(&self) -> Result<Value, Answer> {
+        let body: Value = serde_json::from_str(&self.body)
+            .map_err(|e| Answer::error(400, format!("the body is not JSON: {}", e)))?;
+        if !body.is_object() {
+            return Err(Answer::error(400, "the body must be a JSON object"));
+        }
+        Ok(body)
+    }
+
+    /// Period from `?month=YYYY-MM` or `?year=YYYY`; this month by default.
+    fn period(&self) -> Result<ReportPeriod, Answer> {
+        let mut period = None;
+        for (key, value) in self
+            .query
+            .split('&')
+            .filter_map(|pair| pair.split_once('='))
+        {
+            period = match key {
+                "year" => Some(ReportPeriod::Year(value.parse().map_err(|_| {
+                    Answer::error(400, format!("\"{}\" is not a year", value))
+                })?)),
+                "month" => {
+                    let date = NaiveDate::parse_from_str(&format!("{}-01", value), "%Y-%m-%d")
+                        .map_err(|_| {
+                            Answer::error(400, format!("\"{}\" is not a month like 2025-03", value))
+                        })?;
+                    Some(ReportPeriod::Month {
+                        year: date.year(),
+                        month: date.month(),
+                    })
+                }
+                _ => period,
+            };
+        }
+        let today = Local::now().date_naive();
+        Ok(period.unwrap_or(ReportPeriod::Month {
+            year: today.year(),
+            month: today.month(),
+        }))
+    }
+}
+
+fn read(body: &impl Serialize) -> Handled {
+    Ok((Answer::ok(body), None))
+}
+
+fn not_allowed() -> Answer {
+    Answer::error(405, "method not allowed here")
+}
+
+fn check_name(name: &str) -> Result<(), Answer> {
+    if name.trim().is_empty() {
+        return Err(Answer::error(400, "the name can't be empty"));
+    }
+    Ok(())
+}
+
+/// Planned items only go one way; the list they are in decides which.
+fn check_amount(amount: f32) -> Result<(), Answer> {
+    if amount <= 0.0 {
+        return Err(Answer::error(400, "the amount must be positive"));
+    }
+    Ok(())
+}
+
+/// `base` with the fields given in `fields` replaced.
+fn merged<T: DeserializeOwned>(base: &impl Serialize, fields: &Value) -> Result<T, Answer> {
+    let mut value = serde_json::to_value(base).unwrap_or(Value::Null);
+    if let (Some(value), Some(fields)) = (value.as_object_mut(), fields.as_object()) {
+        for (key, field) in fields {
+            value.insert(key.clone(), field.clone());
+        }
+    }
+    serde_json::from_value(value).map_err(|e| Answer::error(400, e.to_string()))
+}
+
+/// Check the token and hand the request to the UI thread, then send its answer.
+fn forward(
+    mut request: tiny_http::Request,
+    token: &str,
+    sender: &Sender<ApiRequest>,
+    ctx: &egui::Context,
+) {
+    let answer = if !authorized(request.headers(), token) {
+        Answer::error(401, "missing or wrong token")
+    } else {
+        match read_body(&mut request) {
+            Ok(body) => {
+                let (path, query) = request.url().split_once('?').unwrap_or((request.url(), ""));
+                let (answer, answered) = mpsc::channel();
+                let queued = sender.send(ApiRequest {
+                    method: request.method().clone(),
+                    path: path.to_string(),
+                    query: query.to_string(),
+                    body,
+                    answer,
+                });
+                ctx.request_repaint();
+                queued
+                    .ok()
+                    .and_then(|_| answered.recv_timeout(ANSWER_TIMEOUT).ok())
+                    .unwrap_or_else(|| Answer::error(503, "the app did not answer"))
+            }
+            Err(answer) => answer,
+        }
+    };
+    // The client may have hung up
+    let _ = request.respond(answer.into_response());
+}
+
+fn authorized(headers: &[Header], token: &str) -> bool {
+    headers
+        .iter()
+        .filter(|header| header.field.equiv("Authorization"))
+        .filter_map(|header| header.value.as_str().strip_prefix("Bearer "))
+        .any(|given| same(given.trim().as_bytes(), token.as_bytes()))
+}
+
+/// Compare without stopping at the first difference, so the time taken
+/// tells nothing about the token.
+fn same(a: &[u8], b: &[u8]) -> bool {
+    a.len() == b.len() && a.iter().zip(b).fold(0, |diff, (x, y)| diff | (x ^ y)) == 0
+}
+
+fn read_body(request: &mut tiny_http::Request) -> Result<String, Answer> {
+    let mut body = Vec::new();
+    request
+        .as_reader()
+        .take(MAX_BODY + 1)
+        .read_to_end(&mut body)
+        .map_err(|e| Answer::error(400, e.to_string()))?;
+    if body.len() as u64 > MAX_BODY {
+        return Err(Answer::error(413, "the body is too large"));
+    }
+    String::from_utf8(body).map_err(|_| Answer::error(400, "the body is not UTF-8"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::cost_item::ExpenceCategory;
+
+    fn request(method: Method, url: &str, body: &str) -> ApiRequest {
+        let (path, query) = url.split_once('?').unwrap_or((url, ""));
+        ApiRequest {
+            method,
+            path: path.to_string(),
+            query: query.to_string(),
+            body: body.to_string(),
+            answer: mpsc::channel().0,
+        }
+    }
+
+    fn budget() -> BudgetData {
+        let mut budget_data = BudgetData::default();
+        budget_data.apply(&BudgetEvent::AddCostItem(CostItem {
+            what: "Rent".to_string(),
+            cost: 900.0,
+            cost_category: ExpenceCategory::Housing,
+            ..CostItem::default()
+        }));
+        budget_data.apply(&BudgetEvent::AddIncomeItem(IncomeItem {
+            source: "Salary".to_string(),
+            amount: 3000.0,
+            ..IncomeItem::default()
+        }));
+        budget_data
+    }
+
+    fn handle(method: Method, url: &str, body: &str) -> (u16, Value, Option<BudgetEvent>) {
+        let (answer, event) = request(method, url, body).handle(&budget(), Locale::English);
+        (answer.status, answer.body, event)
+    }
+
+    fn status(method: Method, url: &str, body: &str) -> u16 {
+        handle(method, url, body).0
+    }
+
+    fn status_of_patch(body: &str) -> u16 {
+        status(Method::Patch, "/api/cost-items/0", body)
+    }
+
+    #[test]
+    fn only_the_token_is_let_in() {
+        let header = |value: &str| Header::from_bytes("Authorization", value).unwrap();
+        assert!(authorized(&[header("Bearer s3cret")], "s3cret"));
+        assert!(authorized(&[header("Bearer  s3cret ")], "s3cret"));
+        assert!(!authorized(&[header("Bearer s3cre")], "s3cret"));
+        assert!(!authorized(&[header("Basic s3cret")], "s3cret"));
+        assert!(!authorized(&[], "s3cret"));
+        assert!(same(b"abc", b"abc"));
+        assert!(!same(b"abc", b"abd"));
+        assert!(!same(b"abc", b"abcd"));
+    }
+
+    #[test]
+    fn wrong_paths_methods_and_bodies_are_refused() {
+        assert_eq!(status(Method::Get, "/api/nothing", ""), 404);
+        assert_eq!(status(Method::Get, "/api/cost-items/7", ""), 404);
+        assert_eq!(status(Method::Get, "/api/cost-items/rent", ""), 404);
+        assert_eq!(status(Method::Put, "/api/cost-items", ""), 405);
+        assert_eq!(status(Method::Post, "/api/cost-items/0", "{}"), 405);
+        assert_eq!(status(Method::Post, "/api/cost-items", "not json"), 400);
+        assert_eq!(status(Method::Post, "/api/cost-items", "[1]"), 400);
+        assert_eq!(
+            status(Method::Post, "/api/cost-items", r#"{"what": " "}"#),
+            400
+        );
+        assert_eq!(status(Method::Get, "/api/budget", ""), 200);
+    }
+
+    #[test]
+    fn patch_changes_only_the_fields_given() {
+        let (status, body, event) = handle(
+            Method::Patch,
+            "/api/cost-items/0",
+            r#"{"cost": 950, "id": 5}"#,
+        );
+        assert_eq!(status, 200);
+        assert_eq!(body["what"], "Rent");
+        assert_eq!(body["cost_category"], "Housing");
+        assert_eq!(body["id"], 0);
+        match event {
+            Some(BudgetEvent::UpdateCostItem { id: 0, item }) => assert_eq!(item.cost, 950.0),
+            _ => panic!("expected an update of item 0"),
+        }
+
+        let (status, _, event) =
+            handle(Method::Patch, "/api/income-items/0", r#"{"amount": 3000}"#);
+        assert_eq!(status, 200);
+        assert!(event.is_none());
+        assert_eq!(status_of_patch(r#"{"cost": "a lot"}"#), 400);
+    }
+
+    #[test]
+    fn item_amounts_must_be_positive() {
+        assert_eq!(
+            status(
+                Method::Post,
+                "/api/cost-items",
+                r#"{"what": "Gym", "cost": 0}"#
+            ),
+            400
+        );
+        assert_eq!(
+            status(
+                Method::Post,
+                "/api/cost-items",
+                r#"{"what": "Gym", "cost": -30}"#
+            ),
+            400
+        );
+        assert_eq!(
+            status(
+                Method::Post,
+                "/api/cost-items",
+                r#"{"what": "Gym", "cost": 30}"#
+            ),
+            201
+        );
+        assert_eq!(status_of_patch(r#"{"cost": -1}"#), 400);
+        assert_eq!(
+            status(
+                Method::Post,
+                "/api/income-items",
+                r#"{"source": "Gift", "amount": 0}"#
+            ),
+            400
+        );
+        assert_eq!(
+            status(Method::Patch, "/api/income-items/0", r#"{"amount": -5}"#),
+            400
+        );
+        let transaction =
+            r#"{"description": "Refund", "amount": 0, "category": {"Expense": "Other"}}"#;
+        assert_eq!(status(Method::Post, "/api/transactions", transaction), 400);
+    }
+
+    #[test]
+    fn summaries_take_a_month_or_a_year() {
+        let period = |query: &str| {
+            request(Method::Get, &format!("/api/summary?{}", query), "")
+                .period()
+                .ok()
+        };
+        assert_eq!(
+            period("month=2025-03"),
+            Some(ReportPeriod::Month {
+                year: 2025,
+                month: 3
+            })
+        );
+        assert_eq!(period("year=2024"), Some(ReportPeriod::Year(2024)));
+        assert_eq!(period("month=2025-13"), None);
+        assert_eq!(period("year=soon"), None);
+        let today = Local::now().date_naive();
+        assert_eq!(
+            period("other=1"),
+            Some(ReportPeriod::Month {
+                year: today.year(),
+                month: today.month()
+            })
+        );
+        assert_eq!(status(Method::Get, "/api/summary?month=March", ""), 400);
+    }
+}
@@ -1,16 +1,53 @@
-mod models;
+#[cfg(not(target_arch = "wasm32"))]
+mod api;
 mod ui;
+#[cfg(target_arch = "wasm32")]
+mod web;
 
-use std::collections::HashMap;
+use std::{
+    path::PathBuf,
+    time::{Duration, SystemTime},
+};
 
+use budget_core::{
+    BudgetData, BudgetEvent,
+    amount::{self, Locale},
+    export::{self, journal::AccountMapping},
+    import, models,
+    models::template::BudgetTemplate,
+    quick_add, report,
+    settings::{self, AppSettings, BudgetSettings},
+    storage::{
+        self, BackendKind,
+        crypto::{FileKey, Passphrase},
+        files,
+        history::{self, HistoryEntry, Recorded},
+        merge::{ItemKey, Merge},
+        sync::SyncState,
+    },
+    undo::{Step, UndoStack},
+};
 use eframe::egui::{self};
 use egui::*;
+use serde::{Deserialize, Serialize};
+use web_time::Instant;
 
+use crate::ui::{
+    ui_state::UiState,
+    windows::{ConflictWindow, MainWindow, UnlockWindow},
+};
+#[cfg(not(target_arch = "wasm32"))]
 use crate::{
-    models::{cost_item::CostItem, income_item::IncomeItem},
-    ui::windows::MainWindow,
+    api::{Answer, ApiServer},
+    settings::ApiSettings,
 };
 
+/// How long the budget may stay unsaved after the last change.
+const AUTOSAVE_DELAY: Duration = Duration::from_secs(1);
+/// How often the open budget is checked for changes made elsewhere.
+const DISK_CHECK_INTERVAL: Duration = Duration::from_secs(2);
+
+#[cfg(not(target_arch = "wasm32"))]
 fn main() -> eframe::Result<()> {
     let options = eframe::NativeOptions {
         viewport: egui::ViewportBuilder::default()
@@ -22,127 +59,883 @@ fn main() -> eframe::Result<()> {
     eframe::run_native(
         "Budgetting",
         options,
-        Box::new(|cc| {
-            let mut style = (*cc.egui_ctx.style()).clone();
-            if let Some(ts) = style.text_styles.get_mut(&TextStyle::Body) {
-                ts.size = 16.0; // increase default body text size
-            }
-            cc.egui_ctx.set_style(style);
-
-            Ok(Box::new(RustedBudgetApp::new()))
-        }),
+        Box::new(|cc| Ok(Box::new(RustedBudgetApp::new(cc)))),
     )
 }
 
+#[cfg(target_arch = "wasm32")]
+fn main() {
+    wasm_bindgen_futures::spawn_local(async {
+        let started = match web::canvas("budget_canvas") {
+            Ok(canvas) => eframe::WebRunner::new()
+                .start(
+                    canvas,
+                    eframe::WebOptions::default(),
+                    Box::new(|cc| Ok(Box::new(RustedBudgetApp::new(cc)))),
+                )
+                .await
+                .map_err(|e| format!("{:?}", e)),
+            Err(err) => Err(err),
+        };
+        if let Err(err) = started {
+            web_sys::console::error_1(&format!("Could not start the app: {}", err).into());
+        }
+    });
+}
+
 #[derive(Debug, Clone)]
 pub enum AppEvent {
     // Navigation events
     ChangeView(AppView),
 
-    // Cost item events
-    AddCostItem(CostItem),
-    UpdateCostItem { id: u64, item: CostItem },
-    DeleteCostItem(u64),
+    // Changes to the budget itself
+    Budget(BudgetEvent),
 
-    // Income item events
-    AddIncomeItem(IncomeItem),
-    UpdateIncomeItem { index: usize, item: IncomeItem },
-    DeleteIncomeItem(usize),
+    // Undo events
+    Undo,
+    Redo,
 
     // UI events
     ToggleMenu,
 
-    // Future events
+    // Storage events
     SaveData,
     LoadData,
+    // Use another backend or file and load the budget stored there
+    SwitchStorage { kind: BackendKind, path: PathBuf },
+    // Copy the current budget into another backend or file and use it from now on
+    MigrateStorage { kind: BackendKind, path: PathBuf },
+
+    // Encryption events
+    Unlock(Passphrase),
+    Lock,
+    // Encrypt, re-key or (with `new: None`) decrypt the budget file
+    SetPassphrase { current: Passphrase, new: Option<Passphrase> },
+    SetAutoLock(u32),
+
+    // How amounts are typed and shown
+    SetLocale(Locale),
+    // Accounts of journal exports and imports
+    SetAccounts(AccountMapping),
+
+    // Budget events
+    // Create a budget at `path` and switch to it
+    NewBudget { name: String, template: BudgetTemplate, kind: BackendKind, path: PathBuf },
+    ForgetRecent { kind: BackendKind, path: PathBuf },
+
+    // Apply the pending merge, taking the version on disk where the flag is set
+    ResolveConflicts(Vec<bool>),
+
+    // Sync events
+    // An encrypted budget syncs with its passphrase
+    StartSync { folder: PathBuf, passphrase: Option<Passphrase> },
+    StopSync,
+
+    // History events
+    // Replace the budget with the one rebuilt from its history
+    RebuildFromHistory,
+
+    // Automation API events
+    #[cfg(not(target_arch = "wasm32"))]
+    SetApi { enabled: bool, port: u16 },
+    #[cfg(not(target_arch = "wasm32"))]
+    NewApiToken,
 }
 
-#[derive(Debug, Clone, PartialEq)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub enum AppView {
     Home,
     CostItems,
+    Import,
+    Export,
     Settings,
-}
-
-struct BudgetData {
-    cost_items: HashMap<u64, CostItem>,
-    income_items: Vec<IncomeItem>,
-    next_cost_id: u64,
-}
-
-impl Default for BudgetData {
-    fn default() -> Self {
-        Self {
-            cost_items: HashMap::new(),
-            income_items: Vec::new(),
-            next_cost_id: 0,
-        }
-    }
+    Budgets,
+    History,
 }
 
 struct RustedBudgetAppState {
     current_view: AppView,
     budget_data: BudgetData,
+    settings: AppSettings,
+    /// Outcome of the last explicit storage action, shown in Settings.
+    storage_status: Option<Result<String, String>>,
+    /// Set by data changes, cleared once they are saved.
+    unsaved_since: Option<Instant>,
+    /// Off when the stored budget could not be read, so an empty budget
+    /// never overwrites it.
+    autosave: bool,
+    /// Key of an encrypted budget file while it is unlocked.
+    key: Option<FileKey>,
+    /// An encrypted budget is waiting for its passphrase; `budget_data` is empty.
+    locked: bool,
+    last_activity: Instant,
+    /// The budget as last loaded or saved, the base for merging changes
+    /// made elsewhere.
+    synced: BudgetData,
+    /// Modification time of the stored budget when it was last loaded or saved.
+    on_disk: Option<SystemTime>,
+    last_disk_check: Instant,
+    /// Both sides changed the same items; waits for the conflict dialog.
+    pending_merge: Option<Merge>,
+    /// Folder sync of the open budget, when it has a sync folder.
+    sync: Option<SyncState>,
+    /// Author recorded in the history.
+    who: String,
+    /// Bumped on every history write, so views know to read it again.
+    history_version: u64,
+    undo: UndoStack,
+    /// The automation API server while it is on, or why it could not start.
+    #[cfg(not(target_arch = "wasm32"))]
+    api: Option<Result<ApiServer, String>>,
+    #[cfg(not(target_arch = "wasm32"))]
+    ctx: egui::Context,
 }
 
-impl Default for RustedBudgetAppState {
-    fn default() -> Self {
-        Self {
+impl RustedBudgetAppState {
+    /// Start with the saved settings and the budget from their backend.
+    fn load(ctx: &egui::Context) -> Self {
+        let mut state = Self {
             current_view: AppView::Home,
-            budget_data: Default::default(),
+            budget_data: BudgetData::default(),
+            settings: AppSettings::load(),
+            storage_status: None,
+            unsaved_since: None,
+            autosave: false,
+            key: None,
+            locked: false,
+            last_activity: Instant::now(),
+            synced: BudgetData::default(),
+            on_disk: None,
+            last_disk_check: Instant::now(),
+            pending_merge: None,
+            sync: None,
+            who: String::new(),
+            history_version: 0,
+            undo: UndoStack::default(),
+            #[cfg(not(target_arch = "wasm32"))]
+            api: None,
+            #[cfg(not(target_arch = "wasm32"))]
+            ctx: ctx.clone(),
+        };
+        if let Err(err) = state.settings.ensure_device_id() {
+            state.storage_status = Some(Err(err));
+        }
+        state.who = history::who(&state.settings.device_id);
+        if let Err(err) = state.open_storage() {
+            state.storage_status = Some(Err(err));
+        }
+        #[cfg(not(target_arch = "wasm32"))]
+        state.restart_api();
+        state
+    }
+
+    /// Read the budget from the current backend. An encrypted file without
+    /// its key locks the app until the passphrase is entered.
+    fn open_storage(&mut self) -> Result<String, String> {
+        let path = self.settings.storage_path().to_path_buf();
+        if self.key.is_none() && storage::is_encrypted(self.settings.storage, &path) {
+            self.lock_data();
+            return Ok(format!("{} is encrypted", path.display()));
+        }
+
+        self.budget_data = storage::load(self.settings.storage, &path, self.key.as_ref())?;
+        self.undo.clear();
+        self.unsaved_since = None;
+        self.autosave = true;
+        self.locked = false;
+        self.use_storage(self.settings.storage, path.clone());
+        self.mark_synced();
+        self.attach_sync(None);
+        Ok(format!("Loaded {}", path.display()))
+    }
+
+    /// Resume syncing if the open budget has a sync folder on this device.
+    /// An encrypted budget needs its `passphrase`, unless it is only read
+    /// again while already syncing.
+    fn attach_sync(&mut self, passphrase: Option<&Passphrase>) {
+        let Some(folder) = self
+            .settings
+            .sync_folders
+            .get(self.settings.storage_path())
+            .cloned()
+        else {
+            self.sync = None;
+            return;
+        };
+        let started = match self.sync.take() {
+            Some(mut sync) if sync.folder() == folder => {
+                sync.reload(&mut self.budget_data).map(|_| sync)
+            }
+            _ => SyncState::start(
+                &folder,
+                &self.settings.device_id,
+                self.settings.storage_path(),
+                &mut self.budget_data,
+                self.key.as_ref(),
+                passphrase,
+            )
+            .map(|(sync, _)| sync),
+        };
+        match started {
+            Ok(sync) => {
+                self.sync = Some(sync);
+                if self.budget_data != self.synced {
+                    self.unsaved_since = Some(Instant::now());
+                    self.undo.clear();
+                    self.history_snapshot("Changes from other devices");
+                }
+            }
+            Err(err) => self.storage_status = Some(Err(err)),
+        }
+    }
+
+    /// Apply changes other devices left in the sync folder.
+    fn poll_sync(&mut self) {
+        if self.pending_merge.is_some() {
+            return;
+        }
+        let Some(sync) = &mut self.sync else {
+            return;
+        };
+        match sync.poll(&mut self.budget_data) {
+            Ok(true) => {
+                self.unsaved_since = Some(Instant::now());
+                self.undo.clear();
+                self.history_snapshot("Changes from other devices");
+            }
+            Ok(false) => {}
+            Err(err) => self.storage_status = Some(Err(err)),
+        }
+    }
+
+    /// Remember the budget as it now is in storage.
+    fn mark_synced(&mut self) {
+        self.synced = self.budget_data.clone();
+        self.on_disk = storage::modified(self.settings.storage_path());
+        self.pending_merge = None;
+    }
+
+    /// Pick up changes another program or machine made to the stored
+    /// budget: reload it when there are no local edits, merge otherwise.
+    fn check_disk(&mut self) {
+        self.last_disk_check = Instant::now();
+        if self.locked || !self.autosave || self.pending_merge.is_some() {
+            return;
+        }
+        let path = self.settings.storage_path().to_path_buf();
+        let modified = storage::modified(&path);
+        if modified.is_none() || modified == self.on_disk {
+            return;
+        }
+        self.on_disk = modified;
+
+        let theirs = match storage::load(self.settings.storage, &path, self.key.as_ref()) {
+            Ok(theirs) => theirs,
+            Err(err) => {
+                self.storage_status = Some(Err(err));
+                return;
+            }
+        };
+        if self.unsaved_since.is_none() {
+            self.budget_data = theirs;
+            self.mark_synced();
+            self.undo.clear();
+            self.history_snapshot("Reloaded after a change on disk");
+            self.storage_status = Some(Ok(format!(
+                "Reloaded {}; it changed on disk",
+                path.display()
+            )));
+            return;
+        }
+
+        let merge = Merge::new(&self.synced, &self.budget_data, theirs);
+        if merge.conflicts.is_empty() {
+            self.budget_data = merge.resolve(&[]);
+            self.undo.clear();
+            self.history_snapshot("Merged changes made on disk");
+            self.storage_status = Some(
+                self.save()
+                    .map(|_| "Merged changes made on disk".to_string()),
+            );
+        } else {
+            self.pending_merge = Some(merge);
+        }
+    }
+
+    /// Check the stored budget for outside changes every few seconds.
+    fn watch_storage(&mut self, ctx: &egui::Context) {
+        let elapsed = self.last_disk_check.elapsed();
+        if elapsed >= DISK_CHECK_INTERVAL {
+            self.check_disk();
+            self.poll_sync();
+            ctx.request_repaint_after(DISK_CHECK_INTERVAL);
+        } else {
+            ctx.request_repaint_after(DISK_CHECK_INTERVAL - elapsed);
+        }
+    }
+
+    /// Start, restart or stop the API server to match the settings.
+    #[cfg(not(target_arch = "wasm32"))]
+    fn restart_api(&mut self) {
+        // Stop the old server first, so a new one can take its port
+        self.api = None;
+        let api = &self.settings.api;
+        if api.enabled {
+            self.api = Some(ApiServer::start(
+                api.port,
+                api.token.clone(),
+                self.ctx.clone(),
+            ));
+        }
+    }
+
+    /// Answer requests made through the API. Changes are applied like edits
+    /// made in the window, each its own undo step.
+    #[cfg(not(target_arch = "wasm32"))]
+    fn serve_api(&mut self) {
+        let requests = match &self.api {
+            Some(Ok(server)) => server.pending(),
+            _ => return,
+        };
+        for request in requests {
+            if self.locked {
+                request.respond(Answer::error(423, "the budget is locked"));
+                continue;
+            }
+            let (answer, event) = request.handle(&self.budget_data, self.settings.locale);
+            if let Some(event) = event {
+                self.handle_events(vec![AppEvent::Budget(event)]);
+            }
+            request.respond(answer);
+        }
+    }
+
+    /// Drop the budget and its key from memory.
+    fn lock_data(&mut self) {
+        self.budget_data = BudgetData::default();
+        self.key = None;
+        self.locked = true;
+        self.undo.clear();
+        self.unsaved_since = None;
+        self.autosave = false;
+        self.synced = BudgetData::default();
+        self.on_disk = None;
+        self.pending_merge = None;
+        self.sync = None;
+    }
+
+    /// Save pending changes before the budget is replaced or locked.
+    fn save_pending(&mut self) -> Result<(), String> {
+        if self.unsaved_since.is_some() && self.autosave {
+            self.save()?;
+        }
+        Ok(())
+    }
+
+    fn save(&mut self) -> Result<(), String> {
+        storage::save(
+            self.settings.storage,
+            self.settings.storage_path(),
+            &self.budget_data,
+            self.key.as_ref(),
+        )?;
+        self.unsaved_since = None;
+        self.autosave = true;
+        self.mark_synced();
+        Ok(())
+    }
+
+    /// Save once changes have settled, so dragging a value doesn't write
+    /// the budget on every frame.
+    fn autosave(&mut self, ctx: &egui::Context) {
+        let Some(since) = self.unsaved_since else {
+            return;
+        };
+        if !self.autosave || self.pending_merge.is_some() {
+            return;
+        }
+        let elapsed = since.elapsed();
+        if elapsed < AUTOSAVE_DELAY {
+            ctx.request_repaint_after(AUTOSAVE_DELAY - elapsed);
+            return;
+        }
+        // Never write over changes made elsewhere without merging them first
+        self.check_disk();
+        if self.unsaved_since.is_none() || self.pending_merge.is_some() {
+            return;
+        }
+        if let Err(err) = self.save() {
+            // Keep the changes marked unsaved and try again shortly
+            self.unsaved_since = Some(Instant::now());
+            self.storage_status = Some(Err(err));
+        }
+    }
+
+    /// Lock an encrypted budget once nobody has touched the app for the
+    /// configured time.
+    fn auto_lock(&mut self, ctx: &egui::Context) {
+        if self.locked
+            || self.key.is_none()
+            || self.settings.auto_lock_minutes == 0
+            || self.pending_merge.is_some()
+        {
+            return;
+        }
+        let limit = Duration::from_secs(self.settings.auto_lock_minutes as u64 * 60);
+        let idle = self.last_activity.elapsed();
+        if idle >= limit {
+            self.handle_event(AppEvent::Lock);
+        } else {
+            ctx.request_repaint_after(limit - idle);
+        }
+    }
+
+    /// Make `path` the active storage and, unless it is still locked, list
+    /// it first among the recent budgets.
+    fn use_storage(&mut self, kind: BackendKind, path: PathBuf) {
+        if !self.locked {
+            self.settings
+                .remember(kind, &path, &self.budget_data.settings.name);
+        }
+        self.settings.storage = kind;
+        self.settings.set_path_for(kind, path);
+        if let Err(err) = self.settings.save() {
+            self.storage_status = Some(Err(err));
+        }
+    }
+
+    /// A fresh budget laid out by `template`.
+    fn budget_from_template(&self, name: String, template: BudgetTemplate) -> BudgetData {
+        let (cost_items, income_items) = match template {
+            BudgetTemplate::CopyCurrent => (
+                self.budget_data.cost_items.values().cloned().collect(),
+                self.budget_data.income_items.clone(),
+            ),
+            _ => (template.cost_items(), template.income_items()),
+        };
+        BudgetData {
+            next_cost_id: cost_items.iter().map(|item| item.id + 1).max().unwrap_or(0),
+            cost_items: cost_items.into_iter().map(|item| (item.id, item)).collect(),
+            next_income_id: income_items
+                .iter()
+                .map(|item| item.id + 1)
+                .max()
+                .unwrap_or(0),
+            income_items,
+            transactions: Vec::new(),
+            next_transaction_id: 0,
+            settings: BudgetSettings { name },
+        }
+    }
+
+    /// Apply a data event and record it in the history and for other devices.
+    /// Returns the events that undo it.
+    fn apply_data_event(&mut self, event: BudgetEvent) -> Vec<BudgetEvent> {
+        self.unsaved_since = Some(Instant::now());
+        self.start_history();
+
+        let applied = self.budget_data.apply_tracked(&event);
+        self.record_history(vec![HistoryEntry::new(
+            &self.who,
+            Recorded::Event(event.clone()),
+            applied.items,
+        )]);
+        if let Some(sync) = &mut self.sync
+            && let Err(err) = sync.record(&event, &self.budget_data)
+        {
+            self.storage_status = Some(Err(err));
+        }
+        if let BudgetEvent::RenameBudget(_) = event {
+            // Show the new name among the recent budgets
+            let path = self.settings.storage_path().to_path_buf();
+            self.use_storage(self.settings.storage, path);
+        }
+        applied.undo
+    }
+
+    /// Apply events in order. Returns the events that undo all of them.
+    fn apply_data_events(&mut self, events: Vec<BudgetEvent>) -> Vec<BudgetEvent> {
+        let mut inverse = Vec::new();
+        for event in events {
+            let mut undo_events = self.apply_data_event(event);
+            undo_events.append(&mut inverse);
+            inverse = undo_events;
+        }
+        inverse
+    }
+
+    /// Handle one frame's events. Its data events are undone together.
+    fn handle_events(&mut self, events: Vec<AppEvent>) {
+        let mut edits = Vec::new();
+        for event in events {
+            let AppEvent::Budget(event) = event else {
+                self.handle_event(event);
+                continue;
+            };
+            let (label, deleted) = self.budget_data.describe(&event);
+            let group = match event {
+                BudgetEvent::UpdateCostItem { id, .. } => Some(ItemKey::CostItem(id)),
+                BudgetEvent::UpdateIncomeItem { id, .. } => Some(ItemKey::IncomeItem(id)),
+                _ => None,
+            };
+            edits.push((label, deleted, group, self.apply_data_event(event)));
+        }
+
+        let step = match edits.len() {
+            0 => return,
+            1 => {
+                let (label, deleted, group, inverse) = edits.remove(0);
+                Step::new(label, inverse).grouped(group).deleted(deleted)
+            }
+            count => {
+                let mut inverse = Vec::new();
+                for (_, _, _, mut undo_events) in edits {
+                    undo_events.append(&mut inverse);
+                    inverse = undo_events;
+                }
+                Step::new(format!("{} changes", count), inverse)
+            }
+        };
+        self.undo.push(step);
+    }
+
+    /// Begin the history with the budget as it is, if it has none yet.
+    fn start_history(&mut self) {
+        let path = history::path_for(self.settings.storage_path());
+        if self.locked || files::exists(&path) {
+            return;
+        }
+        self.history_snapshot("History started");
+    }
+
+    /// Record the whole budget, after it changed other than through events.
+    fn history_snapshot(&mut self, reason: &str) {
+        if self.locked {
+            return;
+        }
+        let change = Recorded::Snapshot {
+            reason: reason.to_string(),
+            budget: self.budget_data.clone(),
+        };
+        self.record_history(vec![HistoryEntry::new(&self.who, change, Vec::new())]);
+    }
+
+    fn record_history(&mut self, entries: Vec<HistoryEntry>) {
+        let path = history::path_for(self.settings.storage_path());
+        match history::append(&path, &entries, self.key.as_ref()) {
+            Ok(()) => self.history_version += 1,
+            Err(err) => self.storage_status = Some(Err(err)),
         }
     }
-}
 
-impl RustedBudgetAppState {
     fn handle_event(&mut self, event: AppEvent) {
         match event {
+            AppEvent::Budget(event) => {
+                self.apply_data_event(event);
+            }
             AppEvent::ChangeView(app_view) => {
                 self.current_view = app_view;
             }
-            AppEvent::AddCostItem(mut cost_item) => {
-                cost_item.id = self.budget_data.next_cost_id;
-                self.budget_data
-                    .cost_items
-                    .insert(self.budget_data.next_cost_id, cost_item);
-                println!(
-                    "Added cost item: {:?}",
-                    self.budget_data
-                        .cost_items
-                        .get_key_value(&self.budget_data.next_cost_id)
+            AppEvent::ToggleMenu => {}
+            AppEvent::Undo => {
+                if let Some(step) = self.undo.undo() {
+                    let redo = self.apply_data_events(step.events);
+                    self.undo.undone(Step::new(step.label, redo));
+                }
+            }
+            AppEvent::Redo => {
+                if let Some(step) = self.undo.redo() {
+                    let undo = self.apply_data_events(step.events);
+                    self.undo.redone(Step::new(step.label, undo));
+                }
+            }
+            AppEvent::SaveData => {
+                self.storage_status = Some(
+                    self.save()
+                        .map(|_| format!("Saved to {}", self.settings.storage_path().display())),
                 );
-                self.budget_data.next_cost_id += 1;
             }
-            AppEvent::UpdateCostItem { id, item } => {
-                if self.budget_data.cost_items.contains_key(&id) {
-                    self.budget_data.cost_items.insert(id, item);
+            AppEvent::LoadData => {
+                self.storage_status = Some(self.open_storage());
+            }
+            AppEvent::SwitchStorage { kind, path } => {
+                if let Err(err) = self.save_pending() {
+                    self.storage_status = Some(Err(err));
+                    return;
                 }
+                if storage::is_encrypted(kind, &path) {
+                    self.lock_data();
+                    self.use_storage(kind, path.clone());
+                    self.storage_status = Some(Ok(format!("Now using {}", path.display())));
+                    return;
+                }
+                self.storage_status = Some(storage::load(kind, &path, None).map(|budget_data| {
+                    self.budget_data = budget_data;
+                    self.key = None;
+                    self.unsaved_since = None;
+                    self.undo.clear();
+                    self.autosave = true;
+                    self.use_storage(kind, path.clone());
+                    self.mark_synced();
+                    self.attach_sync(None);
+                    format!("Now using {}", path.display())
+                }));
             }
-            AppEvent::DeleteCostItem(id) => {
-                if self.budget_data.cost_items.contains_key(&id) {
-                    self.budget_data.cost_items.remove(&id);
+            AppEvent::MigrateStorage { kind, path } => {
+                // A database can't be encrypted, so it would hold the budget in the clear
+                if self.key.is_some() && kind != BackendKind::File {
+                    self.storage_status = Some(Err(
+                        "Remove the passphrase before moving an encrypted budget out of its file"
+                            .to_string(),
+                    ));
+                    return;
                 }
+                let key = self.key.as_ref();
+                self.storage_status =
+                    Some(storage::save(kind, &path, &self.budget_data, key).map(|_| {
+                        self.unsaved_since = None;
+                        self.autosave = true;
+                        // Keep syncing the budget at its new place
+                        let old_path = self.settings.storage_path().to_path_buf();
+                        if let Some(folder) = self.settings.sync_folders.remove(&old_path) {
+                            self.settings.sync_folders.insert(path.clone(), folder);
+                        }
+                        self.use_storage(kind, path.clone());
+                        self.mark_synced();
+                        format!("Copied the budget to {} and switched to it", path.display())
+                    }));
             }
-            AppEvent::AddIncomeItem(income_item) => {
-                self.budget_data.income_items.push(income_item);
+            AppEvent::Unlock(passphrase) => {
+                let path = self.settings.storage_path().to_path_buf();
+                match storage::file::unlock(&path, &passphrase) {
+                    Ok((budget_data, key)) => {
+                        self.budget_data = budget_data;
+                        self.key = Some(key);
+                        self.locked = false;
+                        self.undo.clear();
+                        self.autosave = true;
+                        self.last_activity = Instant::now();
+                        self.storage_status = None;
+                        self.use_storage(self.settings.storage, path);
+                        self.mark_synced();
+                        self.attach_sync(Some(&passphrase));
+                    }
+                    Err(err) => self.storage_status = Some(Err(err)),
+                }
             }
-            AppEvent::UpdateIncomeItem { index, item } => {
-                if index < self.budget_data.income_items.len() {
-                    self.budget_data.income_items[index] = item;
+            AppEvent::Lock => {
+                if self.key.is_none() {
+                    return;
+                }
+                match self.save_pending() {
+                    Ok(()) => {
+                        self.lock_data();
+                        self.storage_status = None;
+                    }
+                    Err(err) => self.storage_status = Some(Err(err)),
                 }
             }
-            AppEvent::DeleteIncomeItem(index) => {
-                if index < self.budget_data.income_items.len() {
-                    let removed = self.budget_data.income_items.remove(index);
-                    println!("Removed income item: {:?}", removed);
+            AppEvent::SetPassphrase { current, new } => {
+                if self.settings.storage != BackendKind::File {
+                    self.storage_status =
+                        Some(Err("only the file backend can be encrypted".to_string()));
+                    return;
+                }
+                // The sync folder's key comes from the passphrase
+                if self.sync.is_some() {
+                    self.storage_status = Some(Err(
+                        "stop syncing before changing the encryption".to_string()
+                    ));
+                    return;
+                }
+                if self.key.as_ref().is_some_and(|key| !key.matches(&current)) {
+                    self.storage_status = Some(Err("the current passphrase is wrong".to_string()));
+                    return;
                 }
+                let new_key = match new.as_ref().map(FileKey::new).transpose() {
+                    Ok(new_key) => new_key,
+                    Err(err) => {
+                        self.storage_status = Some(Err(err));
+                        return;
+                    }
+                };
+
+                let message = match (&self.key, &new_key) {
+                    (None, Some(_)) => "Budget file encrypted",
+                    (Some(_), Some(_)) => "Passphrase changed",
+                    (_, None) => "Encryption removed",
+                };
+                let old_key = std::mem::replace(&mut self.key, new_key);
+                self.storage_status = Some(match self.save() {
+                    Ok(()) => {
+                        let path = history::path_for(self.settings.storage_path());
+                        history::rekey(&path, old_key.as_ref(), self.key.as_ref())
+                            .map(|_| message.to_string())
+                    }
+                    Err(err) => {
+                        // The file still has the old encryption
+                        self.key = old_key;
+                        Err(err)
+                    }
+                });
             }
-            AppEvent::ToggleMenu => {}
-            AppEvent::SaveData => {
-                println!("Saving data...");
+            AppEvent::SetAutoLock(minutes) => {
+                self.settings.auto_lock_minutes = minutes;
+                if let Err(err) = self.settings.save() {
+                    self.storage_status = Some(Err(err));
+                }
             }
-            AppEvent::LoadData => {
-                println!("Loading data...");
+            AppEvent::SetLocale(locale) => {
+                self.settings.locale = locale;
+                if let Err(err) = self.settings.save() {
+                    self.storage_status = Some(Err(err));
+                }
+            }
+            AppEvent::SetAccounts(accounts) => {
+                self.settings.accounts = accounts;
+                if let Err(err) = self.settings.save() {
+                    self.storage_status = Some(Err(err));
+                }
+            }
+            AppEvent::NewBudget {
+                name,
+                template,
+                kind,
+                path,
+            } => {
+                // Never replace a budget that is already there
+                if files::exists(&path) {
+                    self.storage_status = Some(Err(format!("{} already exists", path.display())));
+                    return;
+                }
+                if let Err(err) = self.save_pending() {
+                    self.storage_status = Some(Err(err));
+                    return;
+                }
+                let budget_data = self.budget_from_template(name, template);
+                self.storage_status =
+                    Some(storage::save(kind, &path, &budget_data, None).map(|_| {
+                        self.budget_data = budget_data;
+                        self.key = None;
+                        self.locked = false;
+                        self.undo.clear();
+                        self.unsaved_since = None;
+                        self.autosave = true;
+                        self.use_storage(kind, path.clone());
+                        self.mark_synced();
+                        self.attach_sync(None);
+                        format!("Created {}", path.display())
+                    }));
+            }
+            AppEvent::ForgetRecent { kind, path } => {
+                self.settings.forget(kind, &path);
+                if let Err(err) = self.settings.save() {
+                    self.storage_status = Some(Err(err));
+                }
+            }
+            AppEvent::ResolveConflicts(take_theirs) => {
+                if let Some(merge) = self.pending_merge.take() {
+                    self.budget_data = merge.resolve(&take_theirs);
+                    self.undo.clear();
+                    self.history_snapshot("Merged changes made on disk");
+                    self.storage_status = Some(
+                        self.save()
+                            .map(|_| "Merged changes made on disk".to_string()),
+                    );
+                }
+            }
+            AppEvent::StartSync { folder, passphrase } => {
+                match SyncState::start(
+                    &folder,
+                    &self.settings.device_id,
+                    self.settings.storage_path(),
+                    &mut self.budget_data,
+                    self.key.as_ref(),
+                    passphrase.as_ref(),
+                ) {
+                    Ok((sync, joined)) => {
+                        let path = self.settings.storage_path().to_path_buf();
+                        self.settings.sync_folders.insert(path, folder.clone());
+                        if let Err(err) = self.settings.save() {
+                            self.storage_status = Some(Err(err));
+                            return;
+                        }
+                        if joined {
+                            self.undo.clear();
+                            self.history_snapshot("Joined a shared budget");
+                        }
+                        self.storage_status = Some(Ok(if joined {
+                            self.unsaved_since = Some(Instant::now());
+                            format!(
+                                "Joined the budget shared in {} by {} devices",
+                                folder.display(),
+                                sync.device_count()
+                            )
+                        } else {
+                            format!("Syncing through {}", folder.display())
+                        }));
+                        self.sync = Some(sync);
+                    }
+                    Err(err) => self.storage_status = Some(Err(err)),
+                }
+            }
+            AppEvent::StopSync => {
+                self.sync = None;
+                let path = self.settings.storage_path().to_path_buf();
+                self.settings.sync_folders.remove(&path);
+                self.storage_status = Some(
+                    self.settings
+                        .save()
+                        .map(|_| "Stopped syncing this budget".to_string()),
+                );
+            }
+            AppEvent::RebuildFromHistory => {
+                let path = history::path_for(self.settings.storage_path());
+                self.storage_status =
+                    Some(history::read(&path, self.key.as_ref()).and_then(|entries| {
+                        if entries.is_empty() {
+                            return Err("this budget has no history yet".to_string());
+                        }
+                        self.budget_data = history::replay(&entries);
+                        self.undo.clear();
+                        self.unsaved_since = Some(Instant::now());
+                        Ok(format!(
+                            "Rebuilt the budget from {} history entries",
+                            entries.len()
+                        ))
+                    }));
+            }
+            #[cfg(not(target_arch = "wasm32"))]
+            AppEvent::SetApi { enabled, port } => {
+                if enabled && self.settings.api.token.is_empty() {
+                    match ApiSettings::new_token() {
+                        Ok(token) => self.settings.api.token = token,
+                        Err(err) => {
+                            self.api = Some(Err(err));
+                            return;
+                        }
+                    }
+                }
+                self.settings.api.enabled = enabled;
+                self.settings.api.port = port;
+                if let Err(err) = self.settings.save() {
+                    self.storage_status = Some(Err(err));
+                }
+                self.restart_api();
+            }
+            #[cfg(not(target_arch = "wasm32"))]
+            AppEvent::NewApiToken => {
+                match ApiSettings::new_token() {
+                    Ok(token) => self.settings.api.token = token,
+                    Err(err) => {
+                        self.api = Some(Err(err));
+                        return;
+                    }
+                }
+                if let Err(err) = self.settings.save() {
+                    self.storage_status = Some(Err(err));
+                }
+                self.restart_api();
             }
         }
     }
@@ -151,25 +944,95 @@ impl RustedBudgetAppState {
 struct RustedBudgetApp {
     state: RustedBudgetAppState,
     main_window: MainWindow,
+    unlock_window: UnlockWindow,
+    conflict_window: ConflictWindow,
 }
 
 impl RustedBudgetApp {
-    fn new() -> Self {
+    fn new(cc: &eframe::CreationContext) -> Self {
+        let mut style = (*cc.egui_ctx.style()).clone();
+        if let Some(ts) = style.text_styles.get_mut(&TextStyle::Body) {
+            ts.size = 16.0; // increase default body text size
+        }
+        cc.egui_ctx.set_style(style);
+
+        let ui_state: UiState = cc
+            .storage
+            .and_then(|storage| eframe::get_value(storage, eframe::APP_KEY))
+            .unwrap_or_default();
+        let mut state = RustedBudgetAppState::load(&cc.egui_ctx);
+        state.current_view = ui_state.view.clone();
+        let mut main_window = MainWindow::new();
+        main_window.restore(&ui_state);
+
         Self {
-            state: RustedBudgetAppState::default(),
-            main_window: MainWindow::new(),
+            state,
+            main_window,
+            unlock_window: UnlockWindow::new(),
+            conflict_window: ConflictWindow::new(),
         }
     }
 }
 
 impl eframe::App for RustedBudgetApp {
     fn update(&mut self, ctx: &egui::Context, _frame: &mut eframe::Frame) {
-        // Collect events from UI
-        let events = self.main_window.show(ctx, &self.state);
+        if ctx.input(|i| !i.events.is_empty()) {
+            self.state.last_activity = Instant::now();
+        }
+
+        // Collect events from UI; an encrypted budget must be unlocked first
+        let mut events = if self.state.locked {
+            self.unlock_window.show(ctx, &self.state)
+        } else {
+            self.main_window.show(ctx, &self.state)
+        };
+        if let Some(merge) = &self.state.pending_merge {
+            events.extend(self.conflict_window.show(ctx, merge));
+        }
+
+        // Undo and redo, unless a text field takes them for its own text
+        if !self.state.locked && !ctx.wants_keyboard_input() {
+            ctx.input_mut(|i| {
+                let undo = KeyboardShortcut::new(Modifiers::COMMAND, Key::Z);
+                let redo = KeyboardShortcut::new(Modifiers::COMMAND | Modifiers::SHIFT, Key::Z);
+                // Redo first, as the undo shortcut matches it too
+                if i.consume_shortcut(&redo) {
+                    events.push(AppEvent::Redo);
+                } else if i.consume_shortcut(&undo) {
+                    events.push(AppEvent::Undo);
+                }
+            });
+        }
 
         // Process all events
-        for event in events {
-            self.state.handle_event(event);
+        self.state.handle_events(events);
+        #[cfg(not(target_arch = "wasm32"))]
+        self.state.serve_api();
+
+        self.state.watch_storage(ctx);
+        self.state.autosave(ctx);
+        self.state.auto_lock(ctx);
+    }
+
+    fn save(&mut self, storage: &mut dyn eframe::Storage) {
+        let mut ui_state = UiState {
+            view: self.state.current_view.clone(),
+            ..Default::default()
+        };
+        self.main_window.store(&mut ui_state);
+        eframe::set_value(storage, eframe::APP_KEY, &ui_state);
+    }
+
+    fn on_exit(&mut self, _gl: Option<&eframe::glow::Context>) {
+        // Unresolved conflicts keep the local version
+        if let Some(merge) = self.state.pending_merge.take() {
+            self.state.budget_data = merge.resolve(&[]);
+        }
+        if self.state.unsaved_since.is_some()
+            && self.state.autosave
+            && let Err(err) = self.state.save()
+        {
+            eprintln!("Could not save budget: {}", err);
         }
     }
 }
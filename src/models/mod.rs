@@ -1,2 +0,0 @@
-pub mod cost_item;
-pub mod income_item;
\ No newline at end of file
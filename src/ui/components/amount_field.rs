@@ -0,0 +1,15 @@
+//! Inline editing of amounts in the cost tables.
+
+use eframe::egui::DragValue;
+
+use crate::amount::{self, Locale};
+
+/// Drag to change `value`, or click and type it as `locale` writes amounts,
+/// e.g. `1 200,50`, `349 kr` or `1.2k`.
+pub fn amount_field(value: &mut f32, locale: Locale) -> DragValue<'_> {
+    DragValue::new(value)
+        .prefix("$")
+        .speed(0.1)
+        .custom_formatter(move |n, _| amount::format(n as f32, locale))
+        .custom_parser(move |text| amount::parse(text, locale).map(f64::from))
+}
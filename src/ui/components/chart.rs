@@ -0,0 +1,188 @@
+use std::path::Path;
+
+use eframe::egui::*;
+
+use crate::{
+    report::{png, svg, svg::Slice},
+    ui::components::file_io::save_file,
+};
+
+fn color((r, g, b): (u8, u8, u8)) -> Color32 {
+    Color32::from_rgb(r, g, b)
+}
+
+/// Horizontal bars with the label and value above each bar. The returned
+/// response covers the whole chart and senses right clicks.
+pub fn bar_chart(ui: &mut Ui, bars: &[Slice]) -> Response {
+    let max_total = bars
+        .iter()
+        .map(|b| b.value)
+        .fold(0.0_f32, f32::max)
+        .max(1.0);
+
+    ui.vertical(|ui| {
+        for bar in bars {
+            ui.horizontal(|ui| {
+                ui.label(RichText::new(&bar.label).strong());
+                ui.with_layout(Layout::right_to_left(Align::Center), |ui| {
+                    ui.add_space(15.0);
+                    ui.label(format!("${:.2}", bar.value));
+                });
+            });
+
+            let available_width = ui.available_width();
+            let bar_height = 14.0;
+            let (rect, _resp) =
+                ui.allocate_exact_size(vec2(available_width, bar_height), Sense::hover());
+            let painter = ui.painter();
+
+            // Background bar
+            painter.rect_filled(rect, 3.0, Color32::from_gray(230));
+
+            // Filled portion
+            let frac = (bar.value / max_total).clamp(0.0, 1.0);
+            let filled_rect = Rect {
+                min: rect.min,
+                max: pos2(rect.min.x + rect.width() * frac, rect.max.y),
+            };
+            painter.rect_filled(filled_rect, 3.0, color(bar.color));
+
+            ui.add_space(8.0);
+        }
+    })
+    .response
+    .interact(Sense::click())
+}
+
+/// Pie chart on a light gray disc, up to 220px wide. Slices under 1% are
+/// skipped to avoid rendering artifacts.
+pub fn pie_chart(ui: &mut Ui, slices: &[Slice], center_label: Option<&str>) -> Response {
+    let desired = vec2(ui.available_width().min(220.0), 220.0);
+    let (rect, response) = ui.allocate_exact_size(desired, Sense::click());
+    let painter = ui.painter();
+    let center = rect.center();
+    let radius = rect.width().min(rect.height()) * 0.45;
+
+    // background circle
+    painter.circle_filled(center, radius, Color32::LIGHT_GRAY);
+
+    let total: f32 = slices.iter().map(|s| s.value.max(0.0)).sum();
+    if total > 0.0 {
+        let mut start = -std::f32::consts::FRAC_PI_2;
+        let max_steps = 96usize;
+
+        for slice in slices {
+            let frac = (slice.value.max(0.0) / total).clamp(0.0, 1.0);
+            if frac < 0.01 {
+                continue;
+            }
+            let end = start + frac * std::f32::consts::TAU;
+            let steps = ((max_steps as f32 * frac).ceil() as usize).clamp(2, max_steps);
+
+            let mut points = Vec::with_capacity(steps + 2);
+            points.push(center);
+            for s in 0..=steps {
+                let t = s as f32 / steps as f32;
+                let a = start + t * (end - start);
+                points.push(center + vec2(a.cos() * radius, a.sin() * radius));
+            }
+            painter.add(Shape::convex_polygon(
+                points,
+                color(slice.color),
+                Stroke::NONE,
+            ));
+
+            start = end;
+        }
+    }
+
+    if let Some(label) = center_label {
+        painter.text(
+            center,
+            Align2::CENTER_CENTER,
+            label,
+            TextStyle::Heading.resolve(ui.style()),
+            Color32::BLACK,
+        );
+    }
+
+    response
+}
+
+/// Where and how big charts are saved from their context menu. Shared by
+/// all charts on a page so the last used path and size stick.
+pub struct ChartExport {
+    path: String,
+    width: u32,
+    status: Option<Result<String, String>>,
+}
+
+impl ChartExport {
+    pub fn new() -> Self {
+        Self {
+            path: String::new(),
+            width: 800,
+            status: None,
+        }
+    }
+
+    /// Right-click menu for saving one chart. `render_svg` returns the chart
+    /// as SVG at any size; it is scaled to the chosen width on export.
+    pub fn context_menu(
+        &mut self,
+        response: &Response,
+        name: &str,
+        render_svg: impl Fn() -> String,
+    ) {
+        response.context_menu(|ui| {
+            ui.label(RichText::new(format!("Export {}", name)).strong());
+            ui.separator();
+
+            ui.horizontal(|ui| {
+                ui.label("File:");
+                ui.add_sized(
+                    [260.0, 20.0],
+                    TextEdit::singleline(&mut self.path).hint_text("/path/to/chart.svg"),
+                );
+            });
+            ui.horizontal(|ui| {
+                ui.label("Width:");
+                ui.add(
+                    DragValue::new(&mut self.width)
+                        .range(100..=8000)
+                        .suffix(" px"),
+                );
+            });
+
+            ui.horizontal(|ui| {
+                let can_export = !self.path.trim().is_empty();
+                let path = Path::new(self.path.trim());
+
+                if ui
+                    .add_enabled(can_export, Button::new("Save SVG"))
+                    .clicked()
+                {
+                    let svg = svg::resize(&render_svg(), self.width as f32);
+                    self.status = Some(save_file(path, svg.as_bytes()));
+                }
+                if ui
+                    .add_enabled(can_export, Button::new("Save PNG"))
+                    .clicked()
+                {
+                    let svg = svg::resize(&render_svg(), self.width as f32);
+                    self.status = Some(png::render(&svg).and_then(|png| save_file(path, &png)));
+                }
+            });
+
+            match &self.status {
+                Some(Ok(message)) => {
+                    ui.colored_label(Color32::from_rgb(0x2C, 0xA0, 0x2C), message);
+                }
+                Some(Err(err)) => {
+                    ui.colored_label(Color32::from_rgb(0xD6, 0x27, 0x28), err);
+                }
+                None => {}
+            }
+        });
+    }
+}
@@ -1,13 +1,20 @@
 use eframe::egui::*;
 use egui_extras::{Column, TableBuilder};
+use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 
 use crate::{
-    AppEvent,
+    AppEvent, BudgetEvent,
+    amount::Locale,
+    export::text_table,
     models::cost_item::{CostCycle, CostItem},
+    ui::components::{
+        amount_field::amount_field,
+        table_copy::{copy_menu, copy_shortcuts},
+    },
 };
 
-#[derive(Clone, Copy, PartialEq)]
+#[derive(Clone, Copy, PartialEq, Serialize, Deserialize)]
 pub enum SortColumn {
     What,
     Cost,
@@ -15,12 +22,28 @@ pub enum SortColumn {
     Cycle,
 }
 
-#[derive(Clone, Copy, PartialEq)]
+#[derive(Clone, Copy, PartialEq, Serialize, Deserialize)]
 pub enum SortOrder {
     Ascending,
     Descending,
 }
 
+/// How a cost item table is sorted, kept between sessions.
+#[derive(Clone, Copy, Serialize, Deserialize)]
+pub struct TableSort {
+    pub column: Option<SortColumn>,
+    pub order: SortOrder,
+}
+
+impl Default for TableSort {
+    fn default() -> Self {
+        Self {
+            column: None,
+            order: SortOrder::Ascending,
+        }
+    }
+}
+
 pub struct CostItemTable {
     _tag_inputs: HashMap<usize, String>,
     sort_column: Option<SortColumn>,
@@ -36,16 +59,29 @@ impl CostItemTable {
         }
     }
 
+    pub fn sort(&self) -> TableSort {
+        TableSort {
+            column: self.sort_column,
+            order: self.sort_order,
+        }
+    }
+
+    pub fn set_sort(&mut self, sort: TableSort) {
+        self.sort_column = sort.column;
+        self.sort_order = sort.order;
+    }
+
     pub fn show(
         &mut self,
         ui: &mut Ui,
         items: &[CostItem],
         _show_summary: bool,
         table_id: String,
+        locale: Locale,
     ) -> Vec<AppEvent> {
         let mut events = Vec::new();
 
-        ScrollArea::vertical()
+        let output = ScrollArea::vertical()
             .id_salt(table_id.clone() + "_scroll")
             .auto_shrink([false, true])
             .show(ui, |ui| {
@@ -54,6 +90,7 @@ impl CostItemTable {
                         .striped(true)
                         .resizable(true) // You can add this back now
                         .cell_layout(Layout::left_to_right(Align::Center))
+                        .sense(Sense::click()) // for the copy context menu
                         .column(Column::auto().at_least(40.0).at_most(50.0)) // Delete
                         .column(Column::auto().at_least(120.0).at_most(180.0)) // What
                         .column(Column::auto().at_least(80.0).at_most(100.0)) // Cost
@@ -64,14 +101,28 @@ impl CostItemTable {
                             self.render_headers(&mut header);
                         })
                         .body(|mut body| {
-                            let sorted_items = self.get_sorted_items(items);
-                            for (_, item) in sorted_items {
-                                self.render_row(&mut body, item, &mut events);
+                            let visible: Vec<&CostItem> = self
+                                .get_sorted_items(items)
+                                .into_iter()
+                                .map(|(_, item)| item)
+                                .collect();
+                            for item in &visible {
+                                self.render_row(&mut body, item, &visible, locale, &mut events);
                             }
                         });
                 });
             });
 
+        // Copy the rows as shown, in the current sort order
+        let visible: Vec<&CostItem> = self
+            .get_sorted_items(items)
+            .into_iter()
+            .map(|(_, item)| item)
+            .collect();
+        copy_shortcuts(ui, output.inner_rect, |format| {
+            text_table::cost_items(&visible, format, locale)
+        });
+
         events
     }
 
@@ -153,13 +204,15 @@ impl CostItemTable {
         &mut self,
         body: &mut egui_extras::TableBody,
         item: &CostItem,
+        visible: &[&CostItem],
+        locale: Locale,
         events: &mut Vec<AppEvent>,
     ) {
         body.row(55.0, |mut row| {
             // Delete button
             row.col(|ui| {
                 if ui.small_button("🗑").on_hover_text("Delete item").clicked() {
-                    events.push(AppEvent::DeleteCostItem(item.id));
+                    events.push(AppEvent::Budget(BudgetEvent::DeleteCostItem(item.id)));
                 }
             });
 
@@ -170,24 +223,24 @@ impl CostItemTable {
                 if response.changed() {
                     let mut updated_item = item.clone();
                     updated_item.what = temp_what;
-                    events.push(AppEvent::UpdateCostItem {
+                    events.push(AppEvent::Budget(BudgetEvent::UpdateCostItem {
                         id: updated_item.id,
                         item: updated_item,
-                    });
+                    }));
                 }
             });
 
             // Cost field
             row.col(|ui| {
                 let mut temp_cost = item.cost;
-                let response = ui.add(DragValue::new(&mut temp_cost).prefix("$").speed(0.1));
+                let response = ui.add(amount_field(&mut temp_cost, locale));
                 if response.changed() {
                     let mut updated_item = item.clone();
                     updated_item.cost = temp_cost;
-                    events.push(AppEvent::UpdateCostItem {
+                    events.push(AppEvent::Budget(BudgetEvent::UpdateCostItem {
                         id: item.id,
                         item: updated_item,
-                    });
+                    }));
                 }
             });
 
@@ -208,6 +261,10 @@ impl CostItemTable {
                     ui.label("(no tags)");
                 }
             });
+
+            row.response().context_menu(|ui| {
+                copy_menu(ui, |format| text_table::cost_items(visible, format, locale));
+            });
         });
     }
 
@@ -0,0 +1,30 @@
+use eframe::egui::*;
+
+use crate::export::csv::{CsvOptions, DecimalFormat};
+
+/// Delimiter and decimal format pickers shared by CSV import and export.
+pub fn csv_options_ui(ui: &mut Ui, id_salt: &str, options: &mut CsvOptions) {
+    ui.label("Delimiter:");
+    ComboBox::from_id_salt((id_salt, "delimiter"))
+        .selected_text(match options.delimiter {
+            b';' => "Semicolon (;)",
+            b'\t' => "Tab",
+            _ => "Comma (,)",
+        })
+        .show_ui(ui, |ui| {
+            ui.selectable_value(&mut options.delimiter, b',', "Comma (,)");
+            ui.selectable_value(&mut options.delimiter, b';', "Semicolon (;)");
+            ui.selectable_value(&mut options.delimiter, b'\t', "Tab");
+        });
+
+    ui.label("Decimals:");
+    ComboBox::from_id_salt((id_salt, "decimal"))
+        .selected_text(match options.decimal {
+            DecimalFormat::Point => "1234.50",
+            DecimalFormat::Comma => "1234,50",
+        })
+        .show_ui(ui, |ui| {
+            ui.selectable_value(&mut options.decimal, DecimalFormat::Point, "1234.50");
+            ui.selectable_value(&mut options.decimal, DecimalFormat::Comma, "1234,50");
+        });
+}
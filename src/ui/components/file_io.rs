@@ -0,0 +1,94 @@
+//! Files in and out of the import and export windows. The desktop reads and
+//! writes the path typed in; the browser build has no file system, so it
+//! opens the file picker instead and hands exports over as downloads.
+
+use std::path::Path;
+
+use eframe::egui::*;
+
+/// A file to import: a path field and `button` on the desktop, a picker
+/// limited to `accept` (e.g. ".csv") in the browser. Returns the file's name
+/// and contents once one is chosen.
+#[cfg(not(target_arch = "wasm32"))]
+pub fn open_file(
+    ui: &mut Ui,
+    _id_salt: &str,
+    path: &mut String,
+    hint: &str,
+    _accept: &str,
+    button: &str,
+) -> Option<(String, Result<Vec<u8>, String>)> {
+    ui.label("File:");
+    ui.add_sized([350.0, 20.0], TextEdit::singleline(path).hint_text(hint));
+
+    if !ui.button(button).clicked() {
+        return None;
+    }
+    let path = Path::new(path.trim());
+    let contents = std::fs::read(path).map_err(|e| format!("{}: {}", path.display(), e));
+    Some((path.display().to_string(), contents))
+}
+
+/// The browser reads the picked file in the background, so it arrives a
+/// few frames after the click, through egui's temporary memory.
+#[cfg(target_arch = "wasm32")]
+pub fn open_file(
+    ui: &mut Ui,
+    id_salt: &str,
+    _path: &mut String,
+    _hint: &str,
+    accept: &str,
+    _button: &str,
+) -> Option<(String, Result<Vec<u8>, String>)> {
+    let id = Id::new(("open_file", id_salt));
+
+    if ui.button("Choose file…").clicked() {
+        let ctx = ui.ctx().clone();
+        let picked = crate::web::pick_file(accept, ui.ctx(), move |name, contents| {
+            ctx.data_mut(|data| data.insert_temp(id, (name, contents)));
+        });
+        if let Err(err) = picked {
+            return Some((String::new(), Err(err)));
+        }
+    }
+
+    ui.ctx()
+        .data_mut(|data| data.remove_temp::<(String, Vec<u8>)>(id))
+        .map(|(name, contents)| (name, Ok(contents)))
+}
+
+/// Write an export to `path`, or download it under the same file name in the
+/// browser. Returns what was done, for the status line.
+pub fn save_file(path: &Path, contents: &[u8]) -> Result<String, String> {
+    #[cfg(not(target_arch = "wasm32"))]
+    {
+        std::fs::write(path, contents)
+            .map(|_| format!("Wrote {}", path.display()))
+            .map_err(|e| format!("{}: {}", path.display(), e))
+    }
+    #[cfg(target_arch = "wasm32")]
+    {
+        let name = path
+            .file_name()
+            .map(|name| name.to_string_lossy().to_string())
+            .unwrap_or_else(|| "export".to_string());
+        crate::web::download(&name, contents).map(|_| format!("Downloaded {}", name))
+    }
+}
+
+/// Several files named by the export, into the folder `dir` or as separate
+/// downloads.
+pub fn save_files(dir: &Path, files: Vec<(&str, Vec<u8>)>) -> Result<String, String> {
+    #[cfg(not(target_arch = "wasm32"))]
+    std::fs::create_dir_all(dir).map_err(|e| format!("{}: {}", dir.display(), e))?;
+
+    let count = files.len();
+    for (name, contents) in files {
+        save_file(&dir.join(name), &contents)?;
+    }
+    Ok(if cfg!(target_arch = "wasm32") {
+        format!("Downloaded {} files", count)
+    } else {
+        format!("Wrote {} files to {}", count, dir.display())
+    })
+}
@@ -1,3 +1,8 @@
-pub mod quick_add_expense;
-pub mod quick_add_income;
-pub mod cost_item_table;
\ No newline at end of file
+pub mod quick_add_input;
+pub mod amount_field;
+pub mod cost_item_table;
+pub mod chart;
+pub mod csv_options;
+pub mod file_io;
+pub mod period_picker;
+pub mod table_copy;
\ No newline at end of file
@@ -0,0 +1,60 @@
+use chrono::{Datelike, Local};
+use eframe::egui::*;
+
+use crate::report::ReportPeriod;
+
+const MONTHS: [&str; 12] = [
+    "January",
+    "February",
+    "March",
+    "April",
+    "May",
+    "June",
+    "July",
+    "August",
+    "September",
+    "October",
+    "November",
+    "December",
+];
+
+pub fn current_month() -> ReportPeriod {
+    let today = Local::now().date_naive();
+    ReportPeriod::Month {
+        year: today.year(),
+        month: today.month(),
+    }
+}
+
+/// Month / year switch with the matching year and month pickers.
+pub fn period_picker(ui: &mut Ui, id_salt: &str, period: &mut ReportPeriod) {
+    let (mut year, mut month) = match *period {
+        ReportPeriod::Month { year, month } => (year, Some(month)),
+        ReportPeriod::Year(year) => (year, None),
+    };
+
+    let mut by_month = month.is_some();
+    ui.radio_value(&mut by_month, true, "Month");
+    ui.radio_value(&mut by_month, false, "Year");
+
+    if by_month {
+        let mut selected = month.unwrap_or(1);
+        ComboBox::from_id_salt((id_salt, "month"))
+            .selected_text(MONTHS[(selected - 1) as usize])
+            .show_ui(ui, |ui| {
+                for (i, name) in MONTHS.iter().enumerate() {
+                    ui.selectable_value(&mut selected, i as u32 + 1, *name);
+                }
+            });
+        month = Some(selected);
+    } else {
+        month = None;
+    }
+
+    ui.add(DragValue::new(&mut year).range(1970..=2100));
+
+    *period = match month {
+        Some(month) => ReportPeriod::Month { year, month },
+        None => ReportPeriod::Year(year),
+    };
+}
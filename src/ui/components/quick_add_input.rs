@@ -0,0 +1,222 @@
+use eframe::egui::{text::LayoutJob, *};
+
+use crate::{
+    AppEvent, BudgetData,
+    amount::Locale,
+    models::{cost_item::CostCycle, cost_item::ExpenceCategory, income_item::IncomeCategory},
+    quick_add::{self, Kind, QuickAdd, TokenKind},
+};
+
+const GREEN: Color32 = Color32::from_rgb(0x2C, 0xA0, 0x2C);
+const RED: Color32 = Color32::from_rgb(0xD6, 0x27, 0x28);
+
+/// The quick add line for expenses or income: the text field with its words
+/// colored by what they were read as, suggestions for the `@category`,
+/// `\cycle` or `#tag` being typed, and what the line adds or what is wrong
+/// with it.
+pub struct QuickAddInput {
+    kind: Kind,
+    input: String,
+}
+
+impl QuickAddInput {
+    pub fn new(kind: Kind) -> Self {
+        Self {
+            kind,
+            input: String::new(),
+        }
+    }
+
+    /// `budget_data` supplies the tags to suggest; amounts are read as
+    /// `locale` writes them.
+    pub fn show(&mut self, ui: &mut Ui, budget_data: &BudgetData, locale: Locale) -> Vec<AppEvent> {
+        let mut events = Vec::new();
+        let kind = self.kind;
+        let (label, hint) = match kind {
+            Kind::Expense => (
+                "Expense:",
+                "e.g. 'Coffee 5 \\daily @dining #work' or 'Rent 1200 @housing'",
+            ),
+            Kind::Income => ("Income:", "e.g. 'Salary 3200 \\monthly @salary'"),
+        };
+
+        ui.horizontal(|ui| {
+            ui.add_space(10.0);
+            ui.label(label);
+
+            let mut layouter = |ui: &Ui, text: &dyn TextBuffer, wrap_width: f32| {
+                let mut job = highlighted(ui, text.as_str(), kind, locale);
+                job.wrap.max_width = wrap_width;
+                ui.fonts(|fonts| fonts.layout_job(job))
+            };
+            let response = ui.add_sized(
+                [350.0, 20.0],
+                TextEdit::singleline(&mut self.input)
+                    .hint_text(hint)
+                    .layouter(&mut layouter),
+            );
+
+            let mut line = quick_add::parse(&self.input, kind, locale);
+            line.match_tags(&budget_data.tags());
+            let event = line.event();
+            let add_clicked = ui.add_enabled(event.is_ok(), Button::new("Add")).clicked();
+            let enter_pressed = response.lost_focus() && ui.input(|i| i.key_pressed(Key::Enter));
+            if add_clicked || enter_pressed {
+                if let Ok(event) = event {
+                    events.push(AppEvent::Budget(event));
+                    self.input.clear();
+                }
+                // Stay in the field to add the next one, or to fix this one
+                if enter_pressed {
+                    response.request_focus();
+                }
+            }
+
+            ui.add_space(15.0);
+        });
+
+        self.show_suggestions(ui, budget_data, locale);
+
+        if !self.input.trim().is_empty() {
+            let mut line = quick_add::parse(&self.input, kind, locale);
+            line.match_tags(&budget_data.tags());
+            ui.horizontal(|ui| {
+                ui.add_space(10.0);
+                show_outcome(ui, &line);
+            });
+        }
+
+        events
+    }
+
+    /// Names for the marked word being typed at the end of the line, which
+    /// replace it when clicked.
+    fn show_suggestions(&mut self, ui: &mut Ui, budget_data: &BudgetData, locale: Locale) {
+        let last_word = match self.input.ends_with(char::is_whitespace) {
+            true => String::new(),
+            false => self
+                .input
+                .split_whitespace()
+                .last()
+                .unwrap_or("")
+                .to_string(),
+        };
+        let Some(marker) = last_word.chars().next() else {
+            return;
+        };
+        let kept = self.input.len() - last_word.len();
+        let (title, names) = match marker {
+            '@' => ("Categories:", self.category_names()),
+            '\\' => ("Cycles:", cycle_names()),
+            '#' => (
+                "Tags:",
+                self.unused_tags(budget_data, &self.input[..kept], locale),
+            ),
+            _ => return,
+        };
+        let typed = last_word[1..].to_lowercase();
+        let matching: Vec<String> = names
+            .into_iter()
+            .filter(|name| name.to_lowercase().starts_with(&typed))
+            .collect();
+        if matching.is_empty() {
+            return;
+        }
+
+        ui.add_space(2.0);
+        ui.horizontal(|ui| {
+            ui.add_space(140.0); // Align with text field
+
+            Frame::popup(ui.style()).show(ui, |ui| {
+                ui.set_max_width(200.0);
+                ui.label(title);
+
+                for name in matching {
+                    if ui
+                        .selectable_label(false, format!("{}{}", marker, name))
+                        .clicked()
+                    {
+                        self.input.truncate(kept);
+                        self.input.push_str(&format!("{}{} ", marker, name));
+                    }
+                }
+            });
+        });
+    }
+
+    /// Tags on existing items, except those already `before` the word
+    /// being typed.
+    fn unused_tags(&self, budget_data: &BudgetData, before: &str, locale: Locale) -> Vec<String> {
+        let used = quick_add::parse(before, self.kind, locale).tags;
+        budget_data
+            .tags()
+            .into_iter()
+            .filter(|tag| !used.iter().any(|t| t.eq_ignore_ascii_case(tag)))
+            .collect()
+    }
+
+    fn category_names(&self) -> Vec<String> {
+        let labels: Vec<&str> = match self.kind {
+            Kind::Expense => ExpenceCategory::ALL.iter().map(|c| c.label()).collect(),
+            Kind::Income => IncomeCategory::ALL.iter().map(|c| c.label()).collect(),
+        };
+        labels
+            .into_iter()
+            .map(|label| label.replace(' ', "").to_lowercase())
+            .collect()
+    }
+}
+
+fn cycle_names() -> Vec<String> {
+    CostCycle::ALL
+        .iter()
+        .map(|cycle| cycle.label().to_lowercase())
+        .collect()
+}
+
+/// What adding the line would do, or why it can't be added yet.
+fn show_outcome(ui: &mut Ui, line: &QuickAdd) {
+    if line.errors.is_empty() {
+        ui.colored_label(GREEN, format!("→ {}", line.preview()));
+        return;
+    }
+    let messages: Vec<&str> = line.errors.iter().map(|e| e.message.as_str()).collect();
+    ui.colored_label(RED, messages.join(", "));
+}
+
+/// The line with each word colored by what it was read as, and words with
+/// errors underlined in red.
+fn highlighted(ui: &Ui, text: &str, kind: Kind, locale: Locale) -> LayoutJob {
+    let line = quick_add::parse(text, kind, locale);
+    let visuals = ui.visuals();
+    let plain = TextFormat::simple(TextStyle::Body.resolve(ui.style()), visuals.text_color());
+
+    let mut job = LayoutJob::default();
+    let mut at = 0;
+    for token in &line.tokens {
+        job.append(&text[at..token.span.start], 0.0, plain.clone());
+        let mut format = TextFormat {
+            color: match token.kind {
+                TokenKind::Name => visuals.text_color(),
+                TokenKind::Amount => GREEN,
+                TokenKind::Cycle | TokenKind::Category | TokenKind::Tag | TokenKind::Date => {
+                    visuals.hyperlink_color
+                }
+                TokenKind::Note => visuals.weak_text_color(),
+            },
+            ..plain.clone()
+        };
+        let wrong = line
+            .errors
+            .iter()
+            .any(|error| error.span.start < token.span.end && token.span.start < error.span.end);
+        if wrong {
+            format.color = RED;
+            format.underline = Stroke::new(1.0, RED);
+        }
+        job.append(&text[token.span.clone()], 0.0, format);
+        at = token.span.end;
+    }
+    job.append(&text[at..], 0.0, plain);
+    job
+}
@@ -0,0 +1,45 @@
+use eframe::egui::*;
+
+use crate::export::text_table::TableFormat;
+
+pub const COPY_TSV: KeyboardShortcut =
+    KeyboardShortcut::new(Modifiers::COMMAND.plus(Modifiers::SHIFT), Key::T);
+pub const COPY_MARKDOWN: KeyboardShortcut =
+    KeyboardShortcut::new(Modifiers::COMMAND.plus(Modifiers::SHIFT), Key::M);
+
+/// "Copy as TSV / Markdown" entries for a table's context menu.
+/// `render` builds the table text for the chosen format.
+pub fn copy_menu(ui: &mut Ui, render: impl Fn(TableFormat) -> String) {
+    for (format, shortcut) in [
+        (TableFormat::Tsv, COPY_TSV),
+        (TableFormat::Markdown, COPY_MARKDOWN),
+    ] {
+        let button = Button::new(format!("Copy as {}", format.label()))
+            .shortcut_text(ui.ctx().format_shortcut(&shortcut));
+        if ui.add(button).clicked() {
+            ui.ctx().copy_text(render(format));
+            ui.close();
+        }
+    }
+}
+
+/// Keyboard variant of `copy_menu`. Only the table under the pointer reacts,
+/// so several tables on one page don't all copy at once.
+pub fn copy_shortcuts(ui: &mut Ui, rect: Rect, render: impl Fn(TableFormat) -> String) {
+    if !ui.rect_contains_pointer(rect) {
+        return;
+    }
+
+    let format = ui.input_mut(|input| {
+        if input.consume_shortcut(&COPY_TSV) {
+            Some(TableFormat::Tsv)
+        } else if input.consume_shortcut(&COPY_MARKDOWN) {
+            Some(TableFormat::Markdown)
+        } else {
+            None
+        }
+    });
+    if let Some(format) = format {
+        ui.ctx().copy_text(render(format));
+    }
+}
@@ -1,2 +1,3 @@
 pub mod components;
+pub mod ui_state;
 pub mod windows;
\ No newline at end of file
@@ -0,0 +1,32 @@
+//! How the window looked when the app was last closed. Saved with eframe's
+//! storage, which also keeps the window geometry and egui's memory, and with
+//! it the column widths and the side menu's width.
+
+use serde::{Deserialize, Serialize};
+
+use crate::{AppView, report::ReportPeriod, ui::components::cost_item_table::TableSort};
+
+#[derive(Serialize, Deserialize)]
+#[serde(default)]
+pub struct UiState {
+    pub view: AppView,
+    pub show_menu: bool,
+    pub cost_items_sort: TableSort,
+    pub yearly_sort: TableSort,
+    pub monthly_sort: TableSort,
+    /// Period picked for reports; this month until one is picked.
+    pub report_period: Option<ReportPeriod>,
+}
+
+impl Default for UiState {
+    fn default() -> Self {
+        Self {
+            view: AppView::Home,
+            show_menu: true,
+            cost_items_sort: TableSort::default(),
+            yearly_sort: TableSort::default(),
+            monthly_sort: TableSort::default(),
+            report_period: None,
+        }
+    }
+}
@@ -0,0 +1,224 @@
+use std::path::PathBuf;
+
+use eframe::egui::*;
+
+use crate::{
+    AppEvent, BudgetEvent, RustedBudgetAppState, models::template::BudgetTemplate,
+    storage::BackendKind,
+};
+
+pub struct BudgetsWindow {
+    /// Name of the open budget being edited.
+    name: String,
+    /// Budget the name draft was last reset from.
+    synced_from: Option<PathBuf>,
+    new_name: String,
+    new_template: BudgetTemplate,
+    new_backend: BackendKind,
+    new_path: String,
+    /// Stop suggesting a path from the name once the user edits it.
+    path_edited: bool,
+    open_backend: BackendKind,
+    open_path: String,
+}
+
+/// File name for a new budget, derived from its name.
+fn suggested_path(kind: BackendKind, name: &str) -> PathBuf {
+    let stem: String = name
+        .trim()
+        .to_lowercase()
+        .chars()
+        .map(|c| if c.is_alphanumeric() { c } else { '_' })
+        .collect();
+    let default = kind.default_path();
+    if stem.is_empty() {
+        return default;
+    }
+    let extension = default
+        .extension()
+        .map(|ext| ext.to_string_lossy().into_owned())
+        .unwrap_or_default();
+    default.with_file_name(format!("{stem}.{extension}"))
+}
+
+impl BudgetsWindow {
+    pub fn new() -> Self {
+        Self {
+            name: String::new(),
+            synced_from: None,
+            new_name: String::new(),
+            new_template: BudgetTemplate::Empty,
+            new_backend: BackendKind::File,
+            new_path: String::new(),
+            path_edited: false,
+            open_backend: BackendKind::File,
+            open_path: String::new(),
+        }
+    }
+
+    pub fn show(&mut self, ui: &mut Ui, app_state: &RustedBudgetAppState) -> Vec<AppEvent> {
+        let mut events = Vec::new();
+        let settings = &app_state.settings;
+        let current_path = settings.storage_path().to_path_buf();
+
+        if self.synced_from.as_ref() != Some(&current_path) {
+            self.name = app_state.budget_data.settings.name.clone();
+            self.synced_from = Some(current_path.clone());
+        }
+
+        ui.group(|ui| {
+            ui.label(RichText::new("Current budget").strong());
+            ui.label(format!(
+                "{}: {}",
+                settings.storage.label(),
+                current_path.display()
+            ));
+            ui.horizontal(|ui| {
+                ui.label("Name:");
+                ui.add_sized(
+                    [260.0, 20.0],
+                    TextEdit::singleline(&mut self.name).hint_text("e.g. Household"),
+                );
+                let changed = self.name.trim() != app_state.budget_data.settings.name;
+                if ui.add_enabled(changed, Button::new("Rename")).clicked() {
+                    events.push(AppEvent::Budget(BudgetEvent::RenameBudget(
+                        self.name.trim().to_string(),
+                    )));
+                }
+            });
+        });
+
+        ui.add_space(10.0);
+        ui.group(|ui| {
+            ui.label(RichText::new("Recent budgets").strong());
+            if settings.recent.is_empty() {
+                ui.label("No budgets opened yet.");
+            }
+            Grid::new("recent_budgets").striped(true).show(ui, |ui| {
+                for recent in &settings.recent {
+                    let is_current = recent.kind == settings.storage && recent.path == current_path;
+                    ui.label(RichText::new(recent.label()).strong());
+                    ui.label(recent.kind.label());
+                    ui.label(recent.path.display().to_string());
+                    if ui.add_enabled(!is_current, Button::new("Open")).clicked() {
+                        events.push(AppEvent::SwitchStorage {
+                            kind: recent.kind,
+                            path: recent.path.clone(),
+                        });
+                    }
+                    if ui
+                        .add_enabled(!is_current, Button::new("Forget"))
+                        .on_hover_text("Remove from this list; the budget itself is kept")
+                        .clicked()
+                    {
+                        events.push(AppEvent::ForgetRecent {
+                            kind: recent.kind,
+                            path: recent.path.clone(),
+                        });
+                    }
+                    ui.end_row();
+                }
+            });
+
+            ui.separator();
+            ui.horizontal(|ui| {
+                ui.label("Open:");
+                for kind in BackendKind::available() {
+                    ui.radio_value(&mut self.open_backend, kind, kind.label());
+                }
+            });
+            ui.horizontal(|ui| {
+                ui.add_sized(
+                    [420.0, 20.0],
+                    TextEdit::singleline(&mut self.open_path).hint_text("/path/to/budget"),
+                );
+                if ui
+                    .add_enabled(!self.open_path.trim().is_empty(), Button::new("Open"))
+                    .clicked()
+                {
+                    events.push(AppEvent::SwitchStorage {
+                        kind: self.open_backend,
+                        path: PathBuf::from(self.open_path.trim()),
+                    });
+                    self.open_path.clear();
+                }
+            });
+        });
+
+        ui.add_space(10.0);
+        ui.group(|ui| {
+            ui.label(RichText::new("New budget").strong());
+            Grid::new("new_budget").show(ui, |ui| {
+                ui.label("Name:");
+                let name = ui.add_sized(
+                    [260.0, 20.0],
+                    TextEdit::singleline(&mut self.new_name).hint_text("e.g. Rental property"),
+                );
+                ui.end_row();
+
+                ui.label("Start from:");
+                ComboBox::from_id_salt("new_budget_template")
+                    .selected_text(self.new_template.label())
+                    .show_ui(ui, |ui| {
+                        for template in BudgetTemplate::ALL {
+                            ui.selectable_value(&mut self.new_template, template, template.label());
+                        }
+                    });
+                ui.end_row();
+
+                ui.label("Backend:");
+                let mut backend_changed = false;
+                ui.horizontal(|ui| {
+                    for kind in BackendKind::available() {
+                        backend_changed |= ui
+                            .radio_value(&mut self.new_backend, kind, kind.label())
+                            .changed();
+                    }
+                });
+                ui.end_row();
+
+                if !self.path_edited
+                    && (name.changed() || backend_changed || self.new_path.is_empty())
+                {
+                    self.new_path = suggested_path(self.new_backend, &self.new_name)
+                        .display()
+                        .to_string();
+                }
+
+                ui.label("File:");
+                if ui
+                    .add_sized([420.0, 20.0], TextEdit::singleline(&mut self.new_path))
+                    .changed()
+                {
+                    self.path_edited = true;
+                }
+                ui.end_row();
+            });
+
+            let ready = !self.new_name.trim().is_empty() && !self.new_path.trim().is_empty();
+            if ui.add_enabled(ready, Button::new("Create")).clicked() {
+                events.push(AppEvent::NewBudget {
+                    name: self.new_name.trim().to_string(),
+                    template: self.new_template,
+                    kind: self.new_backend,
+                    path: PathBuf::from(self.new_path.trim()),
+                });
+                self.new_name.clear();
+                self.new_path.clear();
+                self.path_edited = false;
+            }
+        });
+
+        match &app_state.storage_status {
+            Some(Ok(message)) => {
+                ui.colored_label(Color32::from_rgb(0x2C, 0xA0, 0x2C), message);
+            }
+            Some(Err(err)) => {
+                ui.colored_label(Color32::from_rgb(0xD6, 0x27, 0x28), err);
+            }
+            None => {}
+        }
+
+        events
+    }
+}
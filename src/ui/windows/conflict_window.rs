@@ -0,0 +1,79 @@
+use eframe::egui::{self, *};
+
+use crate::{AppEvent, storage::merge::Merge};
+
+/// Asks which version to keep for items changed both here and on disk.
+pub struct ConflictWindow {
+    /// One entry per conflict; true takes the version on disk.
+    take_theirs: Vec<bool>,
+}
+
+impl ConflictWindow {
+    pub fn new() -> Self {
+        Self {
+            take_theirs: Vec::new(),
+        }
+    }
+
+    pub fn show(&mut self, ctx: &egui::Context, merge: &Merge) -> Vec<AppEvent> {
+        let mut events = Vec::new();
+        self.take_theirs.resize(merge.conflicts.len(), false);
+
+        Modal::new(Id::new("merge_conflicts")).show(ctx, |ui| {
+            ui.heading("Budget changed on disk");
+            ui.label(
+                "The stored budget was changed elsewhere while you had unsaved edits. \
+                 Changes to different items were merged; pick a version for these:",
+            );
+            ui.add_space(6.0);
+
+            ScrollArea::vertical().max_height(400.0).show(ui, |ui| {
+                Grid::new("merge_conflicts_grid")
+                    .striped(true)
+                    .spacing([12.0, 6.0])
+                    .show(ui, |ui| {
+                        ui.label(RichText::new("Item").strong());
+                        ui.label(RichText::new("Here").strong());
+                        ui.label(RichText::new("On disk").strong());
+                        ui.end_row();
+
+                        for (conflict, theirs) in merge.conflicts.iter().zip(&mut self.take_theirs)
+                        {
+                            ui.label(conflict.key.kind());
+                            let deleted = || "(deleted)".to_string();
+                            ui.radio_value(
+                                theirs,
+                                false,
+                                conflict.mine.clone().unwrap_or_else(deleted),
+                            );
+                            ui.radio_value(
+                                theirs,
+                                true,
+                                conflict.theirs.clone().unwrap_or_else(deleted),
+                            );
+                            ui.end_row();
+                        }
+                    });
+            });
+
+            ui.separator();
+            ui.horizontal(|ui| {
+                if ui.button("Keep all mine").clicked() {
+                    self.take_theirs.fill(false);
+                }
+                if ui.button("Take all from disk").clicked() {
+                    self.take_theirs.fill(true);
+                }
+                ui.with_layout(Layout::right_to_left(Align::Center), |ui| {
+                    if ui.button("Apply").clicked() {
+                        events.push(AppEvent::ResolveConflicts(std::mem::take(
+                            &mut self.take_theirs,
+                        )));
+                    }
+                });
+            });
+        });
+
+        events
+    }
+}
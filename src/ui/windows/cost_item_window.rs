@@ -4,27 +4,22 @@ use eframe::egui::*;
 
 use crate::BudgetData;
 use crate::{
-    AppEvent,
+    AppEvent, BudgetEvent,
+    amount::Locale,
     models::cost_item::{CostCycle, CostItem, ExpenceCategory},
-    ui::components::{cost_item_table::CostItemTable, quick_add_expense::QuickAddExpense},
+    quick_add::Kind,
+    ui::{
+        components::{
+            amount_field::amount_field,
+            cost_item_table::{CostItemTable, SortColumn, SortOrder, TableSort},
+            quick_add_input::QuickAddInput,
+        },
+        ui_state::UiState,
+    },
 };
 
-#[derive(Clone, Copy, PartialEq)]
-pub enum SortColumn {
-    What,
-    Cost,
-    Category,
-    Cycle,
-}
-
-#[derive(Clone, Copy, PartialEq)]
-pub enum SortOrder {
-    Ascending,
-    Descending,
-}
-
 pub struct CostItemWindow {
-    quick_add: QuickAddExpense,
+    quick_add: QuickAddInput,
     yearly_table: CostItemTable,
     monthly_table: CostItemTable,
     tag_inputs: HashMap<usize, String>,
@@ -35,7 +30,7 @@ pub struct CostItemWindow {
 impl CostItemWindow {
     pub fn new() -> Self {
         Self {
-            quick_add: QuickAddExpense::new(),
+            quick_add: QuickAddInput::new(Kind::Expense),
             yearly_table: CostItemTable::new(),
             monthly_table: CostItemTable::new(),
             tag_inputs: HashMap::new(),
@@ -44,7 +39,23 @@ impl CostItemWindow {
         }
     }
 
-    pub fn show(&mut self, ui: &mut Ui, budget_data: &BudgetData) -> Vec<AppEvent> {
+    pub fn restore(&mut self, ui_state: &UiState) {
+        self.sort_column = ui_state.cost_items_sort.column;
+        self.sort_order = ui_state.cost_items_sort.order;
+        self.yearly_table.set_sort(ui_state.yearly_sort);
+        self.monthly_table.set_sort(ui_state.monthly_sort);
+    }
+
+    pub fn store(&self, ui_state: &mut UiState) {
+        ui_state.cost_items_sort = TableSort {
+            column: self.sort_column,
+            order: self.sort_order,
+        };
+        ui_state.yearly_sort = self.yearly_table.sort();
+        ui_state.monthly_sort = self.monthly_table.sort();
+    }
+
+    pub fn show(&mut self, ui: &mut Ui, budget_data: &BudgetData, locale: Locale) -> Vec<AppEvent> {
         let mut events = Vec::new();
 
         ui.vertical(|ui|{
@@ -55,7 +66,7 @@ impl CostItemWindow {
                 ui.add_space(10.0);
 
                 if ui.button("Add Daily Cost").clicked() {
-                    events.push(AppEvent::AddCostItem(
+                    events.push(AppEvent::Budget(BudgetEvent::AddCostItem(
                         CostItem {
                             id: 0,
                             what: "New Item".to_string(),
@@ -63,12 +74,12 @@ impl CostItemWindow {
                             cost_cycle: CostCycle::Daily,
                             cost_category: ExpenceCategory::Other,
                             tags: None,
-                        })
+                        }))
                     );
                 }
 
                 if ui.button("Add Weekly Cost").clicked() {
-                    events.push(AppEvent::AddCostItem(
+                    events.push(AppEvent::Budget(BudgetEvent::AddCostItem(
                         CostItem {
                             id: 0,
                             what: "New Item".to_string(),
@@ -76,12 +87,12 @@ impl CostItemWindow {
                             cost_cycle: CostCycle::Weekly,
                             cost_category: ExpenceCategory::Other,
                             tags: None,
-                        })
+                        }))
                     );
                 }
 
                 if ui.button("Add Monthly Cost").clicked() {
-                    events.push(AppEvent::AddCostItem(
+                    events.push(AppEvent::Budget(BudgetEvent::AddCostItem(
                         CostItem {
                             id: 0,
                             what: "New Item".to_string(),
@@ -89,12 +100,12 @@ impl CostItemWindow {
                             cost_cycle: CostCycle::Monthly,
                             cost_category: ExpenceCategory::Other,
                             tags: None,
-                        })
+                        }))
                     );
                 }
 
                 if ui.button("Add Yearly Cost").clicked() {
-                    events.push(AppEvent::AddCostItem(
+                    events.push(AppEvent::Budget(BudgetEvent::AddCostItem(
                         CostItem {
                             id: 0,
                             what: "New Item".to_string(),
@@ -102,7 +113,7 @@ impl CostItemWindow {
                             cost_cycle: CostCycle::Yearly,
                             cost_category: ExpenceCategory::Other,
                             tags: None,
-                        })
+                        }))
                     );
                 }
             });
@@ -114,7 +125,7 @@ impl CostItemWindow {
                 ui.label(RichText::new("Quick Add").strong());
                 ui.separator();
 
-                let mut quick_add_events = self.quick_add.show(ui);
+                let mut quick_add_events = self.quick_add.show(ui, budget_data, locale);
                 events.append(&mut quick_add_events);
             });
 
@@ -240,7 +251,7 @@ impl CostItemWindow {
                                                 // Delete button
                                                 row.col(|ui| {
                                                     if ui.small_button("🗑").on_hover_text("Delete item").clicked() {
-                                                        events.push(AppEvent::DeleteCostItem(item_id));
+                                                        events.push(AppEvent::Budget(BudgetEvent::DeleteCostItem(item_id)));
                                                     }
                                                 });
 
@@ -251,24 +262,24 @@ impl CostItemWindow {
                                                     if response.changed() {
                                                         let mut updated_item = item.clone();
                                                         updated_item.what = temp_what;
-                                                        events.push(AppEvent::UpdateCostItem {
+                                                        events.push(AppEvent::Budget(BudgetEvent::UpdateCostItem {
                                                             id: item_id,
                                                             item: updated_item
-                                                        });
+                                                        }));
                                                     }
                                                 });
 
                                                 // Editable Cost field
                                                 row.col(|ui| {
                                                     let mut temp_cost = item.cost;
-                                                    let response = ui.add(DragValue::new(&mut temp_cost).prefix("$").speed(0.1));
+                                                    let response = ui.add(amount_field(&mut temp_cost, locale));
                                                     if response.changed() {
                                                         let mut updated_item = item.clone();
                                                         updated_item.cost = temp_cost;
-                                                        events.push(AppEvent::UpdateCostItem {
+                                                        events.push(AppEvent::Budget(BudgetEvent::UpdateCostItem {
                                                             id: item_id,
                                                             item: updated_item
-                                                        });
+                                                        }));
                                                     }
                                                 });
 
@@ -312,10 +323,10 @@ impl CostItemWindow {
                                                             if changed {
                                                                 let mut updated_item = item.clone();
                                                                 updated_item.cost_category = temp_category;
-                                                                events.push(AppEvent::UpdateCostItem {
+                                                                events.push(AppEvent::Budget(BudgetEvent::UpdateCostItem {
                                                                     id: item_id,
                                                                     item: updated_item
-                                                                });
+                                                                }));
                                                             }
                                                         });
                                                 });
@@ -342,10 +353,10 @@ impl CostItemWindow {
                                                             if changed {
                                                                 let mut updated_item = item.clone();
                                                                 updated_item.cost_cycle = temp_cycle;
-                                                                events.push(AppEvent::UpdateCostItem {
+                                                                events.push(AppEvent::Budget(BudgetEvent::UpdateCostItem {
                                                                     id: item_id,
                                                                     item: updated_item
-                                                                });
+                                                                }));
                                                             }
                                                         });
                                                 });
@@ -375,10 +386,10 @@ impl CostItemWindow {
                                                                                             updated_item.tags = None;
                                                                                         }
                                                                                     }
-                                                                                    events.push(AppEvent::UpdateCostItem {
+                                                                                    events.push(AppEvent::Budget(BudgetEvent::UpdateCostItem {
                                                                                         id: item_id,
                                                                                         item: updated_item
-                                                                                    });
+                                                                                    }));
                                                                                 }
                                                                             });
                                                                         }
@@ -428,10 +439,10 @@ impl CostItemWindow {
                                                                     let tags = updated_item.tags.get_or_insert_with(Vec::new);
                                                                     if !tags.iter().any(|t| t == &tag) {
                                                                         tags.push(tag);
-                                                                        events.push(AppEvent::UpdateCostItem {
+                                                                        events.push(AppEvent::Budget(BudgetEvent::UpdateCostItem {
                                                                             id: item_id,
                                                                             item: updated_item
-                                                                        });
+                                                                        }));
                                                                         // Clear the input in the HashMap after successful add
                                                                         self.tag_inputs.insert(item_id as usize, String::new());
                                                                     }
@@ -487,13 +498,13 @@ impl CostItemWindow {
 
             ui.vertical(|ui| {
                 ui.heading("Yearly Items");
-                let mut yearly_events = self.yearly_table.show(ui, &yearly_items, true, "yearly_table".to_string());
+                let mut yearly_events = self.yearly_table.show(ui, &yearly_items, true, "yearly_table".to_string(), locale);
                 events.append(&mut yearly_events);
 
                 ui.add_space(20.0);
 
                 ui.heading("Monthly Items");
-                let mut monthly_events = self.monthly_table.show(ui, &monthly_items, true, "monthly_table".to_string());
+                let mut monthly_events = self.monthly_table.show(ui, &monthly_items, true, "monthly_table".to_string(), locale);
                 events.append(&mut monthly_events);
             });
         });
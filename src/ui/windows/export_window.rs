@@ -0,0 +1,238 @@
+use std::path::Path;
+
+use eframe::egui::*;
+
+use crate::{
+    AppEvent, BudgetData,
+    amount::Locale,
+    export::{
+        csv::{self, CsvOptions},
+        journal::{self, AccountMapping, JournalFormat},
+    },
+    report::{ReportPeriod, html, pdf},
+    ui::{
+        components::{
+            csv_options::csv_options_ui,
+            file_io::{save_file, save_files},
+            period_picker::{current_month, period_picker},
+        },
+        ui_state::UiState,
+    },
+};
+
+pub struct ExportWindow {
+    csv_dir: String,
+    csv_options: CsvOptions,
+    journal_path: String,
+    journal_format: JournalFormat,
+    report_path: String,
+    report_period: ReportPeriod,
+    status: Option<Result<String, String>>,
+}
+
+impl ExportWindow {
+    pub fn new() -> Self {
+        Self {
+            csv_dir: String::new(),
+            csv_options: CsvOptions::default(),
+            journal_path: String::new(),
+            journal_format: JournalFormat::Ledger,
+            report_path: String::new(),
+            report_period: current_month(),
+            status: None,
+        }
+    }
+
+    pub fn restore(&mut self, ui_state: &UiState) {
+        if let Some(period) = ui_state.report_period {
+            self.report_period = period;
+        }
+    }
+
+    pub fn store(&self, ui_state: &mut UiState) {
+        ui_state.report_period = Some(self.report_period);
+    }
+
+    pub fn show(
+        &mut self,
+        ui: &mut Ui,
+        budget_data: &BudgetData,
+        locale: Locale,
+        accounts: &AccountMapping,
+    ) -> Vec<AppEvent> {
+        let mut events = Vec::new();
+
+        ui.group(|ui| {
+            ui.label(RichText::new("CSV").strong());
+            ui.label(
+                "Writes cost_items.csv, income_items.csv and transactions.csv into the folder.",
+            );
+            ui.separator();
+
+            ui.horizontal(|ui| {
+                ui.label("Folder:");
+                ui.add_sized(
+                    [350.0, 20.0],
+                    TextEdit::singleline(&mut self.csv_dir).hint_text("/path/to/export"),
+                );
+                csv_options_ui(ui, "export_csv", &mut self.csv_options);
+
+                let can_export = !self.csv_dir.trim().is_empty();
+                if ui.add_enabled(can_export, Button::new("Export")).clicked() {
+                    let dir = Path::new(self.csv_dir.trim());
+                    self.status = Some(
+                        csv::render_all(budget_data, &self.csv_options)
+                            .and_then(|files| save_files(dir, files)),
+                    );
+                }
+            });
+        });
+
+        ui.add_space(10.0);
+        self.show_journal_export(ui, budget_data, accounts, &mut events);
+
+        ui.add_space(10.0);
+        self.show_report_export(ui, budget_data, locale);
+
+        self.show_status(ui);
+
+        events
+    }
+
+    /// Edits to the account mapping are saved in the settings, so the CLI
+    /// and journal imports book the same accounts.
+    fn show_journal_export(
+        &mut self,
+        ui: &mut Ui,
+        budget_data: &BudgetData,
+        accounts: &AccountMapping,
+        events: &mut Vec<AppEvent>,
+    ) {
+        ui.group(|ui| {
+            ui.label(RichText::new("Plain-text accounting").strong());
+            ui.label(format!(
+                "Writes the {} recorded transactions as a journal.",
+                budget_data.transactions.len()
+            ));
+            ui.separator();
+
+            ui.horizontal(|ui| {
+                ui.label("Format:");
+                ComboBox::from_id_salt("journal_format")
+                    .selected_text(self.journal_format.label())
+                    .show_ui(ui, |ui| {
+                        for format in JournalFormat::ALL {
+                            ui.selectable_value(&mut self.journal_format, format, format.label());
+                        }
+                    });
+
+                ui.label("File:");
+                ui.add_sized(
+                    [350.0, 20.0],
+                    TextEdit::singleline(&mut self.journal_path).hint_text(format!(
+                        "/path/to/budget.{}",
+                        self.journal_format.extension()
+                    )),
+                );
+
+                let can_export = !self.journal_path.trim().is_empty();
+                if ui.add_enabled(can_export, Button::new("Export")).clicked() {
+                    let path = Path::new(self.journal_path.trim());
+                    let journal =
+                        journal::render(&budget_data.transactions, self.journal_format, accounts);
+                    self.status = Some(save_file(path, journal.as_bytes()));
+                }
+            });
+
+            CollapsingHeader::new("Account mapping")
+                .id_salt("journal_mapping")
+                .show(ui, |ui| {
+                    ui.label("Also used by the command line and by journal imports.");
+                    let mut mapping = accounts.clone();
+                    Grid::new("journal_mapping_grid")
+                        .num_columns(2)
+                        .striped(true)
+                        .show(ui, |ui| {
+                            ui.label("Asset account");
+                            ui.text_edit_singleline(&mut mapping.asset_account);
+                            ui.end_row();
+
+                            ui.label("Commodity");
+                            ui.text_edit_singleline(&mut mapping.commodity);
+                            ui.end_row();
+
+                            for (category, account) in &mut mapping.expense_accounts {
+                                ui.label(category.label());
+                                ui.text_edit_singleline(account);
+                                ui.end_row();
+                            }
+
+                            for (category, account) in &mut mapping.income_accounts {
+                                ui.label(format!("{} (income)", category.label()));
+                                ui.text_edit_singleline(account);
+                                ui.end_row();
+                            }
+                        });
+
+                    if ui.button("Reset to defaults").clicked() {
+                        mapping = AccountMapping::default();
+                    }
+                    if mapping != *accounts {
+                        events.push(AppEvent::SetAccounts(mapping));
+                    }
+                });
+        });
+    }
+
+    fn show_report_export(&mut self, ui: &mut Ui, budget_data: &BudgetData, locale: Locale) {
+        ui.group(|ui| {
+            ui.label(RichText::new("Report").strong());
+            ui.label("Totals, category breakdown, cost items and charts for a month or a year, as HTML or printable PDF.");
+            ui.separator();
+
+            ui.horizontal(|ui| {
+                ui.label("Period:");
+                period_picker(ui, "report_period", &mut self.report_period);
+            });
+
+            ui.horizontal(|ui| {
+                ui.label("File:");
+                ui.add_sized(
+                    [350.0, 20.0],
+                    TextEdit::singleline(&mut self.report_path)
+                        .hint_text("/path/to/report.html or .pdf"),
+                );
+
+                let can_export = !self.report_path.trim().is_empty();
+                if ui
+                    .add_enabled(can_export, Button::new("Export HTML"))
+                    .clicked()
+                {
+                    let path = Path::new(self.report_path.trim());
+                    let report = html::render(budget_data, self.report_period, locale);
+                    self.status = Some(save_file(path, report.as_bytes()));
+                }
+                if ui
+                    .add_enabled(can_export, Button::new("Export PDF"))
+                    .clicked()
+                {
+                    let path = Path::new(self.report_path.trim());
+                    let report = pdf::render(budget_data, self.report_period, locale);
+                    self.status = Some(save_file(path, &report));
+                }
+            });
+        });
+    }
+
+    fn show_status(&self, ui: &mut Ui) {
+        match &self.status {
+            Some(Ok(message)) => {
+                ui.colored_label(Color32::from_rgb(0x2C, 0xA0, 0x2C), message);
+            }
+            Some(Err(err)) => {
+                ui.colored_label(Color32::from_rgb(0xD6, 0x27, 0x28), err);
+            }
+            None => {}
+        }
+    }
+}
@@ -0,0 +1,261 @@
+use std::path::PathBuf;
+
+use eframe::egui::*;
+use egui_extras::{Column, TableBuilder};
+
+use crate::{
+    AppEvent, BudgetEvent, RustedBudgetAppState,
+    storage::{
+        history::{self, HistoryEntry, Item, ItemChange, Recorded},
+        merge::ItemKey,
+    },
+};
+
+/// Who changed what and when, newest first, with single-change reverts.
+pub struct HistoryWindow {
+    entries: Vec<HistoryEntry>,
+    /// History version and file the entries were read at.
+    loaded: Option<(u64, PathBuf)>,
+    error: Option<String>,
+    /// Show only changes to this item.
+    filter: Option<ItemKey>,
+    search: String,
+}
+
+/// One row: an item change, or an entry that touched no items.
+struct Row<'a> {
+    entry: &'a HistoryEntry,
+    change: Option<&'a ItemChange>,
+}
+
+fn item_label(change: &ItemChange) -> String {
+    let name = change
+        .after
+        .as_ref()
+        .or(change.before.as_ref())
+        .map(Item::name)
+        .unwrap_or_default();
+    format!("{} {}", change.key.kind(), name)
+}
+
+fn describe_change(change: &ItemChange) -> String {
+    match (&change.before, &change.after) {
+        (None, Some(after)) => format!("Added: {}", after.describe()),
+        (Some(before), None) => format!("Deleted: {}", before.describe()),
+        (Some(before), Some(after)) => {
+            let mut text = format!("{} → {}", before.describe(), after.describe());
+            if before.name() != after.name() {
+                text = format!("\"{}\" → \"{}\", {}", before.name(), after.name(), text);
+            }
+            text
+        }
+        (None, None) => String::new(),
+    }
+}
+
+fn describe_entry(entry: &HistoryEntry) -> String {
+    match &entry.change {
+        Recorded::Snapshot { reason, .. } => reason.clone(),
+        Recorded::Event(BudgetEvent::RenameBudget(name)) => {
+            format!("Renamed the budget to \"{}\"", name)
+        }
+        Recorded::Event(BudgetEvent::CommitImport(entries)) => {
+            format!("Imported {} entries", entries.len())
+        }
+        _ => String::new(),
+    }
+}
+
+impl HistoryWindow {
+    pub fn new() -> Self {
+        Self {
+            entries: Vec::new(),
+            loaded: None,
+            error: None,
+            filter: None,
+            search: String::new(),
+        }
+    }
+
+    /// Read the history again after it grew or the budget changed.
+    fn refresh(&mut self, app_state: &RustedBudgetAppState) {
+        let path = history::path_for(app_state.settings.storage_path());
+        let current = (app_state.history_version, path);
+        if self.loaded.as_ref() == Some(&current) {
+            return;
+        }
+        match history::read(&current.1, app_state.key.as_ref()) {
+            Ok(entries) => {
+                self.entries = entries;
+                self.error = None;
+            }
+            Err(err) => self.error = Some(err),
+        }
+        self.loaded = Some(current);
+    }
+
+    pub fn show(&mut self, ui: &mut Ui, app_state: &RustedBudgetAppState) -> Vec<AppEvent> {
+        let mut events = Vec::new();
+        self.refresh(app_state);
+        let budget_data = &app_state.budget_data;
+
+        ui.horizontal(|ui| {
+            ui.label("Item:");
+            let selected = self
+                .filter
+                .and_then(|key| budget_data.item(key))
+                .map(|item| item.name().to_string())
+                .unwrap_or_else(|| "All items".to_string());
+            ComboBox::from_id_salt("history_item")
+                .selected_text(selected)
+                .show_ui(ui, |ui| {
+                    ui.selectable_value(&mut self.filter, None, "All items");
+                    let mut cost_items: Vec<_> = budget_data.cost_items.values().collect();
+                    cost_items.sort_by(|a, b| a.what.cmp(&b.what));
+                    for item in cost_items {
+                        ui.selectable_value(
+                            &mut self.filter,
+                            Some(ItemKey::CostItem(item.id)),
+                            &item.what,
+                        );
+                    }
+                    ui.separator();
+                    for item in &budget_data.income_items {
+                        ui.selectable_value(
+                            &mut self.filter,
+                            Some(ItemKey::IncomeItem(item.id)),
+                            &item.source,
+                        );
+                    }
+                });
+            ui.add(TextEdit::singleline(&mut self.search).hint_text("Search"));
+
+            ui.with_layout(Layout::right_to_left(Align::Center), |ui| {
+                if ui
+                    .button("Rebuild from history")
+                    .on_hover_text("Replace the budget with the one the recorded changes produce")
+                    .clicked()
+                {
+                    events.push(AppEvent::RebuildFromHistory);
+                }
+            });
+        });
+
+        if let Some(err) = &self.error {
+            ui.colored_label(Color32::from_rgb(0xD6, 0x27, 0x28), err);
+        }
+        match &app_state.storage_status {
+            Some(Ok(message)) => {
+                ui.colored_label(Color32::from_rgb(0x2C, 0xA0, 0x2C), message);
+            }
+            Some(Err(err)) => {
+                ui.colored_label(Color32::from_rgb(0xD6, 0x27, 0x28), err);
+            }
+            None => {}
+        }
+        ui.add_space(6.0);
+
+        let search = self.search.trim().to_lowercase();
+        let rows: Vec<Row> = self
+            .entries
+            .iter()
+            .rev()
+            .flat_map(|entry| {
+                if entry.items.is_empty() {
+                    vec![Row {
+                        entry,
+                        change: None,
+                    }]
+                } else {
+                    entry
+                        .items
+                        .iter()
+                        .map(|change| Row {
+                            entry,
+                            change: Some(change),
+                        })
+                        .collect()
+                }
+            })
+            .filter(|row| match (self.filter, row.change) {
+                (Some(key), Some(change)) => change.key == key,
+                (Some(_), None) => false,
+                (None, _) => true,
+            })
+            .filter(|row| {
+                search.is_empty()
+                    || row
+                        .change
+                        .map(item_label)
+                        .unwrap_or_default()
+                        .to_lowercase()
+                        .contains(&search)
+                    || describe_entry(row.entry).to_lowercase().contains(&search)
+            })
+            .collect();
+
+        if rows.is_empty() {
+            ui.label("No changes recorded yet.");
+            return events;
+        }
+
+        TableBuilder::new(ui)
+            .striped(true)
+            .resizable(true)
+            .cell_layout(Layout::left_to_right(Align::Center))
+            .column(Column::auto().at_least(130.0)) // When
+            .column(Column::auto().at_least(120.0)) // Who
+            .column(Column::auto().at_least(160.0)) // Item
+            .column(Column::remainder().at_least(240.0)) // Change
+            .column(Column::auto().at_least(60.0)) // Revert
+            .header(25.0, |mut header| {
+                for title in ["When", "Who", "Item", "Change", ""] {
+                    header.col(|ui| {
+                        ui.strong(title);
+                    });
+                }
+            })
+            .body(|body| {
+                body.rows(22.0, rows.len(), |mut table_row| {
+                    let row = &rows[table_row.index()];
+                    table_row.col(|ui| {
+                        ui.label(row.entry.time.format("%Y-%m-%d %H:%M").to_string());
+                    });
+                    table_row.col(|ui| {
+                        ui.label(&row.entry.who);
+                    });
+                    table_row.col(|ui| {
+                        ui.label(row.change.map(item_label).unwrap_or_default());
+                    });
+                    table_row.col(|ui| {
+                        let text = row
+                            .change
+                            .map(describe_change)
+                            .unwrap_or_else(|| describe_entry(row.entry));
+                        ui.label(text);
+                    });
+                    table_row.col(|ui| {
+                        let Some(change) = row.change else {
+                            return;
+                        };
+                        let Some(revert) = change.revert_event() else {
+                            return;
+                        };
+                        // Only while the item is still as this change left it
+                        let unchanged = budget_data.item(change.key) == change.after;
+                        let button = ui.add_enabled(unchanged, Button::new("Revert"));
+                        let button = if unchanged {
+                            button.on_hover_text("Undo just this change")
+                        } else {
+                            button.on_disabled_hover_text("The item has changed again since")
+                        };
+                        if button.clicked() {
+                            events.push(AppEvent::Budget(revert));
+                        }
+                    });
+                });
+            });
+
+        events
+    }
+}
@@ -0,0 +1,574 @@
+use eframe::egui::*;
+use egui_extras::{Column, TableBuilder};
+
+use crate::{
+    AppEvent, BudgetData, BudgetEvent,
+    amount::Locale,
+    export::{csv::CsvOptions, journal::AccountMapping},
+    import::{
+        ImportBatch, ImportEntry, RowDecision, csv, journal, pasted,
+        spreadsheet::{self, ColumnMapping, Workbook},
+    },
+    models::{
+        cost_item::ExpenceCategory, income_item::IncomeCategory, transaction::TransactionCategory,
+    },
+    ui::components::{csv_options::csv_options_ui, file_io::open_file},
+};
+
+pub struct ImportWindow {
+    paste_input: String,
+    positive_is_expense: bool,
+    csv_path: String,
+    csv_options: CsvOptions,
+    journal_path: String,
+    spreadsheet_path: String,
+    workbook: Option<Workbook>,
+    sheet_index: usize,
+    column_mapping: ColumnMapping,
+    parse_errors: Vec<String>,
+    batch: Option<ImportBatch>,
+    bulk_category: TransactionCategory,
+    bulk_tag: String,
+}
+
+impl ImportWindow {
+    pub fn new() -> Self {
+        Self {
+            paste_input: String::new(),
+            positive_is_expense: false,
+            csv_path: String::new(),
+            csv_options: CsvOptions::default(),
+            journal_path: String::new(),
+            spreadsheet_path: String::new(),
+            workbook: None,
+            sheet_index: 0,
+            column_mapping: ColumnMapping::default(),
+            parse_errors: Vec::new(),
+            batch: None,
+            bulk_category: TransactionCategory::Expense(ExpenceCategory::Other),
+            bulk_tag: String::new(),
+        }
+    }
+
+    pub fn show(
+        &mut self,
+        ui: &mut Ui,
+        budget_data: &BudgetData,
+        locale: Locale,
+        accounts: &AccountMapping,
+    ) -> Vec<AppEvent> {
+        let mut events = Vec::new();
+
+        if self.batch.is_none() {
+            self.show_sources(ui, budget_data, locale, accounts);
+        } else {
+            self.show_review(ui, &mut events);
+        }
+
+        events
+    }
+
+    fn show_sources(
+        &mut self,
+        ui: &mut Ui,
+        budget_data: &BudgetData,
+        locale: Locale,
+        accounts: &AccountMapping,
+    ) {
+        ui.label(format!(
+            "{} transactions recorded so far.",
+            budget_data.transactions.len()
+        ));
+        ui.add_space(10.0);
+
+        ui.group(|ui| {
+            ui.label(RichText::new("Paste rows").strong());
+            ui.label(
+                "One row per line: date, description and amount separated by tabs, ';' or ','.",
+            );
+            ui.separator();
+
+            ui.add_sized(
+                [ui.available_width(), 160.0],
+                TextEdit::multiline(&mut self.paste_input)
+                    .hint_text("2024-03-01;Rema 1000;-312.50\n2024-03-02;Salary March;32000")
+                    .font(TextStyle::Monospace),
+            );
+
+            ui.horizontal(|ui| {
+                ui.checkbox(
+                    &mut self.positive_is_expense,
+                    "Positive amounts are expenses",
+                );
+
+                if ui.button("Stage rows").clicked() {
+                    let (transactions, errors) =
+                        pasted::parse_rows(&self.paste_input, self.positive_is_expense, locale);
+                    self.parse_errors = errors;
+                    if !transactions.is_empty() {
+                        self.batch = Some(ImportBatch::new(
+                            "Pasted rows".to_string(),
+                            transactions
+                                .into_iter()
+                                .map(ImportEntry::Transaction)
+                                .collect(),
+                            budget_data,
+                        ));
+                        self.paste_input.clear();
+                    }
+                }
+            });
+        });
+
+        ui.add_space(10.0);
+
+        ui.group(|ui| {
+            ui.label(RichText::new("Cost items from CSV").strong());
+            ui.label("Columns are matched by header: what, cost, cycle, category, tags.");
+            ui.separator();
+
+            ui.horizontal(|ui| {
+                csv_options_ui(ui, "import_csv", &mut self.csv_options);
+            });
+            ui.horizontal(|ui| {
+                let picked = open_file(
+                    ui,
+                    "import_csv",
+                    &mut self.csv_path,
+                    "/path/to/cost_items.csv",
+                    ".csv",
+                    "Stage file",
+                );
+                let Some((name, contents)) = picked else {
+                    return;
+                };
+                match contents
+                    .and_then(|data| csv::read_cost_items(&data[..], &self.csv_options, locale))
+                {
+                    Ok((items, errors)) => {
+                        self.parse_errors = errors;
+                        if !items.is_empty() {
+                            self.batch = Some(ImportBatch::new(
+                                name,
+                                items.into_iter().map(ImportEntry::CostItem).collect(),
+                                budget_data,
+                            ));
+                        }
+                    }
+                    Err(err) => self.parse_errors = vec![err],
+                }
+            });
+        });
+
+        ui.add_space(10.0);
+
+        ui.group(|ui| {
+            ui.label(RichText::new("Plain-text accounting journal").strong());
+            ui.label(
+                "Ledger, hledger or beancount. Expenses:* and Income:* postings become transactions.",
+            );
+            ui.separator();
+
+            ui.horizontal(|ui| {
+                let picked = open_file(
+                    ui,
+                    "import_journal",
+                    &mut self.journal_path,
+                    "/path/to/books.journal",
+                    ".journal,.ledger,.hledger,.beancount,.txt",
+                    "Stage file",
+                );
+                let Some((name, contents)) = picked else {
+                    return;
+                };
+                let text = contents.and_then(|data| {
+                    String::from_utf8(data).map_err(|e| format!("{}: {}", name, e))
+                });
+                match text {
+                    Ok(text) => {
+                        let (transactions, errors) =
+                            journal::parse(&text, accounts);
+                        self.parse_errors = errors;
+                        if !transactions.is_empty() {
+                            self.batch = Some(ImportBatch::new(
+                                name,
+                                transactions
+                                    .into_iter()
+                                    .map(ImportEntry::Transaction)
+                                    .collect(),
+                                budget_data,
+                            ));
+                        }
+                    }
+                    Err(err) => self.parse_errors = vec![err],
+                }
+            });
+        });
+
+        ui.add_space(10.0);
+        self.show_spreadsheet_source(ui, budget_data, locale);
+
+        for error in &self.parse_errors {
+            ui.colored_label(Color32::from_rgb(0xD6, 0x27, 0x28), error);
+        }
+    }
+
+    fn show_spreadsheet_source(&mut self, ui: &mut Ui, budget_data: &BudgetData, locale: Locale) {
+        ui.group(|ui| {
+            ui.label(RichText::new("Spreadsheet (XLSX / ODS)").strong());
+            ui.label("Pick a sheet and map its columns to cost item fields.");
+            ui.separator();
+
+            ui.horizontal(|ui| {
+                let picked = open_file(
+                    ui,
+                    "import_spreadsheet",
+                    &mut self.spreadsheet_path,
+                    "/path/to/budget.xlsx",
+                    ".xlsx,.xlsm,.xls,.ods",
+                    "Open",
+                );
+                if let Some((name, contents)) = picked {
+                    match contents.and_then(|data| spreadsheet::from_bytes(&name, data)) {
+                        Ok(workbook) => {
+                            self.sheet_index = 0;
+                            self.column_mapping = workbook
+                                .sheets
+                                .first()
+                                .map(|sheet| ColumnMapping::guess(sheet, locale))
+                                .unwrap_or_default();
+                            self.workbook = Some(workbook);
+                            self.parse_errors.clear();
+                        }
+                        Err(err) => {
+                            self.workbook = None;
+                            self.parse_errors = vec![err];
+                        }
+                    }
+                }
+            });
+
+            let Some(workbook) = &self.workbook else {
+                return;
+            };
+            if workbook.sheets.is_empty() {
+                ui.label(RichText::new("The workbook has no sheets").italics());
+                return;
+            }
+
+            ui.horizontal(|ui| {
+                ui.label("Sheet:");
+                let previous = self.sheet_index;
+                ComboBox::from_id_salt("spreadsheet_sheet")
+                    .selected_text(&workbook.sheets[self.sheet_index].name)
+                    .show_ui(ui, |ui| {
+                        for (i, sheet) in workbook.sheets.iter().enumerate() {
+                            ui.selectable_value(&mut self.sheet_index, i, &sheet.name);
+                        }
+                    });
+                if previous != self.sheet_index {
+                    self.column_mapping =
+                        ColumnMapping::guess(&workbook.sheets[self.sheet_index], locale);
+                }
+
+                ui.checkbox(&mut self.column_mapping.header_row, "First row is a header");
+            });
+
+            let sheet = &workbook.sheets[self.sheet_index];
+            let headers: Vec<String> = (0..sheet.column_count())
+                .map(|col| {
+                    let header = sheet
+                        .rows
+                        .first()
+                        .filter(|_| self.column_mapping.header_row)
+                        .and_then(|row| row.get(col))
+                        .cloned()
+                        .unwrap_or_default();
+                    format!("{}: {}", spreadsheet::column_label(col), header)
+                })
+                .collect();
+
+            ui.horizontal(|ui| {
+                column_combo(ui, "Name", &mut self.column_mapping.name, &headers);
+                column_combo(ui, "Amount", &mut self.column_mapping.amount, &headers);
+                column_combo(ui, "Cycle", &mut self.column_mapping.cycle, &headers);
+                column_combo(ui, "Category", &mut self.column_mapping.category, &headers);
+            });
+
+            // Preview of the first rows
+            Grid::new("spreadsheet_preview")
+                .striped(true)
+                .show(ui, |ui| {
+                    for row in sheet.rows.iter().take(6) {
+                        for cell in row {
+                            ui.label(cell);
+                        }
+                        ui.end_row();
+                    }
+                });
+
+            if ui
+                .add_enabled(self.column_mapping.is_complete(), Button::new("Stage rows"))
+                .clicked()
+            {
+                let (items, errors) =
+                    spreadsheet::to_cost_items(sheet, &self.column_mapping, locale);
+                self.parse_errors = errors;
+                if !items.is_empty() {
+                    self.batch = Some(ImportBatch::new(
+                        format!("{} ({})", self.spreadsheet_path.trim(), sheet.name),
+                        items.into_iter().map(ImportEntry::CostItem).collect(),
+                        budget_data,
+                    ));
+                }
+            }
+        });
+    }
+
+    fn show_review(&mut self, ui: &mut Ui, events: &mut Vec<AppEvent>) {
+        let Some(batch) = self.batch.as_mut() else {
+            return;
+        };
+
+        ui.horizontal(|ui| {
+            ui.heading(format!("Review: {}", batch.source));
+            ui.label(format!(
+                "{} rows, {} to import, {} possible duplicates",
+                batch.rows.len(),
+                batch.accepted_count(),
+                batch.duplicate_count()
+            ));
+        });
+        ui.add_space(5.0);
+
+        // Bulk actions
+        ui.horizontal(|ui| {
+            if ui.button("Select all").clicked() {
+                batch.select_all(true);
+            }
+            if ui.button("Select none").clicked() {
+                batch.select_all(false);
+            }
+            if ui.button("Select duplicates").clicked() {
+                batch.select_duplicates();
+            }
+
+            ui.separator();
+            ui.label(format!("{} selected:", batch.selected_count()));
+
+            if ui.button("Accept").clicked() {
+                batch.set_decision_for_selected(RowDecision::Accept);
+            }
+            if ui.button("Skip").clicked() {
+                batch.set_decision_for_selected(RowDecision::Skip);
+            }
+
+            ui.separator();
+            category_combo(ui, "bulk_category", &mut self.bulk_category, true);
+            if ui.button("Re-categorize").clicked() {
+                batch.recategorize_selected(&self.bulk_category);
+            }
+
+            ui.separator();
+            ui.add_sized(
+                [100.0, 20.0],
+                TextEdit::singleline(&mut self.bulk_tag).hint_text("tag"),
+            );
+            if ui.button("Tag").clicked() {
+                let tag = self
+                    .bulk_tag
+                    .trim()
+                    .replace(char::is_whitespace, "_")
+                    .to_lowercase();
+                if !tag.is_empty() {
+                    batch.tag_selected(&tag);
+                    self.bulk_tag.clear();
+                }
+            }
+        });
+
+        ui.add_space(5.0);
+
+        let available_height = ui.available_height() - 40.0;
+        ui.allocate_ui(vec2(ui.available_width(), available_height), |ui| {
+            TableBuilder::new(ui)
+                .striped(true)
+                .resizable(true)
+                .cell_layout(Layout::left_to_right(Align::Center))
+                .column(Column::auto().at_least(30.0)) // Select
+                .column(Column::auto().at_least(70.0)) // Decision
+                .column(Column::auto().at_least(90.0)) // Date / cycle
+                .column(Column::auto().at_least(200.0).at_most(320.0)) // Description
+                .column(Column::auto().at_least(80.0)) // Amount
+                .column(Column::auto().at_least(150.0)) // Category
+                .column(Column::auto().at_least(100.0)) // Tags
+                .column(Column::remainder()) // Duplicate
+                .header(25.0, |mut header| {
+                    header.col(|_| {});
+                    header.col(|ui| {
+                        ui.strong("Status");
+                    });
+                    header.col(|ui| {
+                        ui.strong("Date / Cycle");
+                    });
+                    header.col(|ui| {
+                        ui.strong("Description");
+                    });
+                    header.col(|ui| {
+                        ui.strong("Amount");
+                    });
+                    header.col(|ui| {
+                        ui.strong("Category");
+                    });
+                    header.col(|ui| {
+                        ui.strong("Tags");
+                    });
+                    header.col(|ui| {
+                        ui.strong("Duplicate of");
+                    });
+                })
+                .body(|mut body| {
+                    for (i, row) in batch.rows.iter_mut().enumerate() {
+                        body.row(24.0, |mut table_row| {
+                            table_row.col(|ui| {
+                                ui.checkbox(&mut row.selected, "");
+                            });
+                            table_row.col(|ui| {
+                                let (text, color) = match row.decision {
+                                    RowDecision::Accept => {
+                                        ("Import", Color32::from_rgb(0x2C, 0xA0, 0x2C))
+                                    }
+                                    RowDecision::Skip => ("Skip", Color32::from_gray(140)),
+                                };
+                                if ui
+                                    .button(RichText::new(text).color(color))
+                                    .on_hover_text("Toggle import / skip")
+                                    .clicked()
+                                {
+                                    row.decision = match row.decision {
+                                        RowDecision::Accept => RowDecision::Skip,
+                                        RowDecision::Skip => RowDecision::Accept,
+                                    };
+                                }
+                            });
+                            table_row.col(|ui| {
+                                ui.label(row.entry.when());
+                            });
+                            table_row.col(|ui| {
+                                ui.label(row.entry.description());
+                            });
+                            table_row.col(|ui| {
+                                ui.label(format!("{:+.2}", row.entry.signed_amount()));
+                            });
+                            table_row.col(|ui| {
+                                let allow_income = row.entry.accepts_income_category();
+                                let mut category = row.entry.category();
+                                if category_combo(
+                                    ui,
+                                    ("row_category", i),
+                                    &mut category,
+                                    allow_income,
+                                ) {
+                                    row.entry.set_category(&category);
+                                }
+                            });
+                            table_row.col(|ui| {
+                                if let Some(tags) = row.entry.tags() {
+                                    ui.label(
+                                        tags.iter()
+                                            .map(|t| format!("#{}", t))
+                                            .collect::<Vec<_>>()
+                                            .join(" "),
+                                    );
+                                }
+                            });
+                            table_row.col(|ui| {
+                                if let Some(duplicate) = &row.duplicate_of {
+                                    ui.colored_label(
+                                        Color32::from_rgb(0xE6, 0x7E, 0x22),
+                                        duplicate,
+                                    );
+                                }
+                            });
+                        });
+                    }
+                });
+        });
+
+        ui.separator();
+        let accepted = batch.accepted_count();
+        let mut commit = false;
+        let mut discard = false;
+
+        ui.horizontal(|ui| {
+            if ui
+                .add_enabled(
+                    accepted > 0,
+                    Button::new(format!("Commit {} rows", accepted)),
+                )
+                .clicked()
+            {
+                commit = true;
+            }
+            if ui.button("Discard import").clicked() {
+                discard = true;
+            }
+        });
+
+        if commit && let Some(batch) = self.batch.take() {
+            events.push(AppEvent::Budget(BudgetEvent::CommitImport(
+                batch.into_accepted(),
+            )));
+        } else if discard {
+            self.batch = None;
+        }
+    }
+}
+
+fn column_combo(ui: &mut Ui, label: &str, column: &mut Option<usize>, headers: &[String]) {
+    ui.label(format!("{}:", label));
+    let selected = column
+        .and_then(|col| headers.get(col))
+        .cloned()
+        .unwrap_or_else(|| "(none)".to_string());
+    ComboBox::from_id_salt(("spreadsheet_column", label))
+        .selected_text(selected)
+        .show_ui(ui, |ui| {
+            ui.selectable_value(column, None, "(none)");
+            for (i, header) in headers.iter().enumerate() {
+                ui.selectable_value(column, Some(i), header);
+            }
+        });
+}
+
+/// Returns true when the user picked a different category.
+fn category_combo(
+    ui: &mut Ui,
+    id_salt: impl std::hash::Hash,
+    category: &mut TransactionCategory,
+    allow_income: bool,
+) -> bool {
+    let mut changed = false;
+    ComboBox::from_id_salt(id_salt)
+        .selected_text(category.label())
+        .show_ui(ui, |ui| {
+            ui.label(RichText::new("Expenses").strong());
+            for expense in ExpenceCategory::ALL {
+                let label = expense.label();
+                changed |= ui
+                    .selectable_value(category, TransactionCategory::Expense(expense), label)
+                    .changed();
+            }
+            if allow_income {
+                ui.separator();
+                ui.label(RichText::new("Income").strong());
+                for income in IncomeCategory::ALL {
+                    let label = income.label();
+                    changed |= ui
+                        .selectable_value(category, TransactionCategory::Income(income), label)
+                        .changed();
+                }
+            }
+        });
+    changed
+}
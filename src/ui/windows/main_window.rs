@@ -2,14 +2,24 @@ use eframe::egui::{self, *};
 
 use crate::{
     AppEvent, AppView, RustedBudgetAppState,
-    ui::windows::{cost_item_window::CostItemWindow, home_window::HomeWindow},
+    settings::RecentBudget,
+    ui::ui_state::UiState,
+    ui::windows::{
+        budgets_window::BudgetsWindow, cost_item_window::CostItemWindow,
+        export_window::ExportWindow, history_window::HistoryWindow, home_window::HomeWindow,
+        import_window::ImportWindow, settings_window::SettingsWindow,
+    },
 };
 
 pub struct MainWindow {
     show_menu: bool,
     home_window: HomeWindow,
     cost_item_window: CostItemWindow,
-    // Future: settings_window: SettingsWindow,
+    import_window: ImportWindow,
+    export_window: ExportWindow,
+    settings_window: SettingsWindow,
+    budgets_window: BudgetsWindow,
+    history_window: HistoryWindow,
 }
 
 impl MainWindow {
@@ -18,9 +28,27 @@ impl MainWindow {
             show_menu: true,
             home_window: HomeWindow::new(),
             cost_item_window: CostItemWindow::new(),
+            import_window: ImportWindow::new(),
+            export_window: ExportWindow::new(),
+            settings_window: SettingsWindow::new(),
+            budgets_window: BudgetsWindow::new(),
+            history_window: HistoryWindow::new(),
         }
     }
 
+    /// Bring back the layout of the last session.
+    pub fn restore(&mut self, ui_state: &UiState) {
+        self.show_menu = ui_state.show_menu;
+        self.cost_item_window.restore(ui_state);
+        self.export_window.restore(ui_state);
+    }
+
+    pub fn store(&self, ui_state: &mut UiState) {
+        ui_state.show_menu = self.show_menu;
+        self.cost_item_window.store(ui_state);
+        self.export_window.store(ui_state);
+    }
+
     pub fn show(&mut self, ctx: &egui::Context, app_state: &RustedBudgetAppState) -> Vec<AppEvent> {
         let mut events = Vec::new();
 
@@ -28,10 +56,12 @@ impl MainWindow {
         TopBottomPanel::top("top_bar").show(ctx, |ui| {
             ui.add_space(2.0);
             ui.horizontal(|ui| {
-                if ui.button(if self.show_menu { "☰" } else { "☰" }).clicked() {
+                if ui.button("☰").clicked() {
                     self.show_menu = !self.show_menu;
                     events.push(AppEvent::ToggleMenu);
                 }
+                ui.separator();
+                self.undo_buttons(ui, app_state, &mut events);
             });
             ui.add_space(2.0);
         });
@@ -44,6 +74,7 @@ impl MainWindow {
             .show_animated(ctx, self.show_menu, |ui| {
                 ui.vertical(|ui| {
                     ui.heading("Budget Tracker");
+                    self.budget_switcher(ui, app_state, &mut events);
                     ui.separator();
 
                     ui.add_space(10.0);
@@ -65,6 +96,27 @@ impl MainWindow {
                         events.push(AppEvent::ChangeView(AppView::CostItems));
                     }
 
+                    if ui
+                        .selectable_label(app_state.current_view == AppView::Import, "📥 Import")
+                        .clicked()
+                    {
+                        events.push(AppEvent::ChangeView(AppView::Import));
+                    }
+
+                    if ui
+                        .selectable_label(app_state.current_view == AppView::Export, "📤 Export")
+                        .clicked()
+                    {
+                        events.push(AppEvent::ChangeView(AppView::Export));
+                    }
+
+                    if ui
+                        .selectable_label(app_state.current_view == AppView::History, "🕘 History")
+                        .clicked()
+                    {
+                        events.push(AppEvent::ChangeView(AppView::History));
+                    }
+
                     ui.add_space(ui.available_height() - 60.0);
 
                     ui.separator();
@@ -85,7 +137,11 @@ impl MainWindow {
                 let title = match app_state.current_view {
                     AppView::Home => "🏠 Dashboard",
                     AppView::CostItems => "CostItems",
+                    AppView::Import => "📥 Import",
+                    AppView::Export => "📤 Export",
                     AppView::Settings => "Settings",
+                    AppView::Budgets => "📚 Budgets",
+                    AppView::History => "🕘 History",
                 };
                 ui.heading(title);
 
@@ -117,23 +173,147 @@ impl MainWindow {
             // Content area - delegate to appropriate view
             match app_state.current_view {
                 AppView::Home => {
-                    let mut home_events = self.home_window.show(ui, &app_state.budget_data);
+                    let mut home_events = self.home_window.show(
+                        ui,
+                        &app_state.budget_data,
+                        app_state.settings.locale,
+                    );
                     events.append(&mut home_events);
                 }
                 AppView::CostItems => {
-                    let mut cost_item_events =
-                        self.cost_item_window.show(ui, &app_state.budget_data);
+                    let mut cost_item_events = self.cost_item_window.show(
+                        ui,
+                        &app_state.budget_data,
+                        app_state.settings.locale,
+                    );
                     events.append(&mut cost_item_events);
                 }
+                AppView::Import => {
+                    let mut import_events = self.import_window.show(
+                        ui,
+                        &app_state.budget_data,
+                        app_state.settings.locale,
+                        &app_state.settings.accounts,
+                    );
+                    events.append(&mut import_events);
+                }
+                AppView::Export => {
+                    let mut export_events = self.export_window.show(
+                        ui,
+                        &app_state.budget_data,
+                        app_state.settings.locale,
+                        &app_state.settings.accounts,
+                    );
+                    events.append(&mut export_events);
+                }
                 AppView::Settings => {
-                    // Future: self.settings_window.show(ui, &mut app_state);
-                    ui.centered_and_justified(|ui| {
-                        ui.label("Settings view coming soon...");
-                    });
+                    let mut settings_events = self.settings_window.show(ui, app_state);
+                    events.append(&mut settings_events);
+                }
+                AppView::Budgets => {
+                    let mut budgets_events = self.budgets_window.show(ui, app_state);
+                    events.append(&mut budgets_events);
+                }
+                AppView::History => {
+                    let mut history_events = self.history_window.show(ui, app_state);
+                    events.append(&mut history_events);
                 }
             }
         });
 
+        self.undo_toast(ctx, app_state, &mut events);
+
         events
     }
+
+    fn undo_buttons(
+        &mut self,
+        ui: &mut Ui,
+        app_state: &RustedBudgetAppState,
+        events: &mut Vec<AppEvent>,
+    ) {
+        let undo = app_state.undo.undo_label();
+        let button = ui.add_enabled(undo.is_some(), Button::new("↶ Undo"));
+        if button
+            .on_hover_text(format!("Undo {} (Ctrl+Z)", undo.unwrap_or_default()))
+            .clicked()
+        {
+            events.push(AppEvent::Undo);
+        }
+
+        let redo = app_state.undo.redo_label();
+        let button = ui.add_enabled(redo.is_some(), Button::new("↷ Redo"));
+        if button
+            .on_hover_text(format!("Redo {} (Ctrl+Shift+Z)", redo.unwrap_or_default()))
+            .clicked()
+        {
+            events.push(AppEvent::Redo);
+        }
+    }
+
+    /// Offer to undo a delete for a few seconds after it.
+    fn undo_toast(
+        &mut self,
+        ctx: &egui::Context,
+        app_state: &RustedBudgetAppState,
+        events: &mut Vec<AppEvent>,
+    ) {
+        let Some((name, left)) = app_state.undo.toast() else {
+            return;
+        };
+        ctx.request_repaint_after(left);
+
+        Area::new(Id::new("undo_toast"))
+            .anchor(Align2::CENTER_BOTTOM, [0.0, -24.0])
+            .order(Order::Foreground)
+            .show(ctx, |ui| {
+                Frame::popup(ui.style()).show(ui, |ui| {
+                    ui.horizontal(|ui| {
+                        ui.label(format!("Deleted {}", name));
+                        if ui.button("Undo").clicked() {
+                            events.push(AppEvent::Undo);
+                        }
+                    });
+                });
+            });
+    }
+
+    /// Pick another recent budget, or go manage them.
+    fn budget_switcher(
+        &mut self,
+        ui: &mut Ui,
+        app_state: &RustedBudgetAppState,
+        events: &mut Vec<AppEvent>,
+    ) {
+        let settings = &app_state.settings;
+        let current = RecentBudget {
+            kind: settings.storage,
+            path: settings.storage_path().to_path_buf(),
+            name: app_state.budget_data.settings.name.clone(),
+        };
+
+        ComboBox::from_id_salt("budget_switcher")
+            .width(ui.available_width())
+            .selected_text(current.label())
+            .show_ui(ui, |ui| {
+                for recent in &settings.recent {
+                    let is_current = recent.kind == current.kind && recent.path == current.path;
+                    if ui
+                        .selectable_label(is_current, recent.label())
+                        .on_hover_text(recent.path.display().to_string())
+                        .clicked()
+                        && !is_current
+                    {
+                        events.push(AppEvent::SwitchStorage {
+                            kind: recent.kind,
+                            path: recent.path.clone(),
+                        });
+                    }
+                }
+                ui.separator();
+                if ui.selectable_label(false, "📚 Manage budgets…").clicked() {
+                    events.push(AppEvent::ChangeView(AppView::Budgets));
+                }
+            });
+    }
 }
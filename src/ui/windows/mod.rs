@@ -1,5 +1,14 @@
+mod budgets_window;
+mod conflict_window;
 mod cost_item_window;
+mod export_window;
+mod history_window;
 mod home_window;
+mod import_window;
 mod main_window;
+mod settings_window;
+mod unlock_window;
 
+pub use conflict_window::ConflictWindow;
 pub use main_window::MainWindow;
+pub use unlock_window::UnlockWindow;
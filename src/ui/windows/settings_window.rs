@@ -0,0 +1,494 @@
+use std::path::PathBuf;
+
+use eframe::egui::*;
+
+use crate::{
+    AppEvent, RustedBudgetAppState,
+    amount::{self, Locale},
+    storage::{BackendKind, crypto::Passphrase},
+};
+#[cfg(feature = "sqlite")]
+use crate::{
+    report::ReportPeriod,
+    storage::sqlite::{self, PeriodTotals},
+    ui::components::period_picker::{current_month, period_picker},
+};
+
+pub struct SettingsWindow {
+    /// Backend and path being edited; applied with Open or Move.
+    backend: BackendKind,
+    path: String,
+    /// Settings the draft was last reset from.
+    synced_from: Option<(BackendKind, PathBuf)>,
+    current_passphrase: String,
+    new_passphrase: String,
+    confirm_passphrase: String,
+    #[cfg(not(target_arch = "wasm32"))]
+    sync_folder: String,
+    /// Asked for when an encrypted budget starts syncing.
+    #[cfg(not(target_arch = "wasm32"))]
+    sync_passphrase: String,
+    /// Port being typed or dragged; applied once the field is left.
+    #[cfg(not(target_arch = "wasm32"))]
+    api_port: Option<u16>,
+    #[cfg(feature = "sqlite")]
+    totals_period: ReportPeriod,
+    #[cfg(feature = "sqlite")]
+    totals: Option<Result<PeriodTotals, String>>,
+}
+
+impl SettingsWindow {
+    pub fn new() -> Self {
+        Self {
+            backend: BackendKind::File,
+            path: String::new(),
+            synced_from: None,
+            current_passphrase: String::new(),
+            new_passphrase: String::new(),
+            confirm_passphrase: String::new(),
+            #[cfg(not(target_arch = "wasm32"))]
+            sync_folder: String::new(),
+            #[cfg(not(target_arch = "wasm32"))]
+            sync_passphrase: String::new(),
+            #[cfg(not(target_arch = "wasm32"))]
+            api_port: None,
+            #[cfg(feature = "sqlite")]
+            totals_period: current_month(),
+            #[cfg(feature = "sqlite")]
+            totals: None,
+        }
+    }
+
+    pub fn show(&mut self, ui: &mut Ui, app_state: &RustedBudgetAppState) -> Vec<AppEvent> {
+        let mut events = Vec::new();
+        let settings = &app_state.settings;
+
+        // Start the draft over whenever the active storage changes
+        let current = (settings.storage, settings.storage_path().to_path_buf());
+        if self.synced_from.as_ref() != Some(&current) {
+            self.backend = current.0;
+            self.path = current.1.display().to_string();
+            self.synced_from = Some(current);
+        }
+
+        ui.group(|ui| {
+            ui.label(RichText::new("Storage").strong());
+            ui.horizontal(|ui| {
+                ui.label(format!(
+                    "{}: {}",
+                    settings.storage.label(),
+                    settings.storage_path().display()
+                ));
+                if app_state.unsaved_since.is_some() {
+                    ui.label(RichText::new("(unsaved changes)").italics());
+                }
+            });
+            ui.horizontal(|ui| {
+                if ui.button("Save now").clicked() {
+                    events.push(AppEvent::SaveData);
+                }
+                if ui
+                    .button("Reload")
+                    .on_hover_text("Discard unsaved changes and read the budget again")
+                    .clicked()
+                {
+                    events.push(AppEvent::LoadData);
+                }
+            });
+            ui.separator();
+
+            ui.horizontal(|ui| {
+                ui.label("Backend:");
+                for kind in BackendKind::available() {
+                    if ui.radio(self.backend == kind, kind.label()).clicked() && self.backend != kind
+                    {
+                        self.backend = kind;
+                        self.path = settings.path_for(kind).display().to_string();
+                    }
+                }
+            });
+            ui.horizontal(|ui| {
+                ui.label("File:");
+                ui.add_sized(
+                    [420.0, 20.0],
+                    TextEdit::singleline(&mut self.path).hint_text("/path/to/budget"),
+                );
+            });
+
+            let path = PathBuf::from(self.path.trim());
+            let changed = self.backend != settings.storage || path != settings.storage_path();
+            ui.horizontal(|ui| {
+                let enabled = changed && !self.path.trim().is_empty();
+                if ui
+                    .add_enabled(enabled, Button::new("Open"))
+                    .on_hover_text("Use the budget stored there instead of the current one")
+                    .clicked()
+                {
+                    events.push(AppEvent::SwitchStorage {
+                        kind: self.backend,
+                        path: path.clone(),
+                    });
+                }
+                let encrypted = app_state.key.is_some() && self.backend != BackendKind::File;
+                if ui
+                    .add_enabled(enabled && !encrypted, Button::new("Move budget here"))
+                    .on_hover_text(
+                        "Copy the current budget there, replacing what it holds, and use it from now on",
+                    )
+                    .on_disabled_hover_text(if encrypted {
+                        "Only a file can hold an encrypted budget; remove the passphrase first"
+                    } else {
+                        "Pick another place first"
+                    })
+                    .clicked()
+                {
+                    events.push(AppEvent::MigrateStorage {
+                        kind: self.backend,
+                        path,
+                    });
+                }
+            });
+
+            match &app_state.storage_status {
+                Some(Ok(message)) => {
+                    ui.colored_label(Color32::from_rgb(0x2C, 0xA0, 0x2C), message);
+                }
+                Some(Err(err)) => {
+                    ui.colored_label(Color32::from_rgb(0xD6, 0x27, 0x28), err);
+                }
+                None => {}
+            }
+        });
+
+        ui.add_space(10.0);
+        show_locale(ui, settings.locale, &mut events);
+
+        ui.add_space(10.0);
+        self.show_encryption(ui, app_state, &mut events);
+
+        // Neither a shared folder nor a local port is reachable from a browser
+        #[cfg(not(target_arch = "wasm32"))]
+        {
+            ui.add_space(10.0);
+            self.show_sync(ui, app_state, &mut events);
+
+            ui.add_space(10.0);
+            self.show_api(ui, app_state, &mut events);
+        }
+
+        #[cfg(feature = "sqlite")]
+        if settings.storage == BackendKind::Sqlite {
+            ui.add_space(10.0);
+            self.show_database_totals(ui, app_state);
+        }
+
+        events
+    }
+
+    fn show_encryption(
+        &mut self,
+        ui: &mut Ui,
+        app_state: &RustedBudgetAppState,
+        events: &mut Vec<AppEvent>,
+    ) {
+        ui.group(|ui| {
+            ui.label(RichText::new("Encryption").strong());
+            if app_state.settings.storage != BackendKind::File {
+                ui.label("Only budgets kept in a file can be encrypted.");
+                return;
+            }
+
+            let encrypted = app_state.key.is_some();
+            let password_field = |ui: &mut Ui, label: &str, text: &mut String| {
+                ui.label(label);
+                ui.add_sized([240.0, 20.0], TextEdit::singleline(text).password(true));
+                ui.end_row();
+            };
+            Grid::new("encryption_fields").show(ui, |ui| {
+                if encrypted {
+                    password_field(ui, "Current passphrase:", &mut self.current_passphrase);
+                }
+                password_field(ui, "New passphrase:", &mut self.new_passphrase);
+                password_field(ui, "Repeat new passphrase:", &mut self.confirm_passphrase);
+            });
+
+            let mismatch = self.new_passphrase != self.confirm_passphrase;
+            if mismatch && !self.confirm_passphrase.is_empty() {
+                ui.colored_label(
+                    Color32::from_rgb(0xD6, 0x27, 0x28),
+                    "The new passphrases differ",
+                );
+            }
+
+            let mut request = None;
+            ui.horizontal(|ui| {
+                let label = if encrypted {
+                    "Change passphrase"
+                } else {
+                    "Encrypt budget file"
+                };
+                let ready = !self.new_passphrase.is_empty()
+                    && !mismatch
+                    && (!encrypted || !self.current_passphrase.is_empty());
+                if ui.add_enabled(ready, Button::new(label)).clicked() {
+                    request = Some(Some(Passphrase::new(self.new_passphrase.clone())));
+                }
+                if encrypted
+                    && ui
+                        .add_enabled(
+                            !self.current_passphrase.is_empty(),
+                            Button::new("Remove encryption"),
+                        )
+                        .on_hover_text("Store the budget as plain JSON again")
+                        .clicked()
+                {
+                    request = Some(None);
+                }
+            });
+            if let Some(new) = request {
+                events.push(AppEvent::SetPassphrase {
+                    current: Passphrase::new(std::mem::take(&mut self.current_passphrase)),
+                    new,
+                });
+                self.new_passphrase.clear();
+                self.confirm_passphrase.clear();
+            }
+
+            if encrypted {
+                ui.separator();
+                ui.horizontal(|ui| {
+                    ui.label("Lock after");
+                    let mut minutes = app_state.settings.auto_lock_minutes;
+                    let response =
+                        ui.add(DragValue::new(&mut minutes).range(0..=240).suffix(" min"));
+                    if response.changed() {
+                        events.push(AppEvent::SetAutoLock(minutes));
+                    }
+                    ui.label("idle (0 = never)");
+                    if ui.button("Lock now").clicked() {
+                        events.push(AppEvent::Lock);
+                    }
+                });
+            }
+        });
+    }
+
+    #[cfg(not(target_arch = "wasm32"))]
+    fn show_sync(
+        &mut self,
+        ui: &mut Ui,
+        app_state: &RustedBudgetAppState,
+        events: &mut Vec<AppEvent>,
+    ) {
+        ui.group(|ui| {
+            ui.label(RichText::new("Sync").strong());
+            ui.label(format!("This device: {}", app_state.settings.device_id));
+
+            if let Some(sync) = &app_state.sync {
+                ui.label(format!(
+                    "Syncing through {} ({} devices)",
+                    sync.folder().display(),
+                    sync.device_count()
+                ));
+                if ui.button("Stop syncing").clicked() {
+                    events.push(AppEvent::StopSync);
+                }
+                return;
+            }
+
+            ui.label(
+                "Share this budget between devices through any folder they all see, \
+                 e.g. a Syncthing folder, a network share or a USB stick.",
+            );
+            ui.horizontal(|ui| {
+                ui.label("Folder:");
+                ui.add_sized(
+                    [420.0, 20.0],
+                    TextEdit::singleline(&mut self.sync_folder).hint_text("/path/to/shared/folder"),
+                );
+            });
+            let encrypted = app_state.key.is_some();
+            if encrypted {
+                ui.horizontal(|ui| {
+                    ui.label("Passphrase:");
+                    ui.add_sized(
+                        [200.0, 20.0],
+                        TextEdit::singleline(&mut self.sync_passphrase).password(true),
+                    )
+                    .on_hover_text("The changes in the folder are encrypted with it too");
+                });
+            }
+            ui.horizontal(|ui| {
+                let ready = !self.sync_folder.trim().is_empty()
+                    && (!encrypted || !self.sync_passphrase.is_empty());
+                if ui
+                    .add_enabled(ready, Button::new("Start syncing"))
+                    .on_hover_text(
+                        "An empty folder gets this budget; a folder already in use \
+                         replaces it with the shared one, which gets the items only \
+                         this budget has",
+                    )
+                    .clicked()
+                {
+                    let passphrase = std::mem::take(&mut self.sync_passphrase);
+                    events.push(AppEvent::StartSync {
+                        folder: PathBuf::from(self.sync_folder.trim()),
+                        passphrase: encrypted.then(|| Passphrase::new(passphrase)),
+                    });
+                }
+            });
+        });
+    }
+
+    #[cfg(not(target_arch = "wasm32"))]
+    fn show_api(
+        &mut self,
+        ui: &mut Ui,
+        app_state: &RustedBudgetAppState,
+        events: &mut Vec<AppEvent>,
+    ) {
+        let api = &app_state.settings.api;
+        ui.group(|ui| {
+            ui.label(RichText::new("Automation API").strong());
+            ui.label(
+                "Lets scripts, shortcuts and home automation read and change this budget \
+                 over HTTP while the app is open. Only programs on this computer can \
+                 connect, and they must send the token.",
+            );
+            ui.horizontal(|ui| {
+                let mut enabled = api.enabled;
+                if ui.checkbox(&mut enabled, "Serve the API on port").changed() {
+                    events.push(AppEvent::SetApi {
+                        enabled,
+                        port: api.port,
+                    });
+                }
+                let mut port = self.api_port.unwrap_or(api.port);
+                let response = ui.add(DragValue::new(&mut port).range(1024..=65535));
+                if response.changed() {
+                    self.api_port = Some(port);
+                }
+                if (response.lost_focus() || response.drag_stopped())
+                    && let Some(port) = self.api_port.take()
+                    && port != api.port
+                {
+                    events.push(AppEvent::SetApi {
+                        enabled: api.enabled,
+                        port,
+                    });
+                }
+            });
+
+            match app_state.api.as_ref().map(|api| api.as_ref().map(|s| (s.port, s.status()))) {
+                Some(Ok((port, Ok(true)))) => {
+                    ui.colored_label(
+                        Color32::from_rgb(0x2C, 0xA0, 0x2C),
+                        format!("Listening on http://127.0.0.1:{}/api", port),
+                    );
+                }
+                Some(Ok((port, Ok(false)))) => {
+                    ui.label(format!("Waiting for port {}…", port));
+                }
+                Some(Ok((_, Err(err)))) => {
+                    ui.colored_label(Color32::from_rgb(0xD6, 0x27, 0x28), err);
+                }
+                Some(Err(err)) => {
+                    ui.colored_label(Color32::from_rgb(0xD6, 0x27, 0x28), err);
+                }
+                None => {}
+            }
+            if api.enabled {
+                ui.horizontal(|ui| {
+                    ui.label("Token:");
+                    ui.monospace(&api.token);
+                    if ui.button("Copy").clicked() {
+                        ui.ctx().copy_text(api.token.clone());
+                    }
+                    if ui
+                        .button("New token")
+                        .on_hover_text("Scripts using the old token stop working")
+                        .clicked()
+                    {
+                        events.push(AppEvent::NewApiToken);
+                    }
+                });
+                ui.label(
+                    RichText::new(
+                        "Send it as \"Authorization: Bearer <token>\", e.g. \
+                         curl -H \"Authorization: Bearer <token>\" http://127.0.0.1:<port>/api/summary",
+                    )
+                    .small()
+                    .weak(),
+                );
+            }
+        });
+    }
+
+    /// Recorded totals computed by the database, as a check on what is stored.
+    #[cfg(feature = "sqlite")]
+    fn show_database_totals(&mut self, ui: &mut Ui, app_state: &RustedBudgetAppState) {
+        ui.group(|ui| {
+            ui.label(RichText::new("Database").strong());
+            ui.horizontal(|ui| {
+                ui.label("Period:");
+                period_picker(ui, "database_period", &mut self.totals_period);
+                if ui.button("Show totals").clicked() {
+                    self.totals = Some(sqlite::period_totals(
+                        app_state.settings.storage_path(),
+                        self.totals_period,
+                    ));
+                }
+            });
+
+            match &self.totals {
+                Some(Ok(totals)) => {
+                    ui.label(format!(
+                        "{} transactions: income ${:.2}, expenses ${:.2}",
+                        totals.transaction_count, totals.income, totals.expenses
+                    ));
+                    Grid::new("database_totals").striped(true).show(ui, |ui| {
+                        for (category, amount) in &totals.categories {
+                            ui.label(category.label());
+                            ui.label(format!("${:.2}", amount));
+                            ui.end_row();
+                        }
+                    });
+                }
+                Some(Err(err)) => {
+                    ui.colored_label(Color32::from_rgb(0xD6, 0x27, 0x28), err);
+                }
+                None => {}
+            }
+        });
+    }
+}
+
+/// How amounts are read in quick add and the cost tables, and shown there.
+fn show_locale(ui: &mut Ui, current: Locale, events: &mut Vec<AppEvent>) {
+    ui.group(|ui| {
+        ui.label(RichText::new("Amounts").strong());
+        ui.horizontal(|ui| {
+            ui.label("Number format:");
+            let mut locale = current;
+            ComboBox::from_id_salt("amount_locale")
+                .selected_text(locale.label())
+                .show_ui(ui, |ui| {
+                    for option in Locale::ALL {
+                        ui.selectable_value(&mut locale, option, option.label());
+                    }
+                });
+            if locale != current {
+                events.push(AppEvent::SetLocale(locale));
+            }
+        });
+        ui.label(
+            RichText::new(format!(
+                "e.g. {} or {}; kr, NOK and SEK are understood, and 1.2k is {}",
+                amount::format(1200.5, current),
+                amount::format(12.5, current),
+                amount::format(1200.0, current),
+            ))
+            .weak(),
+        );
+    });
+}
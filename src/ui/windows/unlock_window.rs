@@ -0,0 +1,80 @@
+use std::path::PathBuf;
+
+use eframe::egui::{self, *};
+
+use crate::{
+    AppEvent, RustedBudgetAppState,
+    storage::{BackendKind, crypto::Passphrase},
+};
+
+/// Shown instead of the main window while an encrypted budget is locked.
+pub struct UnlockWindow {
+    passphrase: String,
+    /// Another budget to open instead, for a forgotten passphrase.
+    other_path: String,
+}
+
+impl UnlockWindow {
+    pub fn new() -> Self {
+        Self {
+            passphrase: String::new(),
+            other_path: String::new(),
+        }
+    }
+
+    pub fn show(&mut self, ctx: &egui::Context, app_state: &RustedBudgetAppState) -> Vec<AppEvent> {
+        let mut events = Vec::new();
+
+        CentralPanel::default().show(ctx, |ui| {
+            ui.vertical_centered(|ui| {
+                ui.add_space(80.0);
+                ui.heading("🔒 Budget locked");
+                ui.label(app_state.settings.storage_path().display().to_string());
+                ui.add_space(10.0);
+
+                let field = ui.add_sized(
+                    [260.0, 20.0],
+                    TextEdit::singleline(&mut self.passphrase)
+                        .password(true)
+                        .hint_text("Passphrase"),
+                );
+                if ui.memory(|m| m.focused().is_none()) {
+                    field.request_focus();
+                }
+                let entered = field.lost_focus() && ui.input(|i| i.key_pressed(Key::Enter));
+
+                if (ui.button("Unlock").clicked() || entered) && !self.passphrase.is_empty() {
+                    // Key derivation takes a moment; clear the field right away
+                    let passphrase = std::mem::take(&mut self.passphrase);
+                    events.push(AppEvent::Unlock(Passphrase::new(passphrase)));
+                }
+
+                if let Some(Err(err)) = &app_state.storage_status {
+                    ui.colored_label(Color32::from_rgb(0xD6, 0x27, 0x28), err);
+                }
+
+                ui.add_space(20.0);
+                CollapsingHeader::new("Open another budget").show(ui, |ui| {
+                    ui.horizontal(|ui| {
+                        ui.add_sized(
+                            [320.0, 20.0],
+                            TextEdit::singleline(&mut self.other_path)
+                                .hint_text("/path/to/budget.json"),
+                        );
+                        if ui
+                            .add_enabled(!self.other_path.trim().is_empty(), Button::new("Open"))
+                            .clicked()
+                        {
+                            events.push(AppEvent::SwitchStorage {
+                                kind: BackendKind::File,
+                                path: PathBuf::from(self.other_path.trim()),
+                            });
+                        }
+                    });
+                });
+            });
+        });
+
+        events
+    }
+}
@@ -0,0 +1,84 @@
+//! Browser glue for the web build: the canvas the app draws on, files the
+//! user picks to import and downloads for exports.
+
+use eframe::egui;
+use wasm_bindgen::{JsCast, JsValue, closure::Closure};
+use web_sys::{Blob, Document, HtmlAnchorElement, HtmlCanvasElement, HtmlInputElement, Url};
+
+fn document() -> Result<Document, String> {
+    web_sys::window()
+        .and_then(|window| window.document())
+        .ok_or_else(|| "no document to draw in".to_string())
+}
+
+fn js_error(value: JsValue) -> String {
+    value.as_string().unwrap_or_else(|| format!("{:?}", value))
+}
+
+/// The canvas with `id` in index.html.
+pub fn canvas(id: &str) -> Result<HtmlCanvasElement, String> {
+    document()?
+        .get_element_by_id(id)
+        .and_then(|element| element.dyn_into().ok())
+        .ok_or_else(|| format!("the page has no canvas \"{}\"", id))
+}
+
+/// Ask the user for a file, then call `done` with its name and contents.
+/// `accept` filters the picker, e.g. ".csv".
+pub fn pick_file(
+    accept: &str,
+    ctx: &egui::Context,
+    done: impl FnOnce(String, Vec<u8>) + 'static,
+) -> Result<(), String> {
+    let input: HtmlInputElement = document()?
+        .create_element("input")
+        .map_err(js_error)?
+        .dyn_into()
+        .map_err(|_| "could not open a file picker".to_string())?;
+    input.set_type("file");
+    input.set_accept(accept);
+
+    let picked = input.clone();
+    let ctx = ctx.clone();
+    let on_change = Closure::once_into_js(move || {
+        let Some(file) = picked.files().and_then(|files| files.get(0)) else {
+            return;
+        };
+        wasm_bindgen_futures::spawn_local(async move {
+            if let Ok(buffer) = wasm_bindgen_futures::JsFuture::from(file.array_buffer()).await {
+                done(file.name(), js_sys::Uint8Array::new(&buffer).to_vec());
+                ctx.request_repaint();
+            }
+        });
+    });
+    input.set_onchange(Some(on_change.unchecked_ref()));
+    input.click();
+    Ok(())
+}
+
+/// Hand `contents` to the browser as a download called `name`.
+pub fn download(name: &str, contents: &[u8]) -> Result<(), String> {
+    let parts = js_sys::Array::of1(&js_sys::Uint8Array::from(contents));
+    let blob = Blob::new_with_u8_array_sequence(&parts).map_err(js_error)?;
+    let url = Url::create_object_url_with_blob(&blob).map_err(js_error)?;
+
+    let anchor: HtmlAnchorElement = document()?
+        .create_element("a")
+        .map_err(js_error)?
+        .dyn_into()
+        .map_err(|_| "could not start the download".to_string())?;
+    anchor.set_href(&url);
+    anchor.set_download(name);
+    anchor.click();
+
+    // Revoking the URL right away cancels the download in some browsers, so
+    // the blob is let go once the download has had time to start
+    let revoke = Closure::once_into_js(move || {
+        let _ = Url::revoke_object_url(&url);
+    });
+    web_sys::window()
+        .ok_or_else(|| "no window to download from".to_string())?
+        .set_timeout_with_callback_and_timeout_and_arguments_0(revoke.unchecked_ref(), 60_000)
+        .map_err(js_error)?;
+    Ok(())
+}